@@ -0,0 +1,238 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage:
+    danubec fmt [--check] <PATH>...
+    danubec check [--cfg NAME[=\"VALUE\"]]... <PATH>
+    danubec test [--list] [--cfg NAME[=\"VALUE\"]]... <PATH> [FILTER]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(String::as_str) {
+        Some("fmt") => fmt(&args[1..]),
+        Some("check") => check(&args[1..]),
+        Some("test") => test(&args[1..]),
+        _ => {
+            eprintln!("{}", USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn fmt(args: &[String]) -> ExitCode {
+    let mut check = false;
+    let mut paths = vec![];
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    }
+
+    let mut files = vec![];
+    for path in &paths {
+        if let Err(error) = sources(path, &mut files) {
+            eprintln!("{}: {}", path.display(), error);
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut code = ExitCode::SUCCESS;
+    for file in files {
+        let source = match std::fs::read_to_string(&file) {
+            Ok(source) => source,
+            Err(error) => {
+                eprintln!("{}: {}", file.display(), error);
+                code = ExitCode::FAILURE;
+                continue;
+            }
+        };
+        let formatted = match danubec_fmt::format(&source) {
+            Ok(formatted) => formatted,
+            Err(mut diagnostic) => {
+                let name = file.display().to_string();
+                diagnostic.with_source_code(0, miette::NamedSource::new(name, source.clone()));
+                eprintln!("{:?}", diagnostic);
+                code = ExitCode::FAILURE;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            println!("{}", file.display());
+            code = ExitCode::FAILURE;
+        } else if let Err(error) = std::fs::write(&file, formatted) {
+            eprintln!("{}: {}", file.display(), error);
+            code = ExitCode::FAILURE;
+        }
+    }
+
+    code
+}
+
+fn check(args: &[String]) -> ExitCode {
+    let Some((cfg, positionals)) = semantic_arguments(args) else {
+        return ExitCode::FAILURE;
+    };
+    let [root] = &positionals[..] else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let mut db = danubec_semantic::Database::new();
+    let context = danubec_semantic::Context {
+        root: PathBuf::from(root),
+        cfg,
+        std: Some(db.std()),
+    };
+    let (_, _, diagnostic) = danubec_semantic::semantic(&mut db, context);
+
+    report(&diagnostic)
+}
+
+fn test(args: &[String]) -> ExitCode {
+    let (list, args): (Vec<_>, Vec<_>) = args.iter().cloned().partition(|arg| arg == "--list");
+    let Some((cfg, positionals)) = semantic_arguments(&args) else {
+        return ExitCode::FAILURE;
+    };
+    let (root, filter) = match &positionals[..] {
+        [root] => (root, ""),
+        [root, filter] => (root, filter.as_str()),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut db = danubec_semantic::Database::new();
+    let context = danubec_semantic::Context {
+        root: PathBuf::from(root),
+        cfg: cfg.flag("test"),
+        std: Some(db.std()),
+    };
+    let (env, symbols, mut diagnostic) = danubec_semantic::semantic(&mut db, context);
+    let tests = danubec_semantic::tests(&env, &symbols, filter, &mut diagnostic);
+    if report(&diagnostic) == ExitCode::FAILURE {
+        return ExitCode::FAILURE;
+    }
+
+    if !list.is_empty() {
+        for test in &tests {
+            println!("{}: test", test.path);
+        }
+        match tests.len() {
+            1 => println!("\n1 test"),
+            count => println!("\n{} tests", count),
+        }
+
+        return ExitCode::SUCCESS;
+    }
+
+    match tests.len() {
+        1 => println!("\nrunning 1 test"),
+        count => println!("\nrunning {} tests", count),
+    }
+    let mut failures = vec![];
+    for test in &tests {
+        let (outcome, output) = danubec_semantic::run(&env, &symbols, test);
+        match outcome {
+            danubec_semantic::Outcome::Passed => println!("test {} ... ok", test.path),
+            danubec_semantic::Outcome::Failed(reason) => {
+                println!("test {} ... FAILED", test.path);
+                failures.push((test, reason, output));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (test, reason, output) in &failures {
+            println!("\n---- {} ----\n{}{}", test.path, output, reason);
+        }
+        println!("\nfailures:");
+        for (test, _, _) in &failures {
+            println!("    {}", test.path);
+        }
+    }
+    let result = if failures.is_empty() { "ok" } else { "FAILED" };
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        result,
+        tests.len() - failures.len(),
+        failures.len()
+    );
+
+    match failures.is_empty() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+/// Splits `--cfg` options from positional arguments.
+fn semantic_arguments(args: &[String]) -> Option<(danubec_semantic::CfgSet, Vec<&String>)> {
+    let mut cfg = danubec_semantic::CfgSet::new();
+    let mut positionals = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--cfg" => {
+                let Some(option) = args.next() else {
+                    eprintln!("{}", USAGE);
+                    return None;
+                };
+                if let Err(error) = cfg.parse(option) {
+                    eprintln!("--cfg: {}", error);
+                    return None;
+                }
+            }
+            _ => positionals.push(arg),
+        }
+    }
+
+    Some((cfg, positionals))
+}
+
+/// Prints every report, failing on anything but warnings.
+fn report(diagnostic: &danubec_diagnostic::Diagnostic) -> ExitCode {
+    let mut code = ExitCode::SUCCESS;
+    for report in diagnostic.iter() {
+        eprintln!("{:?}", report);
+        if report.severity() != Some(miette::Severity::Warning) {
+            code = ExitCode::FAILURE;
+        }
+    }
+
+    code
+}
+
+fn sources(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir()
+            || entry
+                .extension()
+                .is_some_and(|extension| extension == "dnb")
+        {
+            sources(&entry, files)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,313 @@
+//! In-place editing of syntax trees.
+//!
+//! These functions edit trees made mutable with
+//! [`SyntaxNode::clone_for_update`], and panic on immutable ones. The nodes
+//! they insert are typically built with [`crate::make`], and are indented to
+//! match the code around them.
+
+use crate::{
+    BlockExpression, DefinitionKind, FunctionDefinition, Root, Statement, UseDefinition,
+    make::{self, INDENT},
+};
+use danubec_syntax::{
+    Danube, GreenNode, GreenToken, NodeOrToken, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken,
+};
+use rowan::ast::AstNode;
+use std::ops::Range;
+
+/// Replaces `old` with `new`, keeping the trivia after `old`.
+///
+/// # Panics
+///
+/// Panics if `old` is the root of its tree.
+pub fn replace(old: &impl AstNode<Language = Danube>, new: &impl AstNode<Language = Danube>) {
+    let old = old.syntax();
+    let parent = old.parent().expect("the replaced node has a parent");
+    let new = make::indent(&new.syntax().green(), &indentation(old));
+    let trivia = trailing_trivia(old);
+    let new = make::with_trivia(&new, trivia.iter().map(|token| token.green().to_owned()));
+
+    let index = old.index();
+    parent.splice_children(index..index + 1, vec![element(new)]);
+}
+
+impl FunctionDefinition {
+    /// Appends `parameter` to the parameters of this function, on a line of
+    /// its own if the trivia after the last parameter breaks the line.
+    pub fn add_parameter(&self, parameter: crate::FunctionParameter) {
+        let parameter = parameter.syntax().green().into_owned();
+        let node = self.syntax();
+        let Some(last) = self.parameters().last() else {
+            let left_paren = self.left_paren().expect("a function has parameters");
+            let index = left_paren.index() + 1;
+            node.splice_children(index..index, vec![element(parameter)]);
+            return;
+        };
+
+        let comma = next_non_trivia_sibling(last.syntax())
+            .filter(|element| element.kind() == SyntaxKind::COMMA);
+        let trivia = match &comma {
+            Some(comma) => std::iter::successors(comma.next_sibling_or_token(), |element| {
+                element.next_sibling_or_token()
+            })
+            .map_while(|element| {
+                element
+                    .into_token()
+                    .filter(|token| token.kind().at_trivia())
+            })
+            .collect(),
+            None => trailing_trivia(last.syntax()),
+        };
+        // Comments before the last line break stay with the last parameter.
+        let (before, after) = match trivia
+            .iter()
+            .rposition(|token| token.kind() == SyntaxKind::NEW_LINE)
+        {
+            Some(position) => trivia.split_at(position + 1),
+            None => trivia.split_at(0),
+        };
+
+        let mut elements = vec![];
+        if comma.is_none() {
+            elements.push(token(SyntaxKind::COMMA, ","));
+        }
+        let mut trailing: Vec<_> = after
+            .iter()
+            .map(|token| (token.kind(), token.text()))
+            .collect();
+        let parameter = if before.is_empty() {
+            elements.push(token(SyntaxKind::WHITESPACE, " "));
+            parameter
+        } else {
+            let indentation = if starts_line(last.syntax()) {
+                indentation(last.syntax())
+            } else {
+                format!("{}{INDENT}", indentation(node))
+            };
+            elements.extend(
+                before
+                    .iter()
+                    .map(|trivia| token(trivia.kind(), trivia.text())),
+            );
+            if !indentation.is_empty() {
+                elements.push(token(SyntaxKind::WHITESPACE, &indentation));
+            }
+            trailing.insert(0, (SyntaxKind::NEW_LINE, "\n"));
+            make::indent(&parameter, &indentation)
+        };
+        // Trivia follows the trailing comma, or else goes inside the new
+        // parameter, as the parser puts it.
+        match comma {
+            Some(_) => {
+                elements.push(element(parameter));
+                elements.push(token(SyntaxKind::COMMA, ","));
+                elements.extend(trailing.iter().map(|&(kind, text)| token(kind, text)));
+            }
+            None => {
+                let trailing = trailing
+                    .iter()
+                    .map(|&(kind, text)| GreenToken::new(kind.into(), text));
+                elements.push(element(make::with_trivia(&parameter, trailing)));
+            }
+        }
+
+        if let (Some(first), Some(last)) = (trivia.first(), trivia.last()) {
+            let parent = first.parent().unwrap();
+            splice(&parent, first.index()..last.index() + 1, vec![]);
+        }
+        let index = match comma {
+            Some(comma) => comma.index() + 1,
+            None => last.syntax().index() + 1,
+        };
+        node.splice_children(index..index, elements);
+    }
+}
+
+impl BlockExpression {
+    /// Appends `statement` to the statements of this block, on a line of its
+    /// own unless the block is on a single line.
+    pub fn add_statement(&self, statement: Statement) {
+        let node = self.syntax();
+        let base = indentation(node);
+        let inner = format!("{base}{INDENT}");
+        let statement = make::indent(&statement.syntax().green(), &inner);
+
+        match self.statements().last() {
+            Some(last) => {
+                let multiline = trailing_trivia(last.syntax())
+                    .iter()
+                    .any(|token| token.kind() == SyntaxKind::NEW_LINE);
+                let separator = if multiline {
+                    vec![
+                        (SyntaxKind::NEW_LINE, "\n"),
+                        (SyntaxKind::WHITESPACE, &*inner),
+                    ]
+                } else {
+                    vec![(SyntaxKind::WHITESPACE, " ")]
+                };
+                insert_after(last.syntax(), statement, &separator);
+            }
+            None => {
+                let left_brace = self.left_brace().expect("a block has braces");
+                let right_brace = self.right_brace().expect("a block has braces");
+                let mut elements = vec![
+                    token(SyntaxKind::NEW_LINE, "\n"),
+                    token(SyntaxKind::WHITESPACE, &inner),
+                ];
+                let mut trivia = vec![GreenToken::new(SyntaxKind::NEW_LINE.into(), "\n")];
+                if !base.is_empty() {
+                    trivia.push(GreenToken::new(SyntaxKind::WHITESPACE.into(), &base));
+                }
+                elements.push(element(make::with_trivia(&statement, trivia)));
+
+                splice(node, left_brace.index() + 1..right_brace.index(), elements);
+            }
+        }
+    }
+}
+
+impl Root {
+    /// Adds `definition` after the last `use` definition of this file, or
+    /// before its first definition if there is none.
+    pub fn add_use(&self, definition: UseDefinition) {
+        let definition = make::definition([], None, definition);
+        let definition = definition.syntax().green().into_owned();
+
+        let last_use = self
+            .definitions()
+            .filter(|definition| matches!(definition.kind(), Some(DefinitionKind::Use(_))))
+            .last();
+        if let Some(last_use) = last_use {
+            insert_after(
+                last_use.syntax(),
+                definition,
+                &[(SyntaxKind::NEW_LINE, "\n")],
+            );
+            return;
+        }
+
+        let node = self.syntax();
+        let index = match self.attributes().last() {
+            Some(attribute) => attribute.syntax().index() + 1,
+            None => self
+                .definitions()
+                .next()
+                .map_or(node.children_with_tokens().count(), |first| {
+                    first.syntax().index()
+                }),
+        };
+        let mut trivia = vec![GreenToken::new(SyntaxKind::NEW_LINE.into(), "\n")];
+        if self.definitions().next().is_some() {
+            trivia.push(GreenToken::new(SyntaxKind::NEW_LINE.into(), "\n"));
+        }
+        let definition = make::with_trivia(&definition, trivia);
+
+        node.splice_children(index..index, vec![element(definition)]);
+    }
+}
+
+/// Inserts `new` after `node`, moving the trivia after `node` to after `new`,
+/// and separating the two by `separator`.
+fn insert_after(node: &SyntaxNode, new: GreenNode, separator: &[(SyntaxKind, &str)]) {
+    let trivia = trailing_trivia(node);
+    let new = make::with_trivia(&new, trivia.iter().map(|token| token.green().to_owned()));
+
+    let separator: Vec<_> = separator
+        .iter()
+        .map(|&(kind, text)| token(kind, text))
+        .collect();
+    match (trivia.first(), trivia.last()) {
+        (Some(first), Some(last)) => {
+            let parent = first.parent().unwrap();
+            splice(&parent, first.index()..last.index() + 1, separator);
+        }
+        _ => {
+            let last = node.last_token().expect("an inserted node follows a token");
+            let parent = last.parent().unwrap();
+            parent.splice_children(last.index() + 1..last.index() + 1, separator);
+        }
+    }
+
+    let parent = node.parent().expect("an inserted node has a parent");
+    let index = node.index() + 1;
+    parent.splice_children(index..index, vec![element(new)]);
+}
+
+/// The trivia at the end of `node`.
+fn trailing_trivia(node: &SyntaxNode) -> Vec<SyntaxToken> {
+    let start = node.text_range().start();
+    let mut trivia = vec![];
+    let mut token = node.last_token();
+    while let Some(current) = token {
+        if !current.kind().at_trivia() || current.text_range().start() < start {
+            break;
+        }
+        token = current.prev_token();
+        trivia.push(current);
+    }
+    trivia.reverse();
+
+    trivia
+}
+
+/// The whitespace at the start of the line `node` starts on.
+fn indentation(node: &SyntaxNode) -> String {
+    let mut indentation = String::new();
+    let mut token = node.first_token().and_then(|token| token.prev_token());
+    while let Some(current) = token {
+        if current.kind() == SyntaxKind::NEW_LINE {
+            break;
+        }
+        indentation = match current.kind() {
+            SyntaxKind::WHITESPACE => current.text().to_owned(),
+            _ => String::new(),
+        };
+        token = current.prev_token();
+    }
+
+    indentation
+}
+
+/// Whether only whitespace comes before `node` on its line.
+fn starts_line(node: &SyntaxNode) -> bool {
+    let mut token = node.first_token().and_then(|token| token.prev_token());
+    if let Some(SyntaxKind::WHITESPACE) = token.as_ref().map(SyntaxToken::kind) {
+        token = token.and_then(|token| token.prev_token());
+    }
+
+    token.is_none_or(|token| token.kind() == SyntaxKind::NEW_LINE)
+}
+
+fn next_non_trivia_sibling(node: &SyntaxNode) -> Option<SyntaxElement> {
+    std::iter::successors(node.next_sibling_or_token(), |element| {
+        element.next_sibling_or_token()
+    })
+    .find(|element| !element.kind().at_trivia())
+}
+
+/// Replaces the children of `parent` in `range` with `elements`.
+///
+/// `splice_children` detaches children while iterating over them, and skips
+/// every other one when removing more than one.
+fn splice(parent: &SyntaxNode, range: Range<usize>, elements: Vec<SyntaxElement>) {
+    for _ in range.clone() {
+        if let Some(child) = parent.children_with_tokens().nth(range.start) {
+            child.detach();
+        }
+    }
+    parent.splice_children(range.start..range.start, elements);
+}
+
+fn element(node: GreenNode) -> SyntaxElement {
+    SyntaxNode::new_root_mut(node).into()
+}
+
+/// A token that can be inserted into a mutable tree.
+fn token(kind: SyntaxKind, text: &str) -> SyntaxElement {
+    let token = GreenToken::new(kind.into(), text);
+    let node = GreenNode::new(SyntaxKind::ROOT_NODE.into(), [NodeOrToken::Token(token)]);
+    let token = SyntaxNode::new_root_mut(node).first_token().unwrap();
+    token.detach();
+
+    token.into()
+}
@@ -0,0 +1,1836 @@
+//! Constructors for syntax nodes.
+//!
+//! Each node kind has a constructor named after its rule in `danube.ungram`.
+//! Nodes are laid out with single spaces, and with one item per line in
+//! braces, indented by four spaces. Trivia follows the token before it, as in
+//! the trees built by the parser.
+//!
+//! Arguments of enum types take anything that converts into the enum, like a
+//! [`PathExpression`] for an [`Expression`].
+
+use crate::*;
+use danubec_syntax::{GreenNode, GreenToken, NodeOrToken, SyntaxKind, SyntaxKind::*};
+use rowan::{GreenNodeData, ast::AstNode};
+
+/// The indentation of one level of nesting.
+pub const INDENT: &str = "    ";
+
+type Element = NodeOrToken<GreenNode, GreenToken>;
+
+/// The children of a node under construction.
+#[derive(Default)]
+struct Builder {
+    children: Vec<Element>,
+}
+
+impl Builder {
+    fn token(self, kind: SyntaxKind) -> Self {
+        self.text(kind, text(kind))
+    }
+
+    fn text(mut self, kind: SyntaxKind, text: &str) -> Self {
+        self.children
+            .push(NodeOrToken::Token(GreenToken::new(kind.into(), text)));
+        self
+    }
+
+    /// An infix operator, which is a node of its kind holding its tokens.
+    fn operator(mut self, kind: SyntaxKind) -> Self {
+        let tokens = operator_tokens(kind)
+            .into_iter()
+            .map(|kind| NodeOrToken::Token(GreenToken::new(kind.into(), text(kind))));
+        self.children
+            .push(NodeOrToken::Node(GreenNode::new(kind.into(), tokens)));
+        self
+    }
+
+    fn node(mut self, node: &impl AstNode<Language = Danube>) -> Self {
+        self.children
+            .push(NodeOrToken::Node(node.syntax().green().into_owned()));
+        self
+    }
+
+    fn optional<T>(self, value: Option<T>, f: impl FnOnce(Self, T) -> Self) -> Self {
+        match value {
+            Some(value) => f(self, value),
+            None => self,
+        }
+    }
+
+    fn nodes<N>(self, nodes: impl IntoIterator<Item = N>) -> Self
+    where
+        N: AstNode<Language = Danube>,
+    {
+        nodes
+            .into_iter()
+            .fold(self, |builder, node| builder.node(&node))
+    }
+
+    /// `nodes` separated by `, `.
+    fn list<N>(mut self, nodes: impl IntoIterator<Item = N>) -> Self
+    where
+        N: AstNode<Language = Danube>,
+    {
+        for (index, node) in nodes.into_iter().enumerate() {
+            if index > 0 {
+                self = self.token(COMMA).space();
+            }
+            self = self.node(&node);
+        }
+        self
+    }
+
+    /// `nodes` on indented lines of their own, each followed by `separator`.
+    fn lines<N>(mut self, nodes: impl IntoIterator<Item = N>, separator: Option<SyntaxKind>) -> Self
+    where
+        N: AstNode<Language = Danube>,
+    {
+        let mut empty = true;
+        for node in nodes {
+            empty = false;
+            self = self.new_line().trivia(WHITESPACE, INDENT);
+            self.children
+                .push(NodeOrToken::Node(indent(&node.syntax().green(), INDENT)));
+            if let Some(separator) = separator {
+                self = self.token(separator);
+            }
+        }
+        if !empty {
+            self = self.new_line();
+        }
+        self
+    }
+
+    /// Attributes on lines of their own, or followed by a space.
+    fn attributes(
+        mut self,
+        attributes: impl IntoIterator<Item = Attribute>,
+        new_line: bool,
+    ) -> Self {
+        for attribute in attributes {
+            self = self.node(&attribute);
+            self = if new_line {
+                self.new_line()
+            } else {
+                self.space()
+            };
+        }
+        self
+    }
+
+    fn space(self) -> Self {
+        self.trivia(WHITESPACE, " ")
+    }
+
+    fn new_line(self) -> Self {
+        self.trivia(NEW_LINE, "\n")
+    }
+
+    fn trivia(mut self, kind: SyntaxKind, text: &str) -> Self {
+        push_trivia(&mut self.children, GreenToken::new(kind.into(), text));
+        self
+    }
+
+    fn finish<N>(self, kind: SyntaxKind) -> N
+    where
+        N: AstNode<Language = Danube>,
+    {
+        let node = SyntaxNode::new_root(GreenNode::new(kind.into(), self.children));
+
+        N::cast(node).unwrap()
+    }
+}
+
+/// Appends `trivia` to the last token in `children`, inside the innermost node
+/// holding it.
+fn push_trivia(children: &mut Vec<Element>, trivia: GreenToken) {
+    match children.last_mut() {
+        Some(NodeOrToken::Node(node)) => {
+            *node = with_trivia(node, [trivia]);
+        }
+        _ => children.push(NodeOrToken::Token(trivia)),
+    }
+}
+
+/// `node` followed by `trivia`.
+pub(crate) fn with_trivia(
+    node: &GreenNodeData,
+    trivia: impl IntoIterator<Item = GreenToken>,
+) -> GreenNode {
+    let mut children: Vec<_> = node.children().map(|child| child.to_owned()).collect();
+    for trivia in trivia {
+        push_trivia(&mut children, trivia);
+    }
+
+    GreenNode::new(node.kind(), children)
+}
+
+/// Prefixes the lines of `node` after the first one with `indentation`.
+pub(crate) fn indent(node: &GreenNodeData, indentation: &str) -> GreenNode {
+    let mut children = vec![];
+    let mut iter = node.children().peekable();
+    while let Some(child) = iter.next() {
+        let token = match child {
+            NodeOrToken::Node(node) => {
+                children.push(NodeOrToken::Node(indent(node, indentation)));
+                continue;
+            }
+            NodeOrToken::Token(token) => token,
+        };
+        children.push(NodeOrToken::Token(token.to_owned()));
+        if token.kind() != NEW_LINE.into() || indentation.is_empty() {
+            continue;
+        }
+
+        let text = match iter.peek() {
+            Some(NodeOrToken::Token(next)) if next.kind() == NEW_LINE.into() => continue,
+            Some(NodeOrToken::Token(next)) if next.kind() == WHITESPACE.into() => {
+                let text = format!("{indentation}{}", next.text());
+                iter.next();
+                text
+            }
+            _ => indentation.to_owned(),
+        };
+        children.push(NodeOrToken::Token(GreenToken::new(
+            WHITESPACE.into(),
+            &text,
+        )));
+    }
+
+    GreenNode::new(node.kind(), children)
+}
+
+/// The text of a token kind that is always spelled the same.
+fn text(kind: SyntaxKind) -> &'static str {
+    match kind {
+        AMPERSAND => "&",
+        ASTERISK => "*",
+        AT => "@",
+        CARET => "^",
+        COLON => ":",
+        COMMA => ",",
+        DOT => ".",
+        EQUAL => "=",
+        EXCLAMATION => "!",
+        HASH => "#",
+        HYPHEN => "-",
+        LEFT_BRACE => "{",
+        LEFT_BRACKET => "[",
+        LEFT_CHEVRON => "<",
+        LEFT_PAREN => "(",
+        PERCENT => "%",
+        PIPE => "|",
+        PLUS => "+",
+        QUESTION => "?",
+        RIGHT_BRACE => "}",
+        RIGHT_BRACKET => "]",
+        RIGHT_CHEVRON => ">",
+        RIGHT_PAREN => ")",
+        SEMICOLON => ";",
+        SLASH => "/",
+        TILDE => "~",
+
+        AS => "as",
+        AWAIT => "await",
+        BREAK => "break",
+        CONST => "const",
+        CONTINUE => "continue",
+        CRATE => "crate",
+        ELSE => "else",
+        ENUM => "enum",
+        FALSE => "false",
+        FN => "fn",
+        FOR => "for",
+        IF => "if",
+        IMPL => "impl",
+        IN => "in",
+        LET => "let",
+        LOOP => "loop",
+        MATCH => "match",
+        MOD => "mod",
+        MUT => "mut",
+        PLACEHOLDER => "_",
+        PUB => "pub",
+        RETURN => "return",
+        SELF => "self",
+        STATIC => "static",
+        STRUCT => "struct",
+        SUPER => "super",
+        TRAIT => "trait",
+        TRUE => "true",
+        TYPE => "type",
+        USE => "use",
+        WHILE => "while",
+        YIELD => "yield",
+
+        RAW_IDENTIFIER_START => "r#",
+        CHARACTER_START | CHARACTER_END => "'",
+        BYTE_START => "b'",
+        STRING_START | STRING_END => "\"",
+        BYTE_STRING_START => "b\"",
+        C_STRING_START => "c\"",
+        ESCAPE_START => "\\",
+        UNICODE_START => "\\u{",
+        UNICODE_END => "}",
+        INTERPOLATION_START => "${",
+        INTERPOLATION_END => "}",
+        BINARY_START => "0b",
+        OCTAL_START => "0o",
+        HEX_START => "0x",
+        FRACTION_START => ".",
+        EXPONENT_START => "e",
+        EXPONENT_SIGN => "-",
+
+        _ => unreachable!("`{kind:?}` has no fixed text"),
+    }
+}
+
+/// The tokens of an operator: `+=` is `+` and `=`.
+fn operator_tokens(kind: SyntaxKind) -> Vec<SyntaxKind> {
+    match kind {
+        PLUS__PIPE => vec![PLUS, PIPE],
+        PLUS__PERCENT => vec![PLUS, PERCENT],
+        HYPHEN__PIPE => vec![HYPHEN, PIPE],
+        HYPHEN__PERCENT => vec![HYPHEN, PERCENT],
+        ASTERISK__PIPE => vec![ASTERISK, PIPE],
+        ASTERISK__PERCENT => vec![ASTERISK, PERCENT],
+        ASTERISK__ASTERISK => vec![ASTERISK, ASTERISK],
+        ASTERISK__ASTERISK__PIPE => vec![ASTERISK, ASTERISK, PIPE],
+        ASTERISK__ASTERISK__PERCENT => vec![ASTERISK, ASTERISK, PERCENT],
+        AMPERSAND__AMPERSAND => vec![AMPERSAND, AMPERSAND],
+        PIPE__PIPE => vec![PIPE, PIPE],
+        EQUAL__EQUAL => vec![EQUAL, EQUAL],
+        EXCLAMATION__EQUAL => vec![EXCLAMATION, EQUAL],
+        LEFT_CHEVRON__EQUAL => vec![LEFT_CHEVRON, EQUAL],
+        RIGHT_CHEVRON__EQUAL => vec![RIGHT_CHEVRON, EQUAL],
+        LEFT_CHEVRON__LEFT_CHEVRON => vec![LEFT_CHEVRON, LEFT_CHEVRON],
+        LEFT_CHEVRON__LEFT_CHEVRON__PIPE => vec![LEFT_CHEVRON, LEFT_CHEVRON, PIPE],
+        RIGHT_CHEVRON__RIGHT_CHEVRON => vec![RIGHT_CHEVRON, RIGHT_CHEVRON],
+        RIGHT_CHEVRON__RIGHT_CHEVRON__RIGHT_CHEVRON => {
+            vec![RIGHT_CHEVRON, RIGHT_CHEVRON, RIGHT_CHEVRON]
+        }
+
+        PLUS__EQUAL => vec![PLUS, EQUAL],
+        PLUS__PIPE__EQUAL => vec![PLUS, PIPE, EQUAL],
+        PLUS__PERCENT__EQUAL => vec![PLUS, PERCENT, EQUAL],
+        HYPHEN__EQUAL => vec![HYPHEN, EQUAL],
+        HYPHEN__PIPE__EQUAL => vec![HYPHEN, PIPE, EQUAL],
+        HYPHEN__PERCENT__EQUAL => vec![HYPHEN, PERCENT, EQUAL],
+        ASTERISK__EQUAL => vec![ASTERISK, EQUAL],
+        ASTERISK__PIPE__EQUAL => vec![ASTERISK, PIPE, EQUAL],
+        ASTERISK__PERCENT__EQUAL => vec![ASTERISK, PERCENT, EQUAL],
+        ASTERISK__ASTERISK__EQUAL => vec![ASTERISK, ASTERISK, EQUAL],
+        ASTERISK__ASTERISK__PIPE__EQUAL => vec![ASTERISK, ASTERISK, PIPE, EQUAL],
+        ASTERISK__ASTERISK__PERCENT__EQUAL => vec![ASTERISK, ASTERISK, PERCENT, EQUAL],
+        SLASH__EQUAL => vec![SLASH, EQUAL],
+        PERCENT__EQUAL => vec![PERCENT, EQUAL],
+        CARET__EQUAL => vec![CARET, EQUAL],
+        AMPERSAND__EQUAL => vec![AMPERSAND, EQUAL],
+        AMPERSAND__AMPERSAND__EQUAL => vec![AMPERSAND, AMPERSAND, EQUAL],
+        PIPE__EQUAL => vec![PIPE, EQUAL],
+        PIPE__PIPE__EQUAL => vec![PIPE, PIPE, EQUAL],
+        LEFT_CHEVRON__LEFT_CHEVRON__EQUAL => vec![LEFT_CHEVRON, LEFT_CHEVRON, EQUAL],
+        LEFT_CHEVRON__LEFT_CHEVRON__PIPE__EQUAL => {
+            vec![LEFT_CHEVRON, LEFT_CHEVRON, PIPE, EQUAL]
+        }
+        RIGHT_CHEVRON__RIGHT_CHEVRON__EQUAL => vec![RIGHT_CHEVRON, RIGHT_CHEVRON, EQUAL],
+        RIGHT_CHEVRON__RIGHT_CHEVRON__RIGHT_CHEVRON__EQUAL => {
+            vec![RIGHT_CHEVRON, RIGHT_CHEVRON, RIGHT_CHEVRON, EQUAL]
+        }
+
+        _ => vec![kind],
+    }
+}
+
+/// `#![argument]` and definitions, one per line.
+pub fn root(
+    attributes: impl IntoIterator<Item = TopLevelAttribute>,
+    definitions: impl IntoIterator<Item = Definition>,
+) -> Root {
+    let mut builder = Builder::default();
+    for attribute in attributes {
+        builder = builder.node(&attribute).new_line();
+    }
+    for definition in definitions {
+        if !builder.children.is_empty() {
+            builder = builder.new_line();
+        }
+        builder = builder.node(&definition).new_line();
+    }
+
+    builder.finish(ROOT_NODE)
+}
+
+/// `#![argument]`
+pub fn top_level_attribute(argument: impl Into<AttributeArgument>) -> TopLevelAttribute {
+    Builder::default()
+        .token(HASH)
+        .token(EXCLAMATION)
+        .token(LEFT_BRACKET)
+        .node(&argument.into())
+        .token(RIGHT_BRACKET)
+        .finish(TOP_LEVEL_ATTRIBUTE_NODE)
+}
+
+/// `#[argument]`
+pub fn attribute(argument: impl Into<AttributeArgument>) -> Attribute {
+    Builder::default()
+        .token(HASH)
+        .token(LEFT_BRACKET)
+        .node(&argument.into())
+        .token(RIGHT_BRACKET)
+        .finish(ATTRIBUTE_NODE)
+}
+
+/// `value`
+pub fn expression_attribute_argument(value: impl Into<Expression>) -> ExpressionAttributeArgument {
+    Builder::default()
+        .node(&value.into())
+        .finish(EXPRESSION_ATTRIBUTE_ARGUMENT_NODE)
+}
+
+/// `key`, `key = value`
+pub fn key_value_attribute_argument(
+    key: Path,
+    value: Option<Expression>,
+) -> KeyValueAttributeArgument {
+    Builder::default()
+        .node(&key)
+        .optional(value, |builder, value| {
+            builder.space().token(EQUAL).space().node(&value)
+        })
+        .finish(KEY_VALUE_ATTRIBUTE_ARGUMENT_NODE)
+}
+
+/// `path(arguments)`
+pub fn nested_attribute_argument(
+    path: Path,
+    arguments: impl IntoIterator<Item = AttributeArgument>,
+) -> NestedAttributeArgument {
+    Builder::default()
+        .node(&path)
+        .token(LEFT_PAREN)
+        .list(arguments)
+        .token(RIGHT_PAREN)
+        .finish(NESTED_ATTRIBUTE_ARGUMENT_NODE)
+}
+
+/// Attributes on lines of their own, then `visibility kind`.
+pub fn definition(
+    attributes: impl IntoIterator<Item = Attribute>,
+    visibility: Option<Visibility>,
+    kind: impl Into<DefinitionKind>,
+) -> Definition {
+    Builder::default()
+        .attributes(attributes, true)
+        .optional(visibility, |builder, visibility| {
+            builder.node(&visibility).space()
+        })
+        .node(&kind.into())
+        .finish(DEFINITION_NODE)
+}
+
+/// `fn name(parameters) -> return_type body`
+pub fn function_definition(
+    name: Identifier,
+    parameters: impl IntoIterator<Item = FunctionParameter>,
+    return_type: Option<TypeExpression>,
+    body: impl Into<FunctionBodyKind>,
+) -> FunctionDefinition {
+    let body = body.into();
+
+    Builder::default()
+        .token(FN)
+        .space()
+        .node(&name)
+        .token(LEFT_PAREN)
+        .list(parameters)
+        .token(RIGHT_PAREN)
+        .optional(return_type, |builder, return_type| {
+            builder
+                .space()
+                .token(HYPHEN)
+                .token(RIGHT_CHEVRON)
+                .space()
+                .node(&return_type)
+        })
+        .optional(
+            matches!(body, FunctionBodyKind::Block(_)).then_some(()),
+            |builder, ()| builder.space(),
+        )
+        .node(&body)
+        .finish(FUNCTION_DEFINITION_NODE)
+}
+
+/// `{ statements }` as the body of a function.
+pub fn function_body_block(body: BlockExpression) -> FunctionBodyBlock {
+    Builder::default()
+        .node(&body)
+        .finish(FUNCTION_BODY_BLOCK_NODE)
+}
+
+/// `;` as the body of a function.
+pub fn function_body_unit() -> FunctionBodyUnit {
+    Builder::default()
+        .token(SEMICOLON)
+        .finish(FUNCTION_BODY_UNIT_NODE)
+}
+
+/// Attributes followed by spaces, then `pattern: type`.
+pub fn function_parameter(
+    attributes: impl IntoIterator<Item = Attribute>,
+    pattern: impl Into<Pattern>,
+    r#type: impl Into<TypeExpression>,
+) -> FunctionParameter {
+    Builder::default()
+        .attributes(attributes, false)
+        .node(&pattern.into())
+        .token(COLON)
+        .space()
+        .node(&r#type.into())
+        .finish(FUNCTION_PARAMETER_NODE)
+}
+
+/// `struct name;`, `struct name { fields }`, `struct name(fields);`
+pub fn struct_definition(name: Identifier, body: impl Into<StructBody>) -> StructDefinition {
+    let body = body.into();
+
+    Builder::default()
+        .token(STRUCT)
+        .space()
+        .node(&name)
+        .optional(
+            matches!(body, StructBody::Named(_)).then_some(()),
+            |builder, ()| builder.space(),
+        )
+        .node(&body)
+        .finish(STRUCT_DEFINITION_NODE)
+}
+
+/// `;` as the body of a struct.
+pub fn struct_body_unit() -> StructBodyUnit {
+    Builder::default()
+        .token(SEMICOLON)
+        .finish(STRUCT_BODY_UNIT_NODE)
+}
+
+/// `{ fields }` as the body of a struct, one field per line.
+pub fn struct_named(fields: impl IntoIterator<Item = StructNamedField>) -> StructNamed {
+    Builder::default()
+        .token(LEFT_BRACE)
+        .lines(fields, Some(COMMA))
+        .token(RIGHT_BRACE)
+        .finish(STRUCT_BODY_NAMED_NODE)
+}
+
+/// Attributes on lines of their own, then `visibility name: type`.
+pub fn struct_named_field(
+    attributes: impl IntoIterator<Item = Attribute>,
+    visibility: Option<Visibility>,
+    name: Identifier,
+    r#type: impl Into<TypeExpression>,
+) -> StructNamedField {
+    Builder::default()
+        .attributes(attributes, true)
+        .optional(visibility, |builder, visibility| {
+            builder.node(&visibility).space()
+        })
+        .node(&name)
+        .token(COLON)
+        .space()
+        .node(&r#type.into())
+        .finish(STRUCT_BODY_NAMED_FIELD_NODE)
+}
+
+/// `(fields);` as the body of a struct.
+pub fn struct_unnamed(fields: impl IntoIterator<Item = StructUnnamedField>) -> StructUnnamed {
+    Builder::default()
+        .token(LEFT_PAREN)
+        .list(fields)
+        .token(RIGHT_PAREN)
+        .token(SEMICOLON)
+        .finish(STRUCT_BODY_UNNAMED_NODE)
+}
+
+/// Attributes followed by spaces, then `visibility type`.
+pub fn struct_unnamed_field(
+    attributes: impl IntoIterator<Item = Attribute>,
+    visibility: Option<Visibility>,
+    r#type: impl Into<TypeExpression>,
+) -> StructUnnamedField {
+    Builder::default()
+        .attributes(attributes, false)
+        .optional(visibility, |builder, visibility| {
+            builder.node(&visibility).space()
+        })
+        .node(&r#type.into())
+        .finish(STRUCT_BODY_UNNAMED_FIELD_NODE)
+}
+
+/// `enum name { variants }`, one variant per line.
+pub fn enum_definition(
+    name: Identifier,
+    variants: impl IntoIterator<Item = EnumVariant>,
+) -> EnumDefinition {
+    Builder::default()
+        .token(ENUM)
+        .space()
+        .node(&name)
+        .space()
+        .token(LEFT_BRACE)
+        .lines(variants, Some(COMMA))
+        .token(RIGHT_BRACE)
+        .finish(ENUM_DEFINITION_NODE)
+}
+
+/// Attributes on lines of their own, then `name`.
+pub fn enum_variant_unit(
+    attributes: impl IntoIterator<Item = Attribute>,
+    name: Identifier,
+) -> EnumVariantUnit {
+    Builder::default()
+        .attributes(attributes, true)
+        .node(&name)
+        .finish(ENUM_VARIANT_UNIT_NODE)
+}
+
+/// Attributes on lines of their own, then `name = initializer`.
+pub fn enum_variant_scalar(
+    attributes: impl IntoIterator<Item = Attribute>,
+    name: Identifier,
+    initializer: impl Into<Expression>,
+) -> EnumVariantScalar {
+    Builder::default()
+        .attributes(attributes, true)
+        .node(&name)
+        .space()
+        .token(EQUAL)
+        .space()
+        .node(&initializer.into())
+        .finish(ENUM_VARIANT_SCALAR_NODE)
+}
+
+/// Attributes on lines of their own, then `name { fields }`.
+pub fn enum_variant_named(
+    attributes: impl IntoIterator<Item = Attribute>,
+    name: Identifier,
+    fields: impl IntoIterator<Item = EnumVariantNamedField>,
+) -> EnumVariantNamed {
+    let fields: Vec<_> = fields.into_iter().collect();
+    let padding = !fields.is_empty();
+
+    Builder::default()
+        .attributes(attributes, true)
+        .node(&name)
+        .space()
+        .token(LEFT_BRACE)
+        .optional(padding.then_some(()), |builder, ()| builder.space())
+        .list(fields)
+        .optional(padding.then_some(()), |builder, ()| builder.space())
+        .token(RIGHT_BRACE)
+        .finish(ENUM_VARIANT_NAMED_NODE)
+}
+
+/// Attributes followed by spaces, then `name: type`.
+pub fn enum_variant_named_field(
+    attributes: impl IntoIterator<Item = Attribute>,
+    name: Identifier,
+    r#type: impl Into<TypeExpression>,
+) -> EnumVariantNamedField {
+    Builder::default()
+        .attributes(attributes, false)
+        .node(&name)
+        .token(COLON)
+        .space()
+        .node(&r#type.into())
+        .finish(ENUM_VARIANT_NAMED_FIELD_NODE)
+}
+
+/// Attributes on lines of their own, then `name(fields)`.
+pub fn enum_variant_unnamed(
+    attributes: impl IntoIterator<Item = Attribute>,
+    name: Identifier,
+    fields: impl IntoIterator<Item = TypeExpression>,
+) -> EnumVariantUnnamed {
+    Builder::default()
+        .attributes(attributes, true)
+        .node(&name)
+        .token(LEFT_PAREN)
+        .list(fields)
+        .token(RIGHT_PAREN)
+        .finish(ENUM_VARIANT_UNNAMED_NODE)
+}
+
+/// `use tree;`
+pub fn use_definition(tree: UseTree) -> UseDefinition {
+    Builder::default()
+        .token(USE)
+        .space()
+        .node(&tree)
+        .token(SEMICOLON)
+        .finish(USE_DEFINITION_NODE)
+}
+
+/// `::kind`, `kind`
+pub fn use_tree(root: Option<PathSegmentRoot>, kind: impl Into<UseTreeKind>) -> UseTree {
+    Builder::default()
+        .optional(root, |builder, root| builder.node(&root))
+        .node(&kind.into())
+        .finish(USE_TREE_NODE)
+}
+
+/// `::{trees}`, `{trees}`
+pub fn use_tree_list(colons: bool, trees: impl IntoIterator<Item = UseTree>) -> UseTreeList {
+    Builder::default()
+        .optional(colons.then_some(()), |builder, ()| {
+            builder.token(COLON).token(COLON)
+        })
+        .token(LEFT_BRACE)
+        .list(trees)
+        .token(RIGHT_BRACE)
+        .finish(USE_TREE_LIST_NODE)
+}
+
+/// `::*`, `*`
+pub fn use_tree_glob(colons: bool) -> UseTreeGlob {
+    Builder::default()
+        .optional(colons.then_some(()), |builder, ()| {
+            builder.token(COLON).token(COLON)
+        })
+        .token(ASTERISK)
+        .finish(USE_TREE_GLOB_NODE)
+}
+
+/// `path`, `path::{trees}`, `path::*`, `path as name`
+pub fn use_tree_element(path: Path, trailing: Option<UseTreeTrailing>) -> UseTreeElement {
+    Builder::default()
+        .node(&path)
+        .optional(trailing, |builder, trailing| match trailing {
+            UseTreeTrailing::Rename(_) => builder.space().node(&trailing),
+            _ => builder.node(&trailing),
+        })
+        .finish(USE_TREE_ELEMENT_NODE)
+}
+
+/// `as identifier`
+pub fn use_tree_rename(identifier: Identifier) -> UseTreeRename {
+    Builder::default()
+        .token(AS)
+        .space()
+        .node(&identifier)
+        .finish(USE_TREE_RENAME_NODE)
+}
+
+/// `mod name;`, `mod name { definitions }`
+pub fn module_definition(
+    name: Identifier,
+    kind: impl Into<ModuleDefinitionKind>,
+) -> ModuleDefinition {
+    let kind = kind.into();
+
+    Builder::default()
+        .token(MOD)
+        .space()
+        .node(&name)
+        .optional(
+            matches!(kind, ModuleDefinitionKind::Inline(_)).then_some(()),
+            |builder, ()| builder.space(),
+        )
+        .node(&kind)
+        .finish(MODULE_DEFINITION_NODE)
+}
+
+/// `{ definitions }` as the body of a module, one definition per line.
+pub fn module_definition_inline(
+    definitions: impl IntoIterator<Item = Definition>,
+) -> ModuleDefinitionInline {
+    Builder::default()
+        .token(LEFT_BRACE)
+        .lines(definitions, None)
+        .token(RIGHT_BRACE)
+        .finish(MODULE_DEFINITION_INLINE_NODE)
+}
+
+/// `;` as the body of a module.
+pub fn module_definition_external() -> ModuleDefinitionExternal {
+    Builder::default()
+        .token(SEMICOLON)
+        .finish(MODULE_DEFINITION_EXTERNAL_NODE)
+}
+
+/// `trait name { definitions }`, one definition per line.
+pub fn trait_definition(
+    name: Identifier,
+    definitions: impl IntoIterator<Item = AssociatedDefinition>,
+) -> TraitDefinition {
+    Builder::default()
+        .token(TRAIT)
+        .space()
+        .node(&name)
+        .space()
+        .token(LEFT_BRACE)
+        .lines(definitions, None)
+        .token(RIGHT_BRACE)
+        .finish(TRAIT_DEFINITION_NODE)
+}
+
+/// `const name: type = initializer;`
+pub fn constant_definition(
+    name: Identifier,
+    r#type: Option<TypeExpression>,
+    initializer: Option<Expression>,
+) -> ConstantDefinition {
+    Builder::default()
+        .token(CONST)
+        .space()
+        .node(&name)
+        .optional(r#type, |builder, r#type| {
+            builder.token(COLON).space().node(&r#type)
+        })
+        .optional(initializer, |builder, initializer| {
+            builder.space().token(EQUAL).space().node(&initializer)
+        })
+        .token(SEMICOLON)
+        .finish(CONSTANT_DEFINITION_NODE)
+}
+
+/// `static name: type = initializer;`
+pub fn static_definition(
+    name: Identifier,
+    r#type: impl Into<TypeExpression>,
+    initializer: impl Into<Expression>,
+) -> StaticDefinition {
+    Builder::default()
+        .token(STATIC)
+        .space()
+        .node(&name)
+        .token(COLON)
+        .space()
+        .node(&r#type.into())
+        .space()
+        .token(EQUAL)
+        .space()
+        .node(&initializer.into())
+        .token(SEMICOLON)
+        .finish(STATIC_DEFINITION_NODE)
+}
+
+/// `type name: bound = initializer;`
+pub fn type_definition(
+    name: Identifier,
+    bound: Option<TypeExpression>,
+    initializer: Option<TypeExpression>,
+) -> TypeDefinition {
+    Builder::default()
+        .token(TYPE)
+        .space()
+        .node(&name)
+        .optional(bound, |builder, bound| {
+            builder.token(COLON).space().node(&bound)
+        })
+        .optional(initializer, |builder, initializer| {
+            builder.space().token(EQUAL).space().node(&initializer)
+        })
+        .token(SEMICOLON)
+        .finish(TYPE_DEFINITION_NODE)
+}
+
+/// `impl trait_type for target_type { definitions }`, one definition per line.
+pub fn implement_definition(
+    trait_type: Option<TypeExpression>,
+    target_type: impl Into<TypeExpression>,
+    definitions: impl IntoIterator<Item = AssociatedDefinition>,
+) -> ImplementDefinition {
+    Builder::default()
+        .token(IMPL)
+        .space()
+        .optional(trait_type, |builder, trait_type| {
+            builder.node(&trait_type).space().token(FOR).space()
+        })
+        .node(&target_type.into())
+        .space()
+        .token(LEFT_BRACE)
+        .lines(definitions, None)
+        .token(RIGHT_BRACE)
+        .finish(IMPLEMENT_DEFINITION_NODE)
+}
+
+/// Attributes on lines of their own, then `kind`.
+pub fn associated_definition(
+    attributes: impl IntoIterator<Item = Attribute>,
+    kind: impl Into<AssociatedDefinitionKind>,
+) -> AssociatedDefinition {
+    Builder::default()
+        .attributes(attributes, true)
+        .node(&kind.into())
+        .finish(ASSOCIATED_DEFINITION_NODE)
+}
+
+/// `!`
+pub fn never_pattern() -> NeverPattern {
+    Builder::default()
+        .token(EXCLAMATION)
+        .finish(NEVER_PATTERN_NODE)
+}
+
+/// `_`
+pub fn placeholder_pattern() -> PlaceholderPattern {
+    Builder::default()
+        .token(PLACEHOLDER)
+        .finish(PLACEHOLDER_PATTERN_NODE)
+}
+
+/// `path`
+pub fn path_pattern(path: Path) -> PathPattern {
+    Builder::default().node(&path).finish(PATH_PATTERN_NODE)
+}
+
+/// `mut pattern`
+pub fn mutable_pattern(pattern: impl Into<Pattern>) -> MutablePattern {
+    Builder::default()
+        .token(MUT)
+        .space()
+        .node(&pattern.into())
+        .finish(MUTABLE_PATTERN_NODE)
+}
+
+/// `(elements)`
+pub fn tuple_pattern(elements: impl IntoIterator<Item = Pattern>) -> TuplePattern {
+    Builder::default()
+        .token(LEFT_PAREN)
+        .list(elements)
+        .token(RIGHT_PAREN)
+        .finish(TUPLE_PATTERN_NODE)
+}
+
+/// `[elements]`
+pub fn array_pattern(elements: impl IntoIterator<Item = Pattern>) -> ArrayPattern {
+    Builder::default()
+        .token(LEFT_BRACKET)
+        .list(elements)
+        .token(RIGHT_BRACKET)
+        .finish(ARRAY_PATTERN_NODE)
+}
+
+/// `literal`
+pub fn literal_pattern(literal: LiteralExpression) -> LiteralPattern {
+    Builder::default()
+        .node(&literal)
+        .finish(LITERAL_PATTERN_NODE)
+}
+
+/// `name @ pattern`
+pub fn at_pattern(name: Identifier, pattern: impl Into<Pattern>) -> AtPattern {
+    Builder::default()
+        .node(&name)
+        .space()
+        .token(AT)
+        .space()
+        .node(&pattern.into())
+        .finish(AT_PATTERN_NODE)
+}
+
+/// `pattern | pattern | ...`
+///
+/// # Panics
+///
+/// Panics if there are fewer than two patterns.
+pub fn or_pattern(patterns: impl IntoIterator<Item = Pattern>) -> OrPattern {
+    let mut builder = Builder::default();
+    let mut count = 0;
+    for pattern in patterns {
+        if count > 0 {
+            builder = builder.space().token(PIPE).space();
+        }
+        builder = builder.node(&pattern);
+        count += 1;
+    }
+    assert!(count >= 2, "an or pattern has at least two patterns");
+
+    builder.finish(OR_PATTERN_NODE)
+}
+
+/// `path { fields }`
+pub fn named_pattern(
+    path: Path,
+    fields: impl IntoIterator<Item = NamedPatternField>,
+) -> NamedPattern {
+    let fields: Vec<_> = fields.into_iter().collect();
+    let padding = !fields.is_empty();
+
+    Builder::default()
+        .node(&path)
+        .space()
+        .token(LEFT_BRACE)
+        .optional(padding.then_some(()), |builder, ()| builder.space())
+        .list(fields)
+        .optional(padding.then_some(()), |builder, ()| builder.space())
+        .token(RIGHT_BRACE)
+        .finish(NAMED_PATTERN_NODE)
+}
+
+/// `name: pattern`
+pub fn named_pattern_field(name: Identifier, pattern: impl Into<Pattern>) -> NamedPatternField {
+    Builder::default()
+        .node(&name)
+        .token(COLON)
+        .space()
+        .node(&pattern.into())
+        .finish(NAMED_PATTERN_FIELD_NODE)
+}
+
+/// `path(elements)`
+pub fn unnamed_pattern(path: Path, elements: impl IntoIterator<Item = Pattern>) -> UnnamedPattern {
+    Builder::default()
+        .node(&path)
+        .token(LEFT_PAREN)
+        .list(elements)
+        .token(RIGHT_PAREN)
+        .finish(UNNAMED_PATTERN_NODE)
+}
+
+/// Attributes on lines of their own, then `visibility definition`.
+///
+/// The parser only builds definition statements with attributes.
+pub fn definition_statement(
+    attributes: impl IntoIterator<Item = Attribute>,
+    visibility: Option<Visibility>,
+    definition: Definition,
+) -> DefinitionStatement {
+    Builder::default()
+        .attributes(attributes, true)
+        .optional(visibility, |builder, visibility| {
+            builder.node(&visibility).space()
+        })
+        .node(&definition)
+        .finish(DEFINITION_STATEMENT_NODE)
+}
+
+/// Attributes on lines of their own, then `expression;` or `expression`.
+pub fn expression_statement(
+    attributes: impl IntoIterator<Item = Attribute>,
+    expression: impl Into<Expression>,
+    semicolon: bool,
+) -> ExpressionStatement {
+    Builder::default()
+        .attributes(attributes, true)
+        .node(&expression.into())
+        .optional(semicolon.then_some(()), |builder, ()| {
+            builder.token(SEMICOLON)
+        })
+        .finish(EXPRESSION_STATEMENT_NODE)
+}
+
+/// `let pattern: type = initializer;`
+pub fn let_statement(
+    pattern: impl Into<Pattern>,
+    r#type: Option<TypeExpression>,
+    initializer: Option<Expression>,
+) -> LetStatement {
+    Builder::default()
+        .token(LET)
+        .space()
+        .node(&pattern.into())
+        .optional(r#type, |builder, r#type| {
+            builder.token(COLON).space().node(&r#type)
+        })
+        .optional(initializer, |builder, initializer| {
+            builder.space().token(EQUAL).space().node(&initializer)
+        })
+        .token(SEMICOLON)
+        .finish(LET_STATEMENT_NODE)
+}
+
+/// `;`
+pub fn semicolon_statement() -> SemicolonStatement {
+    Builder::default()
+        .token(SEMICOLON)
+        .finish(SEMICOLON_STATEMENT_NODE)
+}
+
+/// `break`, `break expression`
+pub fn break_expression(expression: Option<Expression>) -> BreakExpression {
+    Builder::default()
+        .token(BREAK)
+        .optional(expression, |builder, expression| {
+            builder.space().node(&expression)
+        })
+        .finish(BREAK_EXPRESSION_NODE)
+}
+
+/// `continue`
+pub fn continue_expression() -> ContinueExpression {
+    Builder::default()
+        .token(CONTINUE)
+        .finish(CONTINUE_EXPRESSION_NODE)
+}
+
+/// `return`, `return expression`
+pub fn return_expression(expression: Option<Expression>) -> ReturnExpression {
+    Builder::default()
+        .token(RETURN)
+        .optional(expression, |builder, expression| {
+            builder.space().node(&expression)
+        })
+        .finish(RETURN_EXPRESSION_NODE)
+}
+
+/// `for pattern in iterable body`
+pub fn for_expression(
+    pattern: impl Into<Pattern>,
+    iterable: impl Into<Expression>,
+    body: BlockExpression,
+) -> ForExpression {
+    Builder::default()
+        .token(FOR)
+        .space()
+        .node(&pattern.into())
+        .space()
+        .token(IN)
+        .space()
+        .node(&iterable.into())
+        .space()
+        .node(&body)
+        .finish(FOR_EXPRESSION_NODE)
+}
+
+/// `while condition body`
+pub fn while_expression(
+    condition: impl Into<Expression>,
+    body: BlockExpression,
+) -> WhileExpression {
+    Builder::default()
+        .token(WHILE)
+        .space()
+        .node(&condition.into())
+        .space()
+        .node(&body)
+        .finish(WHILE_EXPRESSION_NODE)
+}
+
+/// `loop body`
+pub fn loop_expression(body: BlockExpression) -> LoopExpression {
+    Builder::default()
+        .token(LOOP)
+        .space()
+        .node(&body)
+        .finish(LOOP_EXPRESSION_NODE)
+}
+
+/// `if condition then_branch else else_branch`
+pub fn if_expression(
+    condition: impl Into<Expression>,
+    then_branch: BlockExpression,
+    else_branch: Option<Expression>,
+) -> IfExpression {
+    Builder::default()
+        .token(IF)
+        .space()
+        .node(&condition.into())
+        .space()
+        .node(&then_branch)
+        .optional(else_branch, |builder, else_branch| {
+            builder.space().token(ELSE).space().node(&else_branch)
+        })
+        .finish(IF_EXPRESSION_NODE)
+}
+
+/// `match expression { arms }`, one arm per line.
+pub fn match_expression(
+    expression: impl Into<Expression>,
+    arms: impl IntoIterator<Item = MatchArm>,
+) -> MatchExpression {
+    Builder::default()
+        .token(MATCH)
+        .space()
+        .node(&expression.into())
+        .space()
+        .token(LEFT_BRACE)
+        .lines(arms, Some(COMMA))
+        .token(RIGHT_BRACE)
+        .finish(MATCH_EXPRESSION_NODE)
+}
+
+/// `pattern => expression`
+pub fn match_arm(pattern: impl Into<Pattern>, expression: impl Into<Expression>) -> MatchArm {
+    Builder::default()
+        .node(&pattern.into())
+        .space()
+        .token(EQUAL)
+        .token(RIGHT_CHEVRON)
+        .space()
+        .node(&expression.into())
+        .finish(MATCH_ARM_NODE)
+}
+
+/// `let pattern: type = initializer`
+pub fn let_expression(
+    pattern: impl Into<Pattern>,
+    r#type: Option<TypeExpression>,
+    initializer: Option<Expression>,
+) -> LetExpression {
+    Builder::default()
+        .token(LET)
+        .space()
+        .node(&pattern.into())
+        .optional(r#type, |builder, r#type| {
+            builder.token(COLON).space().node(&r#type)
+        })
+        .optional(initializer, |builder, initializer| {
+            builder.space().token(EQUAL).space().node(&initializer)
+        })
+        .finish(LET_EXPRESSION_NODE)
+}
+
+/// `[elements]`
+pub fn array_expression(elements: impl IntoIterator<Item = Expression>) -> ArrayExpression {
+    Builder::default()
+        .token(LEFT_BRACKET)
+        .list(elements)
+        .token(RIGHT_BRACKET)
+        .finish(ARRAY_EXPRESSION_NODE)
+}
+
+/// `(elements)`
+pub fn tuple_expression(elements: impl IntoIterator<Item = Expression>) -> TupleExpression {
+    Builder::default()
+        .token(LEFT_PAREN)
+        .list(elements)
+        .token(RIGHT_PAREN)
+        .finish(TUPLE_EXPRESSION_NODE)
+}
+
+/// Attributes followed by spaces, then `{ statements }`, one statement per
+/// line.
+pub fn block_expression(
+    attributes: impl IntoIterator<Item = Attribute>,
+    statements: impl IntoIterator<Item = Statement>,
+) -> BlockExpression {
+    Builder::default()
+        .attributes(attributes, false)
+        .token(LEFT_BRACE)
+        .lines(statements, None)
+        .token(RIGHT_BRACE)
+        .finish(BLOCK_EXPRESSION_NODE)
+}
+
+/// `literal`
+pub fn literal_expression(literal: impl Into<Literal>) -> LiteralExpression {
+    Builder::default()
+        .node(&literal.into())
+        .finish(LITERAL_EXPRESSION_NODE)
+}
+
+/// `path`
+pub fn path_expression(path: Path) -> PathExpression {
+    Builder::default().node(&path).finish(PATH_EXPRESSION_NODE)
+}
+
+/// `operator operand`, with a space only after `mut`.
+pub fn unary_expression(
+    operator: UnaryOperator,
+    operand: impl Into<Expression>,
+) -> UnaryExpression {
+    let space = operator.r#mut().is_some();
+
+    Builder::default()
+        .node(&operator)
+        .optional(space.then_some(()), |builder, ()| builder.space())
+        .node(&operand.into())
+        .finish(UNARY_EXPRESSION_NODE)
+}
+
+/// `left operator right`
+///
+/// The operands are used as they are, so `left` or `right` may bind looser than
+/// `operator` in the text of the result.
+pub fn binary_expression(
+    left: impl Into<Expression>,
+    operator: BinaryOperator,
+    right: impl Into<Expression>,
+) -> BinaryExpression {
+    Builder::default()
+        .node(&left.into())
+        .space()
+        .node(&operator)
+        .space()
+        .node(&right.into())
+        .finish(BINARY_EXPRESSION_NODE)
+}
+
+/// `left operator right`
+pub fn assignment_expression(
+    left: impl Into<Expression>,
+    operator: AssignmentOperator,
+    right: impl Into<Expression>,
+) -> AssignmentExpression {
+    Builder::default()
+        .node(&left.into())
+        .space()
+        .node(&operator)
+        .space()
+        .node(&right.into())
+        .finish(ASSIGNMENT_EXPRESSION_NODE)
+}
+
+/// `callee(arguments)`
+pub fn function_call_expression(
+    callee: impl Into<Expression>,
+    arguments: impl IntoIterator<Item = Expression>,
+) -> FunctionCallExpression {
+    Builder::default()
+        .node(&callee.into())
+        .operator(LEFT_PAREN)
+        .list(arguments)
+        .token(RIGHT_PAREN)
+        .finish(FUNCTION_CALL_EXPRESSION_NODE)
+}
+
+/// `receiver.method(arguments)`
+pub fn method_call_expression(
+    receiver: impl Into<Expression>,
+    method: Identifier,
+    arguments: impl IntoIterator<Item = Expression>,
+) -> MethodCallExpression {
+    Builder::default()
+        .node(&receiver.into())
+        .operator(DOT)
+        .node(&method)
+        .token(LEFT_PAREN)
+        .list(arguments)
+        .token(RIGHT_PAREN)
+        .finish(METHOD_CALL_EXPRESSION_NODE)
+}
+
+/// `receiver.field`
+pub fn field_expression(receiver: impl Into<Expression>, field: Identifier) -> FieldExpression {
+    Builder::default()
+        .node(&receiver.into())
+        .operator(DOT)
+        .node(&field)
+        .finish(FIELD_EXPRESSION_NODE)
+}
+
+/// `receiver[index]`
+pub fn index_expression(
+    receiver: impl Into<Expression>,
+    index: impl Into<Expression>,
+) -> IndexExpression {
+    Builder::default()
+        .node(&receiver.into())
+        .operator(LEFT_BRACKET)
+        .node(&index.into())
+        .token(RIGHT_BRACKET)
+        .finish(INDEX_EXPRESSION_NODE)
+}
+
+/// `expression.await`
+pub fn await_expression(expression: impl Into<Expression>) -> AwaitExpression {
+    Builder::default()
+        .node(&expression.into())
+        .operator(DOT)
+        .token(AWAIT)
+        .finish(AWAIT_EXPRESSION_NODE)
+}
+
+/// `expression?`
+pub fn try_expression(expression: impl Into<Expression>) -> TryExpression {
+    Builder::default()
+        .node(&expression.into())
+        .operator(QUESTION)
+        .finish(TRY_EXPRESSION_NODE)
+}
+
+/// `expression.yield`
+pub fn yield_expression(expression: impl Into<Expression>) -> YieldExpression {
+    Builder::default()
+        .node(&expression.into())
+        .operator(DOT)
+        .token(YIELD)
+        .finish(YIELD_EXPRESSION_NODE)
+}
+
+/// A unary operator of kind `PLUS`, `HYPHEN`, `EXCLAMATION`, `TILDE` or `MUT`.
+///
+/// # Panics
+///
+/// Panics if `kind` is not a unary operator.
+pub fn unary_operator(kind: SyntaxKind) -> UnaryOperator {
+    assert!(
+        kind.at_unary_operator(),
+        "`{kind:?}` is not a unary operator",
+    );
+
+    Builder::default().token(kind).finish(UNARY_OPERATOR_NODE)
+}
+
+/// A binary operator of kind `kind`, like `PLUS` or `EQUAL__EQUAL`.
+///
+/// # Panics
+///
+/// Panics if `kind` is not a binary operator.
+pub fn binary_operator(kind: SyntaxKind) -> BinaryOperator {
+    assert!(
+        kind.at_binary_operator(),
+        "`{kind:?}` is not a binary operator",
+    );
+
+    Builder::default()
+        .operator(kind)
+        .finish(BINARY_OPERATOR_NODE)
+}
+
+/// An assignment operator of kind `kind`, like `EQUAL` or `PLUS__EQUAL`.
+///
+/// # Panics
+///
+/// Panics if `kind` is not an assignment operator.
+pub fn assignment_operator(kind: SyntaxKind) -> AssignmentOperator {
+    assert!(
+        kind.at_assign_operator(),
+        "`{kind:?}` is not an assignment operator",
+    );
+
+    Builder::default()
+        .operator(kind)
+        .finish(ASSIGNMENT_OPERATOR_NODE)
+}
+
+/// `true`, `false`
+pub fn boolean_literal(value: bool) -> BooleanLiteral {
+    Builder::default()
+        .token(if value { TRUE } else { FALSE })
+        .finish(BOOLEAN_LITERAL_NODE)
+}
+
+/// `'character'`
+pub fn character_literal(kind: impl Into<CharacterLiteralKind>) -> CharacterLiteral {
+    Builder::default()
+        .token(CHARACTER_START)
+        .node(&kind.into())
+        .token(CHARACTER_END)
+        .finish(CHARACTER_LITERAL_NODE)
+}
+
+/// `b'character'`
+pub fn byte_literal(kind: impl Into<CharacterLiteralKind>) -> ByteLiteral {
+    Builder::default()
+        .token(BYTE_START)
+        .node(&kind.into())
+        .token(CHARACTER_END)
+        .finish(BYTE_LITERAL_NODE)
+}
+
+/// The contents of a character literal holding `value`, escaped if needed.
+pub fn character(value: char) -> CharacterLiteralKind {
+    match escape(value, '\'') {
+        Some(Escape::Segment(segment)) => character_literal_escape(segment).into(),
+        Some(Escape::Unicode) => character_literal_unicode(value).into(),
+        None => character_literal_one(value).into(),
+    }
+}
+
+/// The contents of a byte literal holding `value`, escaped if needed.
+pub fn byte(value: u8) -> CharacterLiteralKind {
+    match escape(value.into(), '\'') {
+        Some(Escape::Segment(segment)) => character_literal_escape(segment).into(),
+        _ if !value.is_ascii() || value.is_ascii_control() => {
+            character_literal_escape(&format!("x{value:02X}")).into()
+        }
+        _ => character_literal_one(value.into()).into(),
+    }
+}
+
+/// `character`, verbatim.
+pub fn character_literal_one(character: char) -> CharacterLiteralOne {
+    Builder::default()
+        .text(CHARACTER_SEGMENT, character.encode_utf8(&mut [0; 4]))
+        .finish(CHARACTER_LITERAL_ONE_NODE)
+}
+
+/// `\segment`: `\n`, `\x7F`, etc.
+pub fn character_literal_escape(segment: &str) -> CharacterLiteralEscape {
+    Builder::default()
+        .token(ESCAPE_START)
+        .text(ESCAPE_SEGMENT, segment)
+        .finish(CHARACTER_LITERAL_ESCAPE_NODE)
+}
+
+/// `\u{XXXX}`
+pub fn character_literal_unicode(character: char) -> CharacterLiteralUnicode {
+    Builder::default()
+        .token(UNICODE_START)
+        .text(UNICODE_SEGMENT, &format!("{:X}", u32::from(character)))
+        .token(UNICODE_END)
+        .finish(CHARACTER_LITERAL_UNICODE_NODE)
+}
+
+/// `42`
+pub fn integer_literal(value: u128) -> IntegerLiteral {
+    Builder::default()
+        .text(INTEGER_SEGMENT, &value.to_string())
+        .finish(INTEGER_LITERAL_NODE)
+}
+
+/// `3.14`, `1e100`
+///
+/// # Panics
+///
+/// Panics if `value` is negative or not finite.
+pub fn float_literal(value: f64) -> FloatLiteral {
+    assert!(
+        value.is_finite() && value.is_sign_positive(),
+        "`{value}` is not a float literal",
+    );
+
+    let text = format!("{value:?}");
+    let (mantissa, exponent) = match text.split_once('e') {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (text.as_str(), None),
+    };
+    let (integer, fraction) = match mantissa.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (mantissa, None),
+    };
+
+    Builder::default()
+        .text(INTEGER_SEGMENT, integer)
+        .optional(fraction, |builder, fraction| {
+            builder
+                .token(FRACTION_START)
+                .text(FRACTION_SEGMENT, fraction)
+        })
+        .optional(exponent, |builder, exponent| {
+            let (sign, exponent) = match exponent.strip_prefix('-') {
+                Some(exponent) => (true, exponent),
+                None => (false, exponent),
+            };
+
+            builder
+                .token(EXPONENT_START)
+                .optional(sign.then_some(()), |builder, ()| {
+                    builder.token(EXPONENT_SIGN)
+                })
+                .text(EXPONENT_SEGMENT, exponent)
+        })
+        .finish(FLOAT_LITERAL_NODE)
+}
+
+/// `"segments"`
+pub fn string_literal(segments: impl IntoIterator<Item = StringSegment>) -> StringLiteral {
+    Builder::default()
+        .token(STRING_START)
+        .nodes(segments)
+        .token(STRING_END)
+        .finish(STRING_LITERAL_NODE)
+}
+
+/// `b"segments"`
+pub fn byte_string_literal(segments: impl IntoIterator<Item = StringSegment>) -> ByteStringLiteral {
+    Builder::default()
+        .token(BYTE_STRING_START)
+        .nodes(segments)
+        .token(STRING_END)
+        .finish(BYTE_STRING_LITERAL_NODE)
+}
+
+/// `c"segments"`
+pub fn c_string_literal(segments: impl IntoIterator<Item = StringSegment>) -> CStringLiteral {
+    Builder::default()
+        .token(C_STRING_START)
+        .nodes(segments)
+        .token(STRING_END)
+        .finish(C_STRING_LITERAL_NODE)
+}
+
+/// The segments of a string literal holding `value`, escaped if needed.
+pub fn string_segments(value: &str) -> Vec<StringSegment> {
+    let mut segments = vec![];
+    let mut text = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        let segment: StringSegment = match escape(c, '"') {
+            Some(Escape::Segment(segment)) => string_literal_escape(segment).into(),
+            Some(Escape::Unicode) => string_literal_unicode(c).into(),
+            None if c == '$' && chars.peek() == Some(&'{') => string_literal_escape("$").into(),
+            None => {
+                text.push(c);
+                continue;
+            }
+        };
+        if !text.is_empty() {
+            segments.push(string_literal_text(&std::mem::take(&mut text)).into());
+        }
+        segments.push(segment);
+    }
+    if !text.is_empty() {
+        segments.push(string_literal_text(&text).into());
+    }
+
+    segments
+}
+
+/// The segments of a byte string literal holding `value`, escaped if needed.
+pub fn byte_string_segments(value: &[u8]) -> Vec<StringSegment> {
+    let mut segments = vec![];
+    let mut text = String::new();
+    for (index, &byte) in value.iter().enumerate() {
+        let segment: StringSegment = match escape(byte.into(), '"') {
+            Some(Escape::Segment(segment)) => string_literal_escape(segment).into(),
+            _ if !byte.is_ascii() || byte.is_ascii_control() => {
+                string_literal_escape(&format!("x{byte:02X}")).into()
+            }
+            _ if byte == b'$' && value.get(index + 1) == Some(&b'{') => {
+                string_literal_escape("$").into()
+            }
+            _ => {
+                text.push(byte.into());
+                continue;
+            }
+        };
+        if !text.is_empty() {
+            segments.push(string_literal_text(&std::mem::take(&mut text)).into());
+        }
+        segments.push(segment);
+    }
+    if !text.is_empty() {
+        segments.push(string_literal_text(&text).into());
+    }
+
+    segments
+}
+
+/// `text`, verbatim.
+pub fn string_literal_text(text: &str) -> StringLiteralText {
+    Builder::default()
+        .text(STRING_SEGMENT, text)
+        .finish(STRING_LITERAL_TEXT_NODE)
+}
+
+/// `\segment`: `\n`, `\x7F`, etc.
+pub fn string_literal_escape(segment: &str) -> StringLiteralEscape {
+    Builder::default()
+        .token(ESCAPE_START)
+        .text(ESCAPE_SEGMENT, segment)
+        .finish(STRING_LITERAL_ESCAPE_NODE)
+}
+
+/// `\u{XXXX}`
+pub fn string_literal_unicode(character: char) -> StringLiteralUnicode {
+    Builder::default()
+        .token(UNICODE_START)
+        .text(UNICODE_SEGMENT, &format!("{:X}", u32::from(character)))
+        .token(UNICODE_END)
+        .finish(STRING_LITERAL_UNICODE_NODE)
+}
+
+/// `${expression}`
+pub fn string_literal_interpolation(
+    expression: impl Into<Expression>,
+) -> StringLiteralInterpolation {
+    Builder::default()
+        .token(INTERPOLATION_START)
+        .node(&expression.into())
+        .token(INTERPOLATION_END)
+        .finish(STRING_LITERAL_INTERPOLATION_NODE)
+}
+
+enum Escape {
+    Segment(&'static str),
+    Unicode,
+}
+
+/// How `c` is written in a literal quoted by `quote`, if not as itself.
+fn escape(c: char, quote: char) -> Option<Escape> {
+    match c {
+        '\\' => Some(Escape::Segment("\\")),
+        '\n' => Some(Escape::Segment("n")),
+        '\r' => Some(Escape::Segment("r")),
+        '\t' => Some(Escape::Segment("t")),
+        '\0' => Some(Escape::Segment("0")),
+        '\'' if quote == '\'' => Some(Escape::Segment("'")),
+        '"' if quote == '"' => Some(Escape::Segment("\"")),
+        c if c.is_control() => Some(Escape::Unicode),
+        _ => None,
+    }
+}
+
+/// `0b101010`
+pub fn binary_literal(value: u128) -> BinaryLiteral {
+    Builder::default()
+        .token(BINARY_START)
+        .text(BINARY_SEGMENT, &format!("{value:b}"))
+        .finish(BINARY_NUMERIC_LITERAL_NODE)
+}
+
+/// `0o52`
+pub fn octal_literal(value: u128) -> OctalLiteral {
+    Builder::default()
+        .token(OCTAL_START)
+        .text(OCTAL_SEGMENT, &format!("{value:o}"))
+        .finish(OCTAL_NUMERIC_LITERAL_NODE)
+}
+
+/// `0x2A`
+pub fn hex_literal(value: u128) -> HexLiteral {
+    Builder::default()
+        .token(HEX_START)
+        .text(HEX_SEGMENT, &format!("{value:X}"))
+        .finish(HEX_NUMERIC_LITERAL_NODE)
+}
+
+/// `mut type`
+pub fn mutable_type(r#type: impl Into<TypeExpression>) -> MutableType {
+    Builder::default()
+        .token(MUT)
+        .space()
+        .node(&r#type.into())
+        .finish(MUTABLE_TYPE_NODE)
+}
+
+/// `path`, `path::<arguments>`
+pub fn path_type(path: Path, arguments: impl IntoIterator<Item = TypeExpression>) -> PathType {
+    let arguments: Vec<_> = arguments.into_iter().collect();
+
+    Builder::default()
+        .node(&path)
+        .optional(
+            (!arguments.is_empty()).then_some(arguments),
+            |builder, arguments| {
+                builder
+                    .token(COLON)
+                    .token(COLON)
+                    .token(LEFT_CHEVRON)
+                    .list(arguments)
+                    .token(RIGHT_CHEVRON)
+            },
+        )
+        .finish(PATH_TYPE_NODE)
+}
+
+/// `[type]`
+pub fn slice_type(r#type: impl Into<TypeExpression>) -> SliceType {
+    Builder::default()
+        .token(LEFT_BRACKET)
+        .node(&r#type.into())
+        .token(RIGHT_BRACKET)
+        .finish(SLICE_TYPE_NODE)
+}
+
+/// `(elements)`
+pub fn tuple_type(elements: impl IntoIterator<Item = TypeExpression>) -> TupleType {
+    Builder::default()
+        .token(LEFT_PAREN)
+        .list(elements)
+        .token(RIGHT_PAREN)
+        .finish(TUPLE_TYPE_NODE)
+}
+
+/// `pub`, `pub(path)`
+pub fn visibility(path: Option<Path>) -> Visibility {
+    Builder::default()
+        .token(PUB)
+        .optional(path, |builder, path| {
+            builder.token(LEFT_PAREN).node(&path).token(RIGHT_PAREN)
+        })
+        .finish(VISIBILITY_NODE)
+}
+
+/// `segments`, joined by `::` except after a root segment.
+pub fn path(segments: impl IntoIterator<Item = PathSegment>) -> Path {
+    let mut builder = Builder::default();
+    let mut separate = false;
+    for segment in segments {
+        if separate {
+            builder = builder.token(COLON).token(COLON);
+        }
+        separate = !matches!(segment, PathSegment::Root(_));
+        builder = builder.node(&segment);
+    }
+
+    builder.finish(PATH_NODE)
+}
+
+/// A path of identifier segments: `a::b::C`.
+pub fn path_from_names<'a>(names: impl IntoIterator<Item = &'a str>) -> Path {
+    path(
+        names
+            .into_iter()
+            .map(|name| path_segment_identifier(identifier(name)).into()),
+    )
+}
+
+/// `::`
+pub fn path_segment_root() -> PathSegmentRoot {
+    Builder::default()
+        .token(COLON)
+        .token(COLON)
+        .finish(PATH_SEGMENT_ROOT_NODE)
+}
+
+/// `self`
+pub fn path_segment_self() -> PathSegmentSelf {
+    Builder::default()
+        .token(SELF)
+        .finish(PATH_SEGMENT_SELF_NODE)
+}
+
+/// `super`
+pub fn path_segment_super() -> PathSegmentSuper {
+    Builder::default()
+        .token(SUPER)
+        .finish(PATH_SEGMENT_SUPER_NODE)
+}
+
+/// `crate`
+pub fn path_segment_krate() -> PathSegmentKrate {
+    Builder::default()
+        .token(CRATE)
+        .finish(PATH_SEGMENT_KRATE_NODE)
+}
+
+/// `identifier`
+pub fn path_segment_identifier(identifier: Identifier) -> PathSegmentIdentifier {
+    Builder::default()
+        .node(&identifier)
+        .finish(PATH_SEGMENT_IDENTIFIER_NODE)
+}
+
+/// `name`, or `r#name` if `name` is a keyword.
+///
+/// # Panics
+///
+/// Panics if `name` is not an identifier.
+pub fn identifier(name: &str) -> Identifier {
+    let (tokens, _) = danubec_lex::lex(name);
+    let raw = match tokens.as_slice() {
+        [(IDENTIFIER, text)] if *text == name => false,
+        [(kind, text)] if *text == name && *kind != PLACEHOLDER && keyword(name) => true,
+        _ => panic!("`{name}` is not an identifier"),
+    };
+
+    Builder::default()
+        .optional(raw.then_some(()), |builder, ()| {
+            builder.token(RAW_IDENTIFIER_START)
+        })
+        .node(&identifier_segment(name))
+        .finish(IDENTIFIER_NODE)
+}
+
+/// Whether `name` lexes as an identifier after `r#`.
+fn keyword(name: &str) -> bool {
+    let source = format!("r#{name}");
+    let (tokens, _) = danubec_lex::lex(&source);
+
+    matches!(
+        tokens.as_slice(),
+        [(RAW_IDENTIFIER_START, _), (IDENTIFIER, text)] if *text == name
+    )
+}
+
+/// `identifier`, verbatim.
+pub fn identifier_segment(identifier: &str) -> IdentifierSegment {
+    Builder::default()
+        .text(IDENTIFIER, identifier)
+        .finish(IDENTIFIER_SEGMENT)
+}
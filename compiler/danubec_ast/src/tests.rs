@@ -0,0 +1,938 @@
+use crate::{edit, make, *};
+use danubec_diagnostic::Diagnostic;
+use danubec_syntax::SyntaxKind;
+use rowan::ast::AstNode;
+
+type Entry = fn(&str, &mut Diagnostic) -> SyntaxNode;
+
+/// Checks that `node` reads as `expected`, and that `entry` parses that text
+/// into the same tree.
+#[track_caller]
+fn check(entry: Entry, node: &impl AstNode<Language = Danube>, expected: &str) {
+    let node = node.syntax();
+    assert_eq!(node.to_string(), expected);
+    validate(expected, node);
+
+    let mut diagnostic = Diagnostic::new();
+    let parsed = entry(expected, &mut diagnostic);
+
+    assert!(diagnostic.is_empty(), "{expected:?}: {diagnostic:?}");
+    assert_eq!(format!("{node:#?}"), format!("{parsed:#?}"));
+}
+
+#[track_caller]
+fn validate(source: &str, node: &SyntaxNode) {
+    if let Err(error) = danubec_grammar::validate(node) {
+        panic!("{source:?}: {error}");
+    }
+}
+
+/// Parses `source` into a tree that can be edited in place.
+fn parse(source: &str) -> Root {
+    let mut diagnostic = Diagnostic::new();
+    let node = danubec_parse::parse(source, &mut diagnostic);
+
+    assert!(diagnostic.is_empty(), "{source:?}: {diagnostic:?}");
+    Root::cast(node.clone_for_update()).unwrap()
+}
+
+/// Checks that `root` reads as `expected`, and is what the parser makes of it.
+#[track_caller]
+fn check_edit(root: &Root, expected: &str) {
+    check(danubec_parse::parse, root, expected);
+}
+
+fn name(name: &str) -> PathExpression {
+    make::path_expression(make::path_from_names([name]))
+}
+
+fn pattern(name: &str) -> PathPattern {
+    make::path_pattern(make::path_from_names([name]))
+}
+
+fn ty(name: &str) -> PathType {
+    make::path_type(make::path_from_names([name]), [])
+}
+
+fn integer(value: u128) -> LiteralExpression {
+    make::literal_expression(make::integer_literal(value))
+}
+
+#[test]
+fn identifier() {
+    assert_eq!(make::identifier("foo").to_string(), "foo");
+    assert_eq!(make::identifier("fn").to_string(), "r#fn");
+    assert!(make::identifier("fn").raw_start().is_some());
+}
+
+#[test]
+#[should_panic = "`1a` is not an identifier"]
+fn invalid_identifier() {
+    make::identifier("1a");
+}
+
+#[test]
+fn paths() {
+    let path = make::path([
+        make::path_segment_root().into(),
+        make::path_segment_krate().into(),
+        make::path_segment_identifier(make::identifier("a")).into(),
+    ]);
+    check(
+        danubec_parse::parse_expression,
+        &make::path_expression(path),
+        "::crate::a",
+    );
+
+    let path = make::path([
+        make::path_segment_self().into(),
+        make::path_segment_super().into(),
+        make::path_segment_identifier(make::identifier("type")).into(),
+    ]);
+    check(
+        danubec_parse::parse_expression,
+        &make::path_expression(path),
+        "self::super::r#type",
+    );
+}
+
+#[test]
+fn operators() {
+    let product = make::binary_expression(
+        name("b"),
+        make::binary_operator(SyntaxKind::ASTERISK),
+        integer(2),
+    );
+    let sum = make::binary_expression(name("a"), make::binary_operator(SyntaxKind::PLUS), product);
+    check(danubec_parse::parse_expression, &sum, "a + b * 2");
+
+    let comparison = make::binary_expression(
+        name("a"),
+        make::binary_operator(SyntaxKind::LEFT_CHEVRON__LEFT_CHEVRON__PIPE),
+        name("b"),
+    );
+    check(danubec_parse::parse_expression, &comparison, "a <<| b");
+
+    let assignment = make::assignment_expression(
+        name("a"),
+        make::assignment_operator(SyntaxKind::ASTERISK__ASTERISK__EQUAL),
+        make::unary_expression(make::unary_operator(SyntaxKind::HYPHEN), name("b")),
+    );
+    check(danubec_parse::parse_expression, &assignment, "a **= -b");
+
+    let mutable = make::unary_expression(make::unary_operator(SyntaxKind::MUT), name("a"));
+    check(danubec_parse::parse_expression, &mutable, "mut a");
+}
+
+#[test]
+#[should_panic = "`SEMICOLON` is not a binary operator"]
+fn invalid_operator() {
+    make::binary_operator(SyntaxKind::SEMICOLON);
+}
+
+#[test]
+fn postfix_expressions() {
+    let call = make::function_call_expression(name("f"), [integer(1).into(), name("a").into()]);
+    let method = make::method_call_expression(call, make::identifier("g"), []);
+    let field = make::field_expression(method, make::identifier("h"));
+    let index = make::index_expression(field, integer(0));
+    let r#try = make::try_expression(make::await_expression(index));
+    let r#yield = make::yield_expression(r#try);
+    check(
+        danubec_parse::parse_expression,
+        &r#yield,
+        "f(1, a).g().h[0].await?.yield",
+    );
+}
+
+#[test]
+fn control_flow() {
+    let body = make::block_expression(
+        [],
+        [
+            make::let_statement(pattern("b"), Some(ty("i32").into()), Some(name("a").into()))
+                .into(),
+            make::expression_statement([], make::break_expression(None), true).into(),
+        ],
+    );
+    let r#for = make::for_expression(pattern("a"), name("items"), body);
+    check(
+        danubec_parse::parse_expression,
+        &r#for,
+        "for a in items {\n    let b: i32 = a;\n    break;\n}",
+    );
+
+    let r#if = make::if_expression(
+        name("a"),
+        make::block_expression([], []),
+        Some(
+            make::if_expression(
+                make::let_expression(pattern("b"), None, Some(name("c").into())),
+                make::block_expression(
+                    [],
+                    [make::expression_statement([], make::continue_expression(), false).into()],
+                ),
+                None,
+            )
+            .into(),
+        ),
+    );
+    check(
+        danubec_parse::parse_expression,
+        &r#if,
+        "if a {} else if let b = c {\n    continue\n}",
+    );
+
+    let r#loop = make::loop_expression(make::block_expression(
+        [],
+        [make::expression_statement(
+            [],
+            make::while_expression(
+                name("a"),
+                make::block_expression(
+                    [],
+                    [make::expression_statement(
+                        [],
+                        make::return_expression(Some(integer(1).into())),
+                        true,
+                    )
+                    .into()],
+                ),
+            ),
+            false,
+        )
+        .into()],
+    ));
+    check(
+        danubec_parse::parse_expression,
+        &r#loop,
+        "loop {\n    while a {\n        return 1;\n    }\n}",
+    );
+
+    let r#match = make::match_expression(
+        name("a"),
+        [
+            make::match_arm(
+                make::unnamed_pattern(make::path_from_names(["Some"]), [pattern("b").into()]),
+                name("b"),
+            ),
+            make::match_arm(make::placeholder_pattern(), make::block_expression([], [])),
+        ],
+    );
+    check(
+        danubec_parse::parse_expression,
+        &r#match,
+        "match a {\n    Some(b) => b,\n    _ => {},\n}",
+    );
+}
+
+#[test]
+fn literals() {
+    let literals: [(Literal, &str); 12] = [
+        (make::boolean_literal(true).into(), "true"),
+        (make::integer_literal(42).into(), "42"),
+        (make::float_literal(3.25).into(), "3.25"),
+        (make::float_literal(1e100).into(), "1e100"),
+        (make::float_literal(1.5e-7).into(), "1.5e-7"),
+        (make::binary_literal(42).into(), "0b101010"),
+        (make::octal_literal(42).into(), "0o52"),
+        (make::hex_literal(42).into(), "0x2A"),
+        (
+            make::character_literal(make::character('\'')).into(),
+            r"'\''",
+        ),
+        (
+            make::character_literal(make::character('\u{7}')).into(),
+            r"'\u{7}'",
+        ),
+        (make::byte_literal(make::byte(0xFF)).into(), r"b'\xFF'"),
+        (make::byte_literal(make::byte(b'a')).into(), "b'a'"),
+    ];
+    for (literal, expected) in literals {
+        check(
+            danubec_parse::parse_expression,
+            &make::literal_expression(literal),
+            expected,
+        );
+    }
+
+    let strings: [(Literal, &str); 3] = [
+        (
+            make::string_literal(make::string_segments("a \"b\"\n${c} $d")).into(),
+            r#""a \"b\"\n\${c} $d""#,
+        ),
+        (
+            make::byte_string_literal(make::byte_string_segments(b"a\xFF\\")).into(),
+            r#"b"a\xFF\\""#,
+        ),
+        (
+            make::c_string_literal(make::string_segments("a\0")).into(),
+            r#"c"a\0""#,
+        ),
+    ];
+    for (literal, expected) in strings {
+        check(
+            danubec_parse::parse_expression,
+            &make::literal_expression(literal),
+            expected,
+        );
+    }
+
+    let interpolation = make::string_literal([
+        make::string_literal_text("a = ").into(),
+        make::string_literal_interpolation(name("a")).into(),
+    ]);
+    check(
+        danubec_parse::parse_expression,
+        &make::literal_expression(interpolation),
+        r#""a = ${a}""#,
+    );
+}
+
+#[test]
+fn types() {
+    let r#type = make::tuple_type([
+        make::mutable_type(ty("i32")).into(),
+        make::slice_type(make::path_type(
+            make::path_from_names(["Vec"]),
+            [ty("String").into()],
+        ))
+        .into(),
+    ]);
+    check(
+        danubec_parse::parse_type,
+        &r#type,
+        "(mut i32, [Vec::<String>])",
+    );
+}
+
+#[test]
+fn patterns() {
+    let pattern = make::tuple_pattern([
+        make::mutable_pattern(pattern("a")).into(),
+        make::array_pattern([
+            make::never_pattern().into(),
+            make::placeholder_pattern().into(),
+        ])
+        .into(),
+        make::or_pattern([
+            make::at_pattern(make::identifier("b"), make::literal_pattern(integer(1))).into(),
+            make::literal_pattern(integer(2)).into(),
+        ])
+        .into(),
+        make::named_pattern(
+            make::path_from_names(["Foo"]),
+            [make::named_pattern_field(
+                make::identifier("c"),
+                pattern("d"),
+            )],
+        )
+        .into(),
+    ]);
+    check(
+        danubec_parse::parse_pattern,
+        &pattern,
+        "(mut a, [!, _], b @ 1 | 2, Foo { c: d })",
+    );
+}
+
+#[test]
+fn definitions() {
+    let function = make::definition(
+        [make::attribute(make::key_value_attribute_argument(
+            make::path_from_names(["inline"]),
+            None,
+        ))],
+        Some(make::visibility(None)),
+        make::function_definition(
+            make::identifier("add"),
+            [
+                make::function_parameter([], pattern("a"), ty("i32")),
+                make::function_parameter([], pattern("b"), ty("i32")),
+            ],
+            Some(ty("i32").into()),
+            make::function_body_block(make::block_expression(
+                [],
+                [make::expression_statement(
+                    [],
+                    make::binary_expression(
+                        name("a"),
+                        make::binary_operator(SyntaxKind::PLUS),
+                        name("b"),
+                    ),
+                    false,
+                )
+                .into()],
+            )),
+        ),
+    );
+    check(
+        danubec_parse::parse_definition,
+        &function,
+        "#[inline]\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}",
+    );
+
+    let r#struct = make::definition(
+        [],
+        None,
+        make::struct_definition(
+            make::identifier("Point"),
+            make::struct_named([
+                make::struct_named_field(
+                    [],
+                    Some(make::visibility(None)),
+                    make::identifier("x"),
+                    ty("f64"),
+                ),
+                make::struct_named_field([], None, make::identifier("y"), ty("f64")),
+            ]),
+        ),
+    );
+    check(
+        danubec_parse::parse_definition,
+        &r#struct,
+        "struct Point {\n    pub x: f64,\n    y: f64,\n}",
+    );
+
+    let r#struct = make::definition(
+        [],
+        None,
+        make::struct_definition(
+            make::identifier("Meters"),
+            make::struct_unnamed([make::struct_unnamed_field([], None, ty("f64"))]),
+        ),
+    );
+    check(
+        danubec_parse::parse_definition,
+        &r#struct,
+        "struct Meters(f64);",
+    );
+
+    let r#enum = make::definition(
+        [],
+        None,
+        make::enum_definition(
+            make::identifier("Shape"),
+            [
+                make::enum_variant_unit(
+                    [make::attribute(make::key_value_attribute_argument(
+                        make::path_from_names(["default"]),
+                        None,
+                    ))],
+                    make::identifier("Empty"),
+                )
+                .into(),
+                make::enum_variant_scalar([], make::identifier("Zero"), integer(0)).into(),
+                make::enum_variant_unnamed([], make::identifier("Circle"), [ty("f64").into()])
+                    .into(),
+                make::enum_variant_named(
+                    [],
+                    make::identifier("Rectangle"),
+                    [
+                        make::enum_variant_named_field([], make::identifier("width"), ty("f64")),
+                        make::enum_variant_named_field([], make::identifier("height"), ty("f64")),
+                    ],
+                )
+                .into(),
+            ],
+        ),
+    );
+    check(
+        danubec_parse::parse_definition,
+        &r#enum,
+        "enum Shape {\n    #[default]\n    Empty,\n    Zero = 0,\n    Circle(f64),\n    Rectangle { width: f64, height: f64 },\n}",
+    );
+
+    let r#impl = make::definition(
+        [],
+        None,
+        make::implement_definition(
+            Some(ty("Area").into()),
+            ty("Shape"),
+            [
+                make::associated_definition(
+                    [],
+                    make::type_definition(make::identifier("Output"), None, Some(ty("f64").into())),
+                ),
+                make::associated_definition(
+                    [],
+                    make::function_definition(
+                        make::identifier("area"),
+                        [],
+                        None,
+                        make::function_body_block(make::block_expression([], [])),
+                    ),
+                ),
+            ],
+        ),
+    );
+    check(
+        danubec_parse::parse_definition,
+        &r#impl,
+        "impl Area for Shape {\n    type Output = f64;\n    fn area() {}\n}",
+    );
+
+    let r#trait = make::definition(
+        [],
+        None,
+        make::trait_definition(
+            make::identifier("Area"),
+            [make::associated_definition(
+                [],
+                make::function_definition(
+                    make::identifier("area"),
+                    [],
+                    Some(ty("f64").into()),
+                    make::function_body_unit(),
+                ),
+            )],
+        ),
+    );
+    check(
+        danubec_parse::parse_definition,
+        &r#trait,
+        "trait Area {\n    fn area() -> f64;\n}",
+    );
+
+    let module = make::definition(
+        [],
+        None,
+        make::module_definition(
+            make::identifier("a"),
+            make::module_definition_inline([
+                make::definition(
+                    [],
+                    None,
+                    make::constant_definition(
+                        make::identifier("A"),
+                        Some(ty("i32").into()),
+                        Some(integer(1).into()),
+                    ),
+                ),
+                make::definition(
+                    [],
+                    None,
+                    make::static_definition(make::identifier("B"), ty("i32"), integer(2)),
+                ),
+                make::definition(
+                    [],
+                    None,
+                    make::module_definition(
+                        make::identifier("b"),
+                        make::module_definition_external(),
+                    ),
+                ),
+            ]),
+        ),
+    );
+    check(
+        danubec_parse::parse_definition,
+        &module,
+        "mod a {\n    const A: i32 = 1;\n    static B: i32 = 2;\n    mod b;\n}",
+    );
+
+    let tree = make::use_tree(
+        Some(make::path_segment_root()),
+        make::use_tree_element(
+            make::path_from_names(["a"]),
+            Some(
+                make::use_tree_list(
+                    true,
+                    [
+                        make::use_tree(
+                            None,
+                            make::use_tree_element(make::path_from_names(["b"]), None),
+                        ),
+                        make::use_tree(
+                            None,
+                            make::use_tree_element(
+                                make::path_from_names(["c"]),
+                                Some(make::use_tree_rename(make::identifier("d")).into()),
+                            ),
+                        ),
+                        make::use_tree(
+                            None,
+                            make::use_tree_element(
+                                make::path_from_names(["e"]),
+                                Some(make::use_tree_glob(true).into()),
+                            ),
+                        ),
+                    ],
+                )
+                .into(),
+            ),
+        ),
+    );
+    check(
+        danubec_parse::parse_definition,
+        &make::definition([], None, make::use_definition(tree)),
+        "use ::a::{b, c as d, e::*};",
+    );
+}
+
+#[test]
+fn nested_blocks() {
+    let inner =
+        make::block_expression([], [make::expression_statement([], name("b"), true).into()]);
+    let body = make::block_expression(
+        [],
+        [
+            make::expression_statement([], name("a"), true).into(),
+            make::expression_statement([], inner, false).into(),
+        ],
+    );
+    let function = make::function_definition(
+        make::identifier("f"),
+        [],
+        None,
+        make::function_body_block(body),
+    );
+    check(
+        danubec_parse::parse_definition,
+        &make::definition([], None, function),
+        "fn f() {\n    a;\n    {\n        b;\n    }\n}",
+    );
+}
+
+#[test]
+fn root() {
+    let root = make::root(
+        [make::top_level_attribute(make::nested_attribute_argument(
+            make::path_from_names(["allow"]),
+            [
+                make::key_value_attribute_argument(make::path_from_names(["dead_code"]), None)
+                    .into(),
+            ],
+        ))],
+        [
+            make::definition(
+                [],
+                None,
+                make::use_definition(make::use_tree(
+                    None,
+                    make::use_tree_element(make::path_from_names(["a"]), None),
+                )),
+            ),
+            make::definition(
+                [],
+                None,
+                make::struct_definition(make::identifier("A"), make::struct_body_unit()),
+            ),
+        ],
+    );
+    check(
+        danubec_parse::parse,
+        &root,
+        "#![allow(dead_code)]\n\nuse a;\n\nstruct A;\n",
+    );
+}
+
+#[test]
+fn add_parameter() {
+    let root = parse("fn f() {}\nfn g(a: i32) {}\nfn h(a: i32,) {}\n");
+    let parameter = || make::function_parameter([], pattern("b"), ty("u8"));
+    for definition in root.definitions() {
+        let Some(DefinitionKind::Function(function)) = definition.kind() else {
+            unreachable!();
+        };
+        function.add_parameter(parameter());
+    }
+
+    check_edit(
+        &root,
+        "fn f(b: u8) {}\nfn g(a: i32, b: u8) {}\nfn h(a: i32, b: u8,) {}\n",
+    );
+}
+
+#[test]
+fn add_parameter_trivia() {
+    let root = parse(concat!(
+        "fn f(a: i32 ) {}\n",
+        "fn g(\n    a: i32\n) {}\n",
+        "fn h(a: i32 // c\n) {}\n",
+        "mod m {\n    fn i(\n        a: i32, // d\n    ) {}\n}\n",
+    ));
+    let parameter = || make::function_parameter([], pattern("b"), ty("u8"));
+    let functions: Vec<_> = root
+        .syntax()
+        .descendants()
+        .filter_map(FunctionDefinition::cast)
+        .collect();
+    for function in functions {
+        function.add_parameter(parameter());
+    }
+
+    check_edit(
+        &root,
+        concat!(
+            "fn f(a: i32, b: u8 ) {}\n",
+            "fn g(\n    a: i32,\n    b: u8\n) {}\n",
+            "fn h(a: i32, // c\n    b: u8\n) {}\n",
+            "mod m {\n    fn i(\n        a: i32, // d\n        b: u8,\n    ) {}\n}\n",
+        ),
+    );
+}
+
+#[test]
+fn add_statement() {
+    let root = parse("fn f() {\n    if a {\n        b;\n    }\n    {}\n    { c; }\n}\n");
+    let statement = |name| make::expression_statement([], self::name(name), true).into();
+    let blocks: Vec<_> = root
+        .syntax()
+        .descendants()
+        .filter_map(BlockExpression::cast)
+        .collect();
+    for (block, name) in blocks.iter().zip(["x", "y", "z", "w"]) {
+        block.add_statement(statement(name));
+    }
+
+    check_edit(
+        &root,
+        "fn f() {\n    if a {\n        b;\n        y;\n    }\n    {\n        z;\n    }\n    { c; w; }\n    x;\n}\n",
+    );
+}
+
+#[test]
+fn add_statement_multiline() {
+    let root = parse("fn f() {\n    a;\n}\n");
+    let block = root
+        .syntax()
+        .descendants()
+        .find_map(BlockExpression::cast)
+        .unwrap();
+    let r#loop = make::loop_expression(make::block_expression(
+        [],
+        [make::expression_statement([], make::break_expression(None), true).into()],
+    ));
+    block.add_statement(make::expression_statement([], r#loop, false).into());
+
+    check_edit(
+        &root,
+        "fn f() {\n    a;\n    loop {\n        break;\n    }\n}\n",
+    );
+}
+
+#[test]
+fn add_use() {
+    let tree = |name| {
+        make::use_definition(make::use_tree(
+            None,
+            make::use_tree_element(make::path_from_names([name]), None),
+        ))
+    };
+
+    let root = parse("use a;\n\nfn f() {}\n");
+    root.add_use(tree("b"));
+    check_edit(&root, "use a;\nuse b;\n\nfn f() {}\n");
+
+    let root = parse("#![a]\n\nfn f() {}\n");
+    root.add_use(tree("b"));
+    check_edit(&root, "#![a]\n\nuse b;\n\nfn f() {}\n");
+
+    let root = parse("fn f() {}\n");
+    root.add_use(tree("b"));
+    check_edit(&root, "use b;\n\nfn f() {}\n");
+
+    let root = parse("");
+    root.add_use(tree("b"));
+    check_edit(&root, "use b;\n");
+}
+
+#[test]
+fn replace() {
+    let root = parse("fn f() {\n    a + b;\n}\n");
+    let path = root
+        .syntax()
+        .descendants()
+        .filter_map(PathExpression::cast)
+        .next()
+        .unwrap();
+    let replacement = make::block_expression(
+        [],
+        [make::expression_statement([], name("c"), false).into()],
+    );
+    edit::replace(&path, &replacement);
+
+    check_edit(&root, "fn f() {\n    {\n        c\n    } + b;\n}\n");
+}
+
+/// The messages of the reports for `source`, with the text their labels cover.
+fn reports(source: &str) -> Vec<(String, Vec<&str>)> {
+    let root = parse(source);
+    let mut diagnostic = Diagnostic::new();
+    crate::validate(&root, &mut diagnostic);
+
+    diagnostic
+        .iter()
+        .map(|report| {
+            let labels = report
+                .labels()
+                .into_iter()
+                .flatten()
+                .map(|label| &source[label.offset()..label.offset() + label.len()])
+                .collect();
+            (report.to_string(), labels)
+        })
+        .collect()
+}
+
+#[test]
+fn validate_visibility() {
+    let source = r#"
+enum A {
+    pub B,
+    C,
+}
+
+impl A {
+    pub(crate) fn f() {}
+    const D: i32 = 1;
+}
+"#;
+
+    assert_eq!(
+        reports(source),
+        [
+            ("Visibility on an enum variant".to_owned(), vec!["pub"]),
+            (
+                "Visibility on an associated definition".to_owned(),
+                vec!["pub(crate)"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn validate_mutable_patterns() {
+    let source = r#"
+fn f() {
+    let mut a = 1;
+    let mut b @ 2 = 2;
+    let (mut c, mut _) = (3, 4);
+    let mut (d, e) = (5, 6);
+    let mut mut f = 7;
+}
+"#;
+
+    assert_eq!(
+        reports(source),
+        [
+            (
+                "`mut` on a pattern that is not a binding".to_owned(),
+                vec!["mut _"]
+            ),
+            (
+                "`mut` on a pattern that is not a binding".to_owned(),
+                vec!["mut (d, e)"]
+            ),
+            (
+                "`mut` on a pattern that is not a binding".to_owned(),
+                vec!["mut mut f"]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn validate_loops() {
+    let source = r#"
+fn f() {
+    loop {
+        if a {
+            break;
+        }
+        fn g() {
+            continue;
+        }
+    }
+    while (break) {}
+    for a in b {
+        continue;
+    }
+    break 1;
+}
+"#;
+
+    assert_eq!(
+        reports(source),
+        [
+            ("`continue` outside of a loop".to_owned(), vec!["continue"]),
+            ("`break` outside of a loop".to_owned(), vec!["break"]),
+            ("`break` outside of a loop".to_owned(), vec!["break 1"]),
+        ]
+    );
+}
+
+#[test]
+fn validate_self_parameters() {
+    let source = r#"
+fn f(self: A) {}
+
+trait B {
+    fn g(self: B);
+}
+
+impl B for A {
+    fn g(mut self: A) {
+        fn h(a: i32, self: A) {}
+    }
+}
+"#;
+
+    assert_eq!(
+        reports(source),
+        [
+            (
+                "`self` parameter in a function that is not associated".to_owned(),
+                vec!["self: A"],
+            ),
+            (
+                "`self` parameter in a function that is not associated".to_owned(),
+                vec!["self: A"],
+            ),
+        ]
+    );
+}
+
+#[test]
+fn validate_attributes() {
+    let source = r#"
+#[a]
+struct A {
+    #[b]
+    c: i32,
+}
+
+#[d]
+fn f(#[e] a: i32) {
+    #[f]
+    {}
+}
+"#;
+
+    // Where each attribute may go is up to the attribute, see `danubec_semantic`.
+    assert_eq!(reports(source), []);
+}
+
+#[test]
+fn validate_duplicate_fields() {
+    let source = r#"
+struct A {
+    a: i32,
+    b: i32,
+    r#a: i32,
+}
+
+enum B {
+    C { d: i32, d: i32 },
+}
+"#;
+
+    assert_eq!(
+        reports(source),
+        [
+            ("Duplicate field `a`".to_owned(), vec!["a", "r#a"]),
+            ("Duplicate field `d`".to_owned(), vec!["d", "d"]),
+        ]
+    );
+}
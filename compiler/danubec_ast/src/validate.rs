@@ -0,0 +1,188 @@
+//! Checks that are easier to make on a parsed tree than while parsing.
+
+use crate::*;
+use danubec_diagnostic::Diagnostic;
+use miette::LabeledSpan;
+use rowan::{NodeOrToken, ast::AstNode};
+use std::{collections::HashMap, ops::Range};
+
+/// Reports the constructs in `root` that parse but are not allowed where they
+/// are written.
+pub fn validate(root: &Root, diagnostic: &mut Diagnostic) {
+    for node in root.syntax().descendants() {
+        if let Some(node) = AssociatedDefinition::cast(node.clone()) {
+            if let Some(visibility) = node.visibility() {
+                report(
+                    diagnostic,
+                    visibility.syntax(),
+                    "Visibility on an associated definition",
+                );
+            }
+        } else if let Some(node) = EnumVariant::cast(node.clone()) {
+            if let Some(visibility) = variant_visibility(&node) {
+                report(
+                    diagnostic,
+                    visibility.syntax(),
+                    "Visibility on an enum variant",
+                );
+            }
+            if let EnumVariant::Named(node) = node {
+                duplicates(diagnostic, node.fields().filter_map(|field| field.name()));
+            }
+        } else if let Some(node) = MutablePattern::cast(node.clone()) {
+            if !node.pattern().is_some_and(|pattern| binding(&pattern)) {
+                report(
+                    diagnostic,
+                    node.syntax(),
+                    "`mut` on a pattern that is not a binding",
+                );
+            }
+        } else if let Some(node) = BreakExpression::cast(node.clone()) {
+            if !in_loop(node.syntax()) {
+                report(diagnostic, node.syntax(), "`break` outside of a loop");
+            }
+        } else if let Some(node) = ContinueExpression::cast(node.clone()) {
+            if !in_loop(node.syntax()) {
+                report(diagnostic, node.syntax(), "`continue` outside of a loop");
+            }
+        } else if let Some(node) = FunctionParameter::cast(node.clone()) {
+            if self_parameter(&node) && !associated(&node) {
+                report(
+                    diagnostic,
+                    node.syntax(),
+                    "`self` parameter in a function that is not associated",
+                );
+            }
+        } else if let Some(node) = StructNamed::cast(node) {
+            duplicates(diagnostic, node.fields().filter_map(|field| field.name()));
+        }
+    }
+}
+
+fn variant_visibility(node: &EnumVariant) -> Option<Visibility> {
+    match node {
+        EnumVariant::Unit(node) => node.visibility(),
+        EnumVariant::Scalar(node) => node.visibility(),
+        EnumVariant::Named(node) => node.visibility(),
+        EnumVariant::Unnamed(node) => node.visibility(),
+    }
+}
+
+/// Whether `pattern` binds a name: `a`, `self` or `a @ pattern`.
+fn binding(pattern: &Pattern) -> bool {
+    match pattern {
+        Pattern::Path(pattern) => matches!(
+            single_segment(pattern),
+            Some(PathSegment::Identifier(_) | PathSegment::Self_(_))
+        ),
+        Pattern::At(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether `node` is in the body of a loop in the same definition.
+fn in_loop(node: &SyntaxNode) -> bool {
+    let mut child = node.clone();
+    for ancestor in node.ancestors().skip(1) {
+        let body = match ancestor.kind() {
+            SyntaxKind::DEFINITION_NODE | SyntaxKind::ASSOCIATED_DEFINITION_NODE => return false,
+            SyntaxKind::FOR_EXPRESSION_NODE => {
+                ForExpression::cast(ancestor.clone()).and_then(|node| node.body())
+            }
+            SyntaxKind::WHILE_EXPRESSION_NODE => {
+                WhileExpression::cast(ancestor.clone()).and_then(|node| node.body())
+            }
+            SyntaxKind::LOOP_EXPRESSION_NODE => {
+                LoopExpression::cast(ancestor.clone()).and_then(|node| node.body())
+            }
+            _ => None,
+        };
+        if body.is_some_and(|body| *body.syntax() == child) {
+            return true;
+        }
+        child = ancestor;
+    }
+
+    false
+}
+
+/// Whether `node` is `self: Type` or `mut self: Type`.
+fn self_parameter(node: &FunctionParameter) -> bool {
+    let pattern = match node.pattern() {
+        Some(Pattern::Mutable(pattern)) => pattern.pattern(),
+        pattern => pattern,
+    };
+    let Some(Pattern::Path(pattern)) = pattern else {
+        return false;
+    };
+
+    matches!(single_segment(&pattern), Some(PathSegment::Self_(_)))
+}
+
+/// The segment of the path in `pattern`, if it has only one.
+fn single_segment(pattern: &PathPattern) -> Option<PathSegment> {
+    let path = pattern.path()?;
+    let mut segments = path.segments();
+
+    match (segments.next(), segments.next()) {
+        (Some(segment), None) => Some(segment),
+        _ => None,
+    }
+}
+
+/// Whether `node` is a parameter of a function in a trait or an impl block.
+fn associated(node: &FunctionParameter) -> bool {
+    node.syntax()
+        .parent()
+        .and_then(|function| function.parent())
+        .is_some_and(|definition| definition.kind() == SyntaxKind::ASSOCIATED_DEFINITION_NODE)
+}
+
+/// Reports the names given more than once among `names`.
+fn duplicates(diagnostic: &mut Diagnostic, names: impl Iterator<Item = Identifier>) {
+    let mut seen: HashMap<String, Range<usize>> = HashMap::new();
+    for name in names {
+        let Some(text) = name
+            .segment()
+            .and_then(|segment| segment.identifier())
+            .map(|token| token.text().to_owned())
+        else {
+            continue;
+        };
+        let span = span(name.syntax());
+        match seen.get(&text) {
+            Some(first) => diagnostic.report(miette!(
+                labels = vec![
+                    LabeledSpan::at(first.clone(), "first defined here"),
+                    LabeledSpan::at(span, "defined again here"),
+                ],
+                "Duplicate field `{}`",
+                text
+            )),
+            None => {
+                seen.insert(text, span);
+            }
+        }
+    }
+}
+
+fn report(diagnostic: &mut Diagnostic, node: &SyntaxNode, message: &str) {
+    diagnostic.report(miette!(
+        labels = vec![LabeledSpan::underline(span(node))],
+        "{}",
+        message
+    ));
+}
+
+/// The range of `node` without its trailing trivia.
+fn span(node: &SyntaxNode) -> Range<usize> {
+    let range = node.text_range();
+    let end = node
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| !token.kind().at_trivia())
+        .last()
+        .map_or(range.end(), |token| token.text_range().end());
+
+    usize::from(range.start())..usize::from(end)
+}
@@ -4,6 +4,12 @@ pub struct Diagnostic {
     reports: Vec<miette::Report>,
 }
 
+impl Default for Diagnostic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Diagnostic {
     pub const fn new() -> Self {
         Self { reports: vec![] }
@@ -13,9 +19,30 @@ impl Diagnostic {
         self.reports.push(report);
     }
 
+    pub const fn len(&self) -> usize {
+        self.reports.len()
+    }
+
     pub const fn is_empty(&self) -> bool {
         self.reports.is_empty()
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = &miette::Report> {
+        self.reports.iter()
+    }
+
+    /// Gives the reports from the `start`-th on the source their labels point into.
+    pub fn with_source_code<S>(&mut self, start: usize, source: S)
+    where
+        S: miette::SourceCode + Clone + Send + Sync + 'static,
+    {
+        let reports: Vec<_> = self.reports.drain(start..).collect();
+        self.reports.extend(
+            reports
+                .into_iter()
+                .map(|report| report.with_source_code(source.clone())),
+        );
+    }
 }
 
 impl std::fmt::Debug for Diagnostic {
@@ -0,0 +1,518 @@
+#[macro_use]
+extern crate miette;
+
+#[cfg(test)]
+mod tests;
+
+use danubec_diagnostic::Diagnostic;
+use danubec_syntax::{
+    SyntaxKind::{self, *},
+    SyntaxNode, SyntaxToken,
+};
+use rowan::{NodeOrToken, TextRange, TextSize};
+use std::collections::HashSet;
+
+const INDENT: &str = "    ";
+
+/// Braces whose contents are laid out one item per line.
+const VERTICAL: [SyntaxKind; 7] = [
+    BLOCK_EXPRESSION_NODE,
+    ENUM_DEFINITION_NODE,
+    IMPLEMENT_DEFINITION_NODE,
+    MATCH_EXPRESSION_NODE,
+    MODULE_DEFINITION_INLINE_NODE,
+    STRUCT_BODY_NAMED_NODE,
+    TRAIT_DEFINITION_NODE,
+];
+
+/// Vertical braces whose items are separated by commas.
+const LIST: [SyntaxKind; 3] = [
+    ENUM_DEFINITION_NODE,
+    MATCH_EXPRESSION_NODE,
+    STRUCT_BODY_NAMED_NODE,
+];
+
+/// Literals are printed verbatim.
+const LITERAL: [SyntaxKind; 10] = [
+    CHARACTER_LITERAL_NODE,
+    STRING_LITERAL_NODE,
+    BYTE_LITERAL_NODE,
+    BYTE_STRING_LITERAL_NODE,
+    C_STRING_LITERAL_NODE,
+    INTEGER_LITERAL_NODE,
+    FLOAT_LITERAL_NODE,
+    BINARY_NUMERIC_LITERAL_NODE,
+    OCTAL_NUMERIC_LITERAL_NODE,
+    HEX_NUMERIC_LITERAL_NODE,
+];
+
+pub fn format(source: &str) -> Result<String, Diagnostic> {
+    let mut diagnostic = Diagnostic::new();
+    let node = danubec_parse::parse(source, &mut diagnostic);
+    if !diagnostic.is_empty() {
+        return Err(diagnostic);
+    }
+
+    let erroneous = node
+        .descendants_with_tokens()
+        .any(|element| matches!(element.kind(), ERROR | ERROR_NODE));
+    if erroneous || node.text() != source {
+        diagnostic.report(miette!("Cannot format source with syntax errors"));
+        return Err(diagnostic);
+    }
+
+    Ok(format_node(&node))
+}
+
+pub fn check(source: &str) -> Result<bool, Diagnostic> {
+    Ok(format(source)? == source)
+}
+
+pub fn format_node(node: &SyntaxNode) -> String {
+    let atoms = atoms(node);
+    let line_ends = line_ends(node);
+
+    Printer::new(&atoms, line_ends).print()
+}
+
+#[derive(Debug)]
+struct Atom {
+    kind: SyntaxKind,
+    text: String,
+    parent: Option<SyntaxNode>,
+    operator: Option<TextRange>,
+    first_colon: bool,
+    second_colon: bool,
+    newlines: usize,
+    end: TextSize,
+}
+
+impl Atom {
+    fn new(kind: SyntaxKind, text: String, parent: Option<SyntaxNode>, end: TextSize) -> Self {
+        Self {
+            kind,
+            text,
+            parent,
+            operator: None,
+            first_colon: false,
+            second_colon: false,
+            newlines: 0,
+            end,
+        }
+    }
+
+    fn parent_kind(&self) -> Option<SyntaxKind> {
+        self.parent.as_ref().map(SyntaxNode::kind)
+    }
+
+    fn is_comment(&self) -> bool {
+        matches!(self.kind, LINE_COMMENT_START | DOC_COMMENT_START)
+    }
+
+    fn is_vertical(&self, kind: SyntaxKind) -> bool {
+        self.kind == kind
+            && self
+                .parent_kind()
+                .is_some_and(|kind| VERTICAL.contains(&kind))
+    }
+
+    fn is_list(&self, kind: SyntaxKind) -> bool {
+        self.kind == kind && self.parent_kind().is_some_and(|kind| LIST.contains(&kind))
+    }
+
+    fn is_in(&self, kind: SyntaxKind) -> bool {
+        self.parent_kind() == Some(kind)
+    }
+
+    fn is_sibling(&self, other: &Atom) -> bool {
+        self.parent.is_some() && self.parent == other.parent
+    }
+}
+
+fn atoms(root: &SyntaxNode) -> Vec<Atom> {
+    let mut atoms: Vec<Atom> = vec![];
+    let mut newlines = 0;
+    let mut previous = None;
+    let mut literal: Option<(SyntaxNode, TextRange)> = None;
+
+    for token in root
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+    {
+        let kind = token.kind();
+        let range = token.text_range();
+
+        if let Some(node) = literal_ancestor(&token) {
+            let core = match &literal {
+                Some((cached, core)) if *cached == node => *core,
+                _ => {
+                    let core = core_range(&node);
+                    literal = Some((node.clone(), core));
+                    core
+                }
+            };
+
+            if core.contains_range(range) {
+                if range.start() == core.start() {
+                    let text = root.text().slice(core).to_string();
+                    let mut atom = Atom::new(node.kind(), text, node.parent(), core.end());
+                    atom.newlines = std::mem::take(&mut newlines);
+                    atoms.push(atom);
+                }
+
+                previous = Some(kind);
+                continue;
+            }
+        }
+
+        match kind {
+            WHITESPACE | TAB => {
+                //
+            }
+            NEW_LINE => newlines += 1,
+            LINE_COMMENT_START | DOC_COMMENT_START => {
+                let mut atom = Atom::new(kind, token.text().to_owned(), None, range.end());
+                atom.newlines = std::mem::take(&mut newlines);
+                atoms.push(atom);
+            }
+            LINE_COMMENT_SEGMENT | DOC_COMMENT_SEGMENT => {
+                if let Some(atom) = atoms.last_mut() {
+                    atom.text.push_str(token.text());
+                    atom.end = range.end();
+                }
+            }
+            _ => {
+                let mut atom =
+                    Atom::new(kind, token.text().to_owned(), token.parent(), range.end());
+                atom.newlines = std::mem::take(&mut newlines);
+                atom.operator = token
+                    .parent_ancestors()
+                    .find(|node| {
+                        matches!(node.kind(), BINARY_OPERATOR_NODE | ASSIGNMENT_OPERATOR_NODE)
+                    })
+                    .map(|node| node.text_range());
+
+                if kind == COLON
+                    && previous == Some(COLON)
+                    && let Some(last) = atoms.last_mut()
+                    && !last.second_colon
+                {
+                    last.first_colon = true;
+                    atom.second_colon = true;
+                }
+
+                atoms.push(atom);
+            }
+        }
+
+        previous = Some(kind);
+    }
+
+    for atom in &mut atoms {
+        if atom.is_comment() {
+            atom.text.truncate(atom.text.trim_end().len());
+        }
+    }
+
+    atoms
+}
+
+fn literal_ancestor(token: &SyntaxToken) -> Option<SyntaxNode> {
+    token
+        .parent_ancestors()
+        .filter(|node| LITERAL.contains(&node.kind()))
+        .last()
+}
+
+fn core_range(node: &SyntaxNode) -> TextRange {
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(NodeOrToken::into_token)
+        .filter(|token| !token.kind().at_trivia())
+        .map(|token| token.text_range());
+
+    match tokens.next() {
+        Some(first) => first.cover(tokens.last().unwrap_or(first)),
+        None => TextRange::empty(node.text_range().start()),
+    }
+}
+
+fn line_ends(root: &SyntaxNode) -> HashSet<TextSize> {
+    root.descendants()
+        .filter(breaks_after)
+        .filter_map(|node| last_significant(&node))
+        .collect()
+}
+
+fn breaks_after(node: &SyntaxNode) -> bool {
+    match node.kind() {
+        TOP_LEVEL_ATTRIBUTE_NODE
+        | DEFINITION_NODE
+        | ASSOCIATED_DEFINITION_NODE
+        | DEFINITION_STATEMENT_NODE
+        | EXPRESSION_STATEMENT_NODE
+        | LET_STATEMENT_NODE
+        | SEMICOLON_STATEMENT_NODE => true,
+        ATTRIBUTE_NODE => !matches!(
+            node.parent().map(|parent| parent.kind()),
+            Some(
+                FUNCTION_PARAMETER_NODE
+                    | STRUCT_BODY_UNNAMED_FIELD_NODE
+                    | ENUM_VARIANT_NAMED_FIELD_NODE
+                    | BLOCK_EXPRESSION_NODE
+            )
+        ),
+        _ => false,
+    }
+}
+
+fn last_significant(node: &SyntaxNode) -> Option<TextSize> {
+    let mut token = node.last_token()?;
+    while token.kind().at_trivia() {
+        token = token.prev_token()?;
+    }
+
+    let range = token.text_range();
+    node.text_range()
+        .contains_range(range)
+        .then_some(range.end())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Separator {
+    Glue,
+    Space,
+    Line,
+    BlankLine,
+}
+
+struct Printer<'atom> {
+    atoms: &'atom [Atom],
+    line_ends: HashSet<TextSize>,
+    output: String,
+    depth: usize,
+    /// The depth at which each open parenthesis, bracket or inline brace was
+    /// opened, and whether a line has been broken inside it.
+    delimiters: Vec<(usize, bool)>,
+}
+
+impl<'atom> Printer<'atom> {
+    fn new(atoms: &'atom [Atom], line_ends: HashSet<TextSize>) -> Self {
+        Self {
+            atoms,
+            line_ends,
+            output: String::new(),
+            depth: 0,
+            delimiters: vec![],
+        }
+    }
+
+    fn print(mut self) -> String {
+        let mut comma = false;
+        for (index, atom) in self.atoms.iter().enumerate() {
+            if index > 0 {
+                let separator = self.separator(index, comma);
+                if self.closes_block(index) {
+                    self.depth -= 1;
+                }
+                if closes_delimiter(atom) {
+                    self.delimiters.pop();
+                }
+                self.push(separator);
+            }
+
+            self.output.push_str(&atom.text);
+
+            if self.opens_block(index) {
+                self.depth += 1;
+            }
+            if opens_delimiter(atom) {
+                self.delimiters.push((self.depth, false));
+            }
+
+            comma = self.needs_comma(index);
+            if comma {
+                self.output.push(',');
+            }
+        }
+
+        if !self.output.is_empty() {
+            self.output.push('\n');
+        }
+
+        self.output
+    }
+
+    fn push(&mut self, separator: Separator) {
+        match separator {
+            Separator::Glue => {
+                //
+            }
+            Separator::Space => self.output.push(' '),
+            Separator::Line | Separator::BlankLine => {
+                if separator == Separator::BlankLine {
+                    self.output.push('\n');
+                }
+                self.output.push('\n');
+                // Lines broken inside delimiters, after a comment, are
+                // continuations of the line the delimiters are opened on.
+                for (depth, broken) in &mut self.delimiters {
+                    *broken |= *depth == self.depth;
+                }
+                let continuation = self
+                    .delimiters
+                    .iter()
+                    .filter(|(_, broken)| *broken)
+                    .count();
+                for _ in 0..self.depth + continuation {
+                    self.output.push_str(INDENT);
+                }
+            }
+        }
+    }
+
+    fn opens_block(&self, index: usize) -> bool {
+        let atom = &self.atoms[index];
+        if !atom.is_vertical(LEFT_BRACE) {
+            return false;
+        }
+
+        match self.atoms.get(index + 1) {
+            Some(next) => !(next.kind == RIGHT_BRACE && next.is_sibling(atom)),
+            None => true,
+        }
+    }
+
+    fn closes_block(&self, index: usize) -> bool {
+        let atom = &self.atoms[index];
+        if !atom.is_vertical(RIGHT_BRACE) {
+            return false;
+        }
+
+        match index.checked_sub(1).map(|index| &self.atoms[index]) {
+            Some(previous) => !(previous.kind == LEFT_BRACE && previous.is_sibling(atom)),
+            None => true,
+        }
+    }
+
+    fn needs_comma(&self, index: usize) -> bool {
+        let atom = &self.atoms[index];
+        if atom.is_comment() || atom.kind == COMMA {
+            return false;
+        }
+
+        let next = self.atoms[index + 1..]
+            .iter()
+            .find(|atom| !atom.is_comment());
+        match next {
+            Some(next) if next.is_list(RIGHT_BRACE) => {
+                !(atom.kind == LEFT_BRACE && atom.is_sibling(next))
+            }
+            _ => false,
+        }
+    }
+
+    fn separator(&self, index: usize, comma: bool) -> Separator {
+        let previous = &self.atoms[index - 1];
+        let current = &self.atoms[index];
+        let line = if current.newlines > 1 {
+            Separator::BlankLine
+        } else {
+            Separator::Line
+        };
+
+        if current.is_comment() {
+            return match current.newlines {
+                0 => Separator::Space,
+                _ if self.opens_block(index - 1) => Separator::Line,
+                _ => line,
+            };
+        }
+        if previous.is_comment() {
+            return if self.closes_block(index) {
+                Separator::Line
+            } else {
+                line
+            };
+        }
+        if previous.is_vertical(LEFT_BRACE) {
+            return if self.opens_block(index - 1) {
+                Separator::Line
+            } else {
+                Separator::Glue
+            };
+        }
+        if self.closes_block(index) {
+            return Separator::Line;
+        }
+        if comma || previous.is_list(COMMA) || self.line_ends.contains(&previous.end) {
+            return line;
+        }
+
+        inline(previous, current)
+    }
+}
+
+fn opens_delimiter(atom: &Atom) -> bool {
+    match atom.kind {
+        LEFT_PAREN | LEFT_BRACKET => true,
+        LEFT_BRACE => !atom.is_vertical(LEFT_BRACE),
+        _ => false,
+    }
+}
+
+fn closes_delimiter(atom: &Atom) -> bool {
+    match atom.kind {
+        RIGHT_PAREN | RIGHT_BRACKET => true,
+        RIGHT_BRACE => !atom.is_vertical(RIGHT_BRACE),
+        _ => false,
+    }
+}
+
+fn inline(previous: &Atom, current: &Atom) -> Separator {
+    // `::` never has spaces inside, and only a leading one is spaced from what precedes it.
+    if previous.second_colon || current.second_colon {
+        return Separator::Glue;
+    }
+    if current.kind == COLON && !(current.first_colon && current.is_in(PATH_SEGMENT_ROOT_NODE)) {
+        return Separator::Glue;
+    }
+    if previous.operator.is_some() && previous.operator == current.operator {
+        return Separator::Glue;
+    }
+
+    let glue_after = match previous.kind {
+        LEFT_PAREN | LEFT_BRACKET | DOT | HASH | RAW_IDENTIFIER_START => true,
+        MUT => false,
+        _ if previous.is_in(UNARY_OPERATOR_NODE) => true,
+        EXCLAMATION => previous.is_in(TOP_LEVEL_ATTRIBUTE_NODE),
+        HYPHEN => previous.is_in(FUNCTION_DEFINITION_NODE),
+        EQUAL => previous.is_in(MATCH_ARM_NODE),
+        LEFT_CHEVRON => previous.is_in(PATH_TYPE_NODE),
+        LEFT_BRACE => previous.is_in(USE_TREE_LIST_NODE),
+        _ => false,
+    };
+    if glue_after {
+        return Separator::Glue;
+    }
+
+    let glue_before = match current.kind {
+        COMMA | SEMICOLON | RIGHT_PAREN | RIGHT_BRACKET | DOT | QUESTION => true,
+        RIGHT_BRACE => {
+            current.is_in(USE_TREE_LIST_NODE)
+                || (previous.kind == LEFT_BRACE && previous.is_sibling(current))
+        }
+        LEFT_CHEVRON | RIGHT_CHEVRON => current.is_in(PATH_TYPE_NODE),
+        LEFT_PAREN | LEFT_BRACKET => match previous.kind {
+            IDENTIFIER | SELF | SUPER | CRATE | PUB | RIGHT_PAREN | RIGHT_BRACKET => true,
+            RIGHT_CHEVRON => previous.is_in(PATH_TYPE_NODE),
+            _ => false,
+        },
+        _ => false,
+    };
+    if glue_before {
+        return Separator::Glue;
+    }
+
+    Separator::Space
+}
@@ -0,0 +1,121 @@
+fn format(source: &str) -> String {
+    let formatted = match crate::format(source) {
+        Ok(formatted) => formatted,
+        Err(diagnostic) => panic!("{:?}", diagnostic),
+    };
+
+    assert_eq!(crate::format(&formatted).ok().as_ref(), Some(&formatted));
+
+    formatted
+}
+
+#[test]
+fn top_level_attribute() {
+    let source = r#"
+#![a( "value" )]
+#![ a(key1,key2) ]
+#![a :: b(key = "value")]
+"#;
+
+    insta::assert_snapshot!(format(source));
+}
+
+#[test]
+fn definitions() {
+    let source = r#"
+use   ::{ foo , bar::{ baz as qux , * } };
+mod   foo ;
+mod bar{struct Foo;
+
+
+fn baz  ( ) { }
+}
+#[a("value")] struct Foo { a : i32 , #[b] b : String }
+struct Bar ( usize , ( ) );
+enum Baz { A , B { a : i32 } , C ( Foo , Bar ) , D = 42 }
+trait Qux { fn foo ( ) ; const BAR : i32 ; type Baz ; }
+impl Qux for Foo { fn foo ( ) { } }
+const FOO : i32 = 42 ;
+static BAR : Vec :: < i32 > = 42 ;
+type Baz = ( i32 , String ) ;
+fn foo ( a : i32 , b : String ) -> ( i32 , String ) { ( a , b ) }
+"#;
+
+    insta::assert_snapshot!(format(source));
+}
+
+#[test]
+fn expressions() {
+    let source = r#"
+fn main() {
+    let mut x : i32 = - 42 ;
+    let r#y = mut ~ x ;
+    x += 1 ; x = x*2+!true;
+    foo . bar ( 1 , "a${ b }c" ) ? . await ;
+    let a = [ 0 , 1 ] [ 0 ] ;
+    if x == 42 { return ; } else if x != 0 { break 1 } else { continue }
+    match x { 0 => { } , 1 | 2 => a , y @ 3 => b , _ => c }
+    while true { loop { } }
+    for x in xs { ; }
+    { 42 } ;
+}
+"#;
+
+    insta::assert_snapshot!(format(source));
+}
+
+#[test]
+fn comments() {
+    let source = r#"
+// leading comment
+/// documentation
+struct Foo; // trailing comment
+
+mod qux { //
+}
+
+enum Bar {
+    A, // first
+    // before second
+    B // second
+}
+"#;
+
+    insta::assert_snapshot!(format(source));
+}
+
+#[test]
+fn comments_in_delimiters() {
+    let source = r#"
+fn main() {
+    foo(a, // first
+    b);
+    let c = [1, // second
+    2, {
+        bar(d // third
+        )
+    }];
+}
+"#;
+
+    insta::assert_snapshot!(format(source));
+}
+
+#[test]
+fn blank_lines() {
+    let source = "\n\n\nstruct Foo;\n\n\n\nstruct Bar;\nstruct Baz;\n\n\n";
+
+    insta::assert_snapshot!(format(source));
+}
+
+#[test]
+fn syntax_error() {
+    assert!(crate::format("struct Foo").is_err());
+    assert!(crate::format("fn foo() { let }").is_err());
+}
+
+#[test]
+fn check() {
+    assert_eq!(crate::check("struct Foo;\n").ok(), Some(true));
+    assert_eq!(crate::check("struct  Foo;").ok(), Some(false));
+}
@@ -0,0 +1,448 @@
+#![warn(clippy::all)]
+
+#[cfg(test)]
+mod tests;
+
+pub mod sourcegen;
+pub mod validate;
+
+pub use validate::*;
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::OnceLock,
+};
+
+/// The grammar in `danube.ungram`.
+pub fn grammar() -> &'static Grammar {
+    static GRAMMAR: OnceLock<Grammar> = OnceLock::new();
+
+    GRAMMAR.get_or_init(|| {
+        let source = include_str!("../danube.ungram");
+
+        Grammar::parse(source).unwrap_or_else(|error| panic!("danube.ungram: {error}"))
+    })
+}
+
+/// The rules of a grammar, in the order they are written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grammar {
+    pub rules: Vec<Rule>,
+    kinds: HashMap<String, HashSet<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub name: String,
+    pub docs: Vec<String>,
+    pub body: RuleBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleBody {
+    /// A node of kind `kind`, whose children match `pattern`.
+    Struct { kind: String, pattern: Pattern },
+    /// Any of the `(variant, rule)` nodes.
+    Enum { variants: Vec<(String, String)> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    Node { label: Option<String>, rule: String },
+    Token { label: Option<String>, kind: String },
+    Sequence(Vec<Pattern>),
+    Choice(Vec<Pattern>),
+    Optional(Box<Pattern>),
+    Repeat(Box<Pattern>),
+}
+
+impl Grammar {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, index: 0 };
+
+        let mut rules = vec![];
+        while !parser.at_end() {
+            rules.push(parser.rule()?);
+        }
+
+        Self::new(rules)
+    }
+
+    fn new(rules: Vec<Rule>) -> Result<Self, String> {
+        let mut names = HashSet::new();
+        let mut kinds = HashSet::new();
+        for rule in &rules {
+            if !names.insert(rule.name.as_str()) {
+                return Err(format!("`{}` is defined twice", rule.name));
+            }
+            if let RuleBody::Struct { kind, .. } = &rule.body
+                && !kinds.insert(kind.as_str())
+            {
+                return Err(format!("`{kind}` is the kind of two rules"));
+            }
+        }
+        for rule in &rules {
+            for name in rule.references() {
+                if !names.contains(name) {
+                    return Err(format!("`{}` refers to undefined `{name}`", rule.name));
+                }
+            }
+        }
+
+        let mut grammar = Self {
+            rules,
+            kinds: HashMap::new(),
+        };
+        for rule in &grammar.rules {
+            let mut kinds = HashSet::new();
+            grammar.collect_kinds(&rule.name, &mut kinds, &mut vec![])?;
+            grammar.kinds.insert(rule.name.clone(), kinds);
+        }
+
+        Ok(grammar)
+    }
+
+    fn collect_kinds<'a>(
+        &'a self,
+        name: &'a str,
+        kinds: &mut HashSet<String>,
+        stack: &mut Vec<&'a str>,
+    ) -> Result<(), String> {
+        if stack.contains(&name) {
+            return Err(format!("`{name}` is a variant of itself"));
+        }
+        stack.push(name);
+
+        match &self.rule(name).expect("rules are checked").body {
+            RuleBody::Struct { kind, .. } => {
+                kinds.insert(kind.clone());
+            }
+            RuleBody::Enum { variants } => {
+                for (_, rule) in variants {
+                    self.collect_kinds(rule, kinds, stack)?;
+                }
+            }
+        }
+
+        stack.pop();
+
+        Ok(())
+    }
+
+    pub fn rule(&self, name: &str) -> Option<&Rule> {
+        self.rules.iter().find(|rule| rule.name == name)
+    }
+
+    /// The struct rule describing nodes of `kind`.
+    pub fn rule_for_kind(&self, kind: &str) -> Option<&Rule> {
+        self.rules
+            .iter()
+            .find(|rule| matches!(&rule.body, RuleBody::Struct { kind: k, .. } if k == kind))
+    }
+
+    /// The node kinds a node described by `name` may have.
+    pub fn kinds(&self, name: &str) -> &HashSet<String> {
+        &self.kinds[name]
+    }
+}
+
+impl Rule {
+    fn references(&self) -> Vec<&str> {
+        match &self.body {
+            RuleBody::Struct { pattern, .. } => {
+                let mut names = vec![];
+                pattern.visit(&mut |pattern| {
+                    if let Pattern::Node { rule, .. } = pattern {
+                        names.push(rule.as_str());
+                    }
+                });
+                names
+            }
+            RuleBody::Enum { variants } => variants.iter().map(|(_, rule)| rule.as_str()).collect(),
+        }
+    }
+}
+
+impl Pattern {
+    /// Calls `f` on every node and token in the pattern.
+    pub fn visit<'a>(&'a self, f: &mut impl FnMut(&'a Pattern)) {
+        match self {
+            Pattern::Node { .. } | Pattern::Token { .. } => f(self),
+            Pattern::Sequence(patterns) | Pattern::Choice(patterns) => {
+                for pattern in patterns {
+                    pattern.visit(f);
+                }
+            }
+            Pattern::Optional(pattern) | Pattern::Repeat(pattern) => pattern.visit(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Kind(String),
+    Doc(String),
+    Equal,
+    Colon,
+    Pipe,
+    Star,
+    Question,
+    LeftParen,
+    RightParen,
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, String> {
+    let mut tokens = vec![];
+    for (line, text) in source.lines().enumerate() {
+        let line = line + 1;
+        let trimmed = text.trim_start();
+        if let Some(doc) = trimmed.strip_prefix("///") {
+            tokens.push((
+                Token::Doc(doc.strip_prefix(' ').unwrap_or(doc).to_owned()),
+                line,
+            ));
+            continue;
+        }
+
+        let mut chars = text.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let token = match c {
+                _ if c.is_whitespace() => continue,
+                '/' if text[start..].starts_with("//") => break,
+                '=' => Token::Equal,
+                ':' => Token::Colon,
+                '|' => Token::Pipe,
+                '*' => Token::Star,
+                '?' => Token::Question,
+                '(' => Token::LeftParen,
+                ')' => Token::RightParen,
+                '\'' => {
+                    let end = text[start + 1..]
+                        .find('\'')
+                        .ok_or_else(|| format!("line {line}: unterminated token kind"))?;
+                    for _ in 0..=end {
+                        chars.next();
+                    }
+                    Token::Kind(text[start + 1..start + 1 + end].to_owned())
+                }
+                _ if c.is_ascii_alphabetic() || c == '_' => {
+                    let mut end = start + 1;
+                    while let Some(&(index, c)) = chars.peek() {
+                        if !(c.is_ascii_alphanumeric() || c == '_') {
+                            break;
+                        }
+                        end = index + 1;
+                        chars.next();
+                    }
+                    Token::Ident(text[start..end].to_owned())
+                }
+                _ => return Err(format!("line {line}: unexpected `{c}`")),
+            };
+            tokens.push((token, line));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+}
+
+impl Parser {
+    fn at_end(&self) -> bool {
+        self.index == self.tokens.len()
+    }
+
+    fn nth(&self, n: usize) -> Option<&Token> {
+        self.tokens.get(self.index + n).map(|(token, _)| token)
+    }
+
+    fn error(&self, message: &str) -> String {
+        match self.tokens.get(self.index) {
+            Some((token, line)) => format!("line {line}: {message}, found {token:?}"),
+            None => format!("{message}, found the end of the file"),
+        }
+    }
+
+    fn eat(&mut self, token: Token) -> bool {
+        if self.nth(0) == Some(&token) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn ident(&mut self) -> Result<String, String> {
+        match self.nth(0) {
+            Some(Token::Ident(ident)) => {
+                let ident = ident.clone();
+                self.index += 1;
+                Ok(ident)
+            }
+            _ => Err(self.error("Expected a name")),
+        }
+    }
+
+    /// Whether a rule starts here: `Name =` or `Name(KIND) =`.
+    fn at_rule(&self) -> bool {
+        match (self.nth(0), self.nth(1)) {
+            (Some(Token::Doc(_)), _) => true,
+            (Some(Token::Ident(_)), Some(Token::Equal)) => true,
+            (Some(Token::Ident(_)), Some(Token::LeftParen)) => matches!(
+                (self.nth(2), self.nth(3), self.nth(4)),
+                (
+                    Some(Token::Ident(_)),
+                    Some(Token::RightParen),
+                    Some(Token::Equal)
+                )
+            ),
+            _ => false,
+        }
+    }
+
+    fn rule(&mut self) -> Result<Rule, String> {
+        let mut docs = vec![];
+        while let Some(Token::Doc(doc)) = self.nth(0) {
+            docs.push(doc.clone());
+            self.index += 1;
+        }
+
+        let name = self.ident()?;
+        let kind = if self.eat(Token::LeftParen) {
+            let kind = self.ident()?;
+            if !self.eat(Token::RightParen) {
+                return Err(self.error("Expected `)`"));
+            }
+            Some(kind)
+        } else {
+            None
+        };
+        if !self.eat(Token::Equal) {
+            return Err(self.error("Expected `=`"));
+        }
+
+        let pattern = self.choice()?;
+        let body = match enum_variants(&pattern) {
+            Some(variants) if kind.is_none() => RuleBody::Enum { variants },
+            Some(_) => return Err(format!("`{name}` is an enum, which has no kind")),
+            None => RuleBody::Struct {
+                kind: kind.unwrap_or_else(|| format!("{}_NODE", screaming_snake_case(&name))),
+                pattern,
+            },
+        };
+
+        Ok(Rule { name, docs, body })
+    }
+
+    fn choice(&mut self) -> Result<Pattern, String> {
+        let mut patterns = vec![self.sequence()?];
+        while self.eat(Token::Pipe) {
+            patterns.push(self.sequence()?);
+        }
+
+        Ok(match patterns.len() {
+            1 => patterns.pop().unwrap(),
+            _ => Pattern::Choice(patterns),
+        })
+    }
+
+    fn sequence(&mut self) -> Result<Pattern, String> {
+        let mut patterns = vec![];
+        while !self.at_end()
+            && !self.at_rule()
+            && !matches!(self.nth(0), Some(Token::Pipe | Token::RightParen))
+        {
+            patterns.push(self.postfix()?);
+        }
+
+        Ok(match patterns.len() {
+            0 => return Err(self.error("Expected a pattern")),
+            1 => patterns.pop().unwrap(),
+            _ => Pattern::Sequence(patterns),
+        })
+    }
+
+    fn postfix(&mut self) -> Result<Pattern, String> {
+        let mut pattern = self.atom()?;
+        loop {
+            if self.eat(Token::Star) {
+                pattern = Pattern::Repeat(Box::new(pattern));
+            } else if self.eat(Token::Question) {
+                pattern = Pattern::Optional(Box::new(pattern));
+            } else {
+                return Ok(pattern);
+            }
+        }
+    }
+
+    fn atom(&mut self) -> Result<Pattern, String> {
+        if self.eat(Token::LeftParen) {
+            let pattern = self.choice()?;
+            if !self.eat(Token::RightParen) {
+                return Err(self.error("Expected `)`"));
+            }
+            return Ok(pattern);
+        }
+
+        let label = match (self.nth(0), self.nth(1)) {
+            (Some(Token::Ident(label)), Some(Token::Colon)) => {
+                let label = label.clone();
+                self.index += 2;
+                Some(label)
+            }
+            _ => None,
+        };
+
+        match self.nth(0).cloned() {
+            Some(Token::Ident(rule)) => {
+                self.index += 1;
+                Ok(Pattern::Node { label, rule })
+            }
+            Some(Token::Kind(kind)) => {
+                self.index += 1;
+                Ok(Pattern::Token { label, kind })
+            }
+            _ => Err(self.error("Expected a rule or a token kind")),
+        }
+    }
+}
+
+/// The variants of a rule that is a choice between single nodes.
+fn enum_variants(pattern: &Pattern) -> Option<Vec<(String, String)>> {
+    let Pattern::Choice(patterns) = pattern else {
+        return None;
+    };
+
+    patterns
+        .iter()
+        .map(|pattern| match pattern {
+            Pattern::Node { label, rule } => {
+                Some((label.clone().unwrap_or_else(|| rule.clone()), rule.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// `FunctionDefinition` -> `FUNCTION_DEFINITION`
+pub fn screaming_snake_case(name: &str) -> String {
+    snake_case(name).to_uppercase()
+}
+
+/// `FunctionDefinition` -> `function_definition`
+pub fn snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && index > 0 {
+            result.push('_');
+        }
+        result.push(c.to_ascii_lowercase());
+    }
+
+    result
+}
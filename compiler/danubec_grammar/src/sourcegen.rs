@@ -0,0 +1,205 @@
+use crate::{Grammar, Pattern, Rule, RuleBody};
+use std::{collections::HashSet, fmt::Write};
+
+/// The marker in `syntax_kind.rs` after which the node kinds are generated.
+pub const NODE_KINDS_MARKER: &str =
+    "    // Generated from `danube.ungram` by `cargo test -p danubec_grammar`.";
+
+/// An accessor of a struct rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Accessor {
+    pub name: String,
+    pub kind: AccessorKind,
+    pub many: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccessorKind {
+    Node(String),
+    Token(String),
+}
+
+/// The accessors of a struct rule.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Accessors {
+    /// Accessors generated into `danubec_ast`.
+    pub generated: Vec<Accessor>,
+    /// Labelled accessors that may match the same child as another one, which
+    /// `danubec_ast` defines by position.
+    pub manual: Vec<Accessor>,
+}
+
+/// The node kinds of the struct rules, in order.
+pub fn node_kinds(grammar: &Grammar) -> Vec<&str> {
+    grammar
+        .rules
+        .iter()
+        .filter_map(|rule| match &rule.body {
+            RuleBody::Struct { kind, .. } => Some(kind.as_str()),
+            RuleBody::Enum { .. } => None,
+        })
+        .collect()
+}
+
+/// The contents of `danubec_ast/src/generated.rs`.
+pub fn ast(grammar: &Grammar) -> Result<String, String> {
+    let mut output = String::new();
+    output.push_str("//! Generated from `danube.ungram` by `cargo test -p danubec_grammar`.\n\n");
+    output.push_str("use crate::{ast_node, children, first_child, first_token, tokens};\n");
+    output.push_str("use danubec_syntax::{Danube, SyntaxKind, SyntaxNode, SyntaxToken};\n");
+
+    for rule in &grammar.rules {
+        output.push_str("\nast_node! {\n");
+        for doc in &rule.docs {
+            match doc.as_str() {
+                "" => output.push_str("    ///\n"),
+                doc => writeln!(output, "    /// {doc}").unwrap(),
+            }
+        }
+
+        match &rule.body {
+            RuleBody::Enum { variants } => {
+                writeln!(output, "    enum {};", rule.name).unwrap();
+                output.push('\n');
+                for (variant, rule) in variants {
+                    writeln!(output, "    variant {variant} -> {rule};").unwrap();
+                }
+            }
+            RuleBody::Struct { kind, .. } => {
+                writeln!(output, "    struct {} where {kind};", rule.name).unwrap();
+
+                let accessors = accessors(grammar, rule)?;
+                if !accessors.generated.is_empty() {
+                    output.push('\n');
+                }
+                for accessor in accessors.generated {
+                    let name = escape(&accessor.name);
+                    let line = match (accessor.kind, accessor.many) {
+                        (AccessorKind::Node(rule), false) => format!("node {name} -> {rule};"),
+                        (AccessorKind::Node(rule), true) => format!("nodes {name} -> {rule};"),
+                        (AccessorKind::Token(kind), false) => format!("token {name} where {kind};"),
+                        (AccessorKind::Token(kind), true) => format!("tokens {name} where {kind};"),
+                    };
+                    writeln!(output, "    {line}").unwrap();
+                }
+            }
+        }
+
+        output.push_str("}\n");
+    }
+
+    Ok(output)
+}
+
+/// Groups the elements of `rule` by accessor name.
+///
+/// An element is named by its label, or else by its token kind or rule. An
+/// unlabelled name used more than once gets no accessor.
+pub fn accessors(grammar: &Grammar, rule: &Rule) -> Result<Accessors, String> {
+    let RuleBody::Struct { pattern, .. } = &rule.body else {
+        return Ok(Accessors::default());
+    };
+
+    // (name, kind, labelled), in order of first appearance.
+    let mut elements: Vec<(String, AccessorKind, bool)> = vec![];
+    let mut result = Ok(());
+    pattern.visit(&mut |pattern| {
+        let (name, kind, labelled) = match pattern {
+            Pattern::Node { label, rule } => (
+                label.clone().unwrap_or_else(|| crate::snake_case(rule)),
+                AccessorKind::Node(rule.clone()),
+                label.is_some(),
+            ),
+            Pattern::Token { label, kind } => (
+                label.clone().unwrap_or_else(|| kind.to_lowercase()),
+                AccessorKind::Token(kind.clone()),
+                label.is_some(),
+            ),
+            _ => unreachable!("only nodes and tokens are visited"),
+        };
+
+        match elements.iter_mut().find(|(n, ..)| *n == name) {
+            Some((_, k, _)) if *k != kind => {
+                result = Err(format!(
+                    "`{}` uses `{name}` for different elements",
+                    rule.name
+                ));
+            }
+            Some((_, _, l)) => *l |= labelled,
+            None => elements.push((name, kind, labelled)),
+        }
+    });
+    result?;
+
+    let mut accessors = Accessors::default();
+    for (index, (name, kind, labelled)) in elements.iter().enumerate() {
+        let many = count(pattern, name) > 1;
+        if !labelled && many {
+            continue;
+        }
+
+        let ambiguous = elements.iter().enumerate().any(|(other, (_, k, _))| {
+            other != index && !kinds(grammar, kind).is_disjoint(&kinds(grammar, k))
+        });
+        let accessor = Accessor {
+            name: name.clone(),
+            kind: kind.clone(),
+            many,
+        };
+        match (ambiguous, labelled) {
+            (false, _) => accessors.generated.push(accessor),
+            (true, true) => accessors.manual.push(accessor),
+            (true, false) => {}
+        }
+    }
+
+    Ok(accessors)
+}
+
+/// The node or token kinds an accessor can return.
+fn kinds<'a>(grammar: &'a Grammar, kind: &'a AccessorKind) -> HashSet<&'a str> {
+    match kind {
+        AccessorKind::Node(rule) => grammar.kinds(rule).iter().map(String::as_str).collect(),
+        AccessorKind::Token(kind) => HashSet::from([kind.as_str()]),
+    }
+}
+
+/// How many elements named `name` a match of `pattern` can have, up to 2.
+fn count(pattern: &Pattern, name: &str) -> usize {
+    match pattern {
+        Pattern::Node { label, rule } => {
+            usize::from(label.clone().unwrap_or_else(|| crate::snake_case(rule)) == name)
+        }
+        Pattern::Token { label, kind } => {
+            usize::from(label.clone().unwrap_or_else(|| kind.to_lowercase()) == name)
+        }
+        Pattern::Sequence(patterns) => patterns
+            .iter()
+            .map(|pattern| count(pattern, name))
+            .sum::<usize>()
+            .min(2),
+        Pattern::Choice(patterns) => patterns
+            .iter()
+            .map(|pattern| count(pattern, name))
+            .max()
+            .unwrap_or(0),
+        Pattern::Optional(pattern) => count(pattern, name),
+        Pattern::Repeat(pattern) => 2 * count(pattern, name).min(1),
+    }
+}
+
+/// Escapes Rust keywords in accessor names: `type` -> `r#type`.
+pub fn escape(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+        "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut",
+        "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type", "unsafe",
+        "use", "where", "while", "yield",
+    ];
+
+    if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_owned()
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{
+    Pattern, RuleBody, grammar,
+    sourcegen::{self, NODE_KINDS_MARKER},
+    validate,
+};
+use danubec_syntax::{GreenNodeBuilder, SyntaxKind, SyntaxNode};
+use std::{collections::HashSet, env, fs, path::PathBuf};
+
+fn compiler_path(path: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(path)
+}
+
+/// Fails if `path` does not hold `contents`. With `UPDATE_SOURCEGEN=1` set,
+/// the file is regenerated instead.
+fn ensure_file_contents(path: PathBuf, contents: &str) {
+    let old = fs::read_to_string(&path).unwrap();
+    if old == contents {
+        return;
+    }
+
+    if env::var_os("UPDATE_SOURCEGEN").is_some_and(|value| value == "1") {
+        fs::write(&path, contents).unwrap();
+        return;
+    }
+
+    panic!(
+        "{} is out of date, rerun the tests with `UPDATE_SOURCEGEN=1` to regenerate it",
+        path.display()
+    );
+}
+
+#[test]
+fn sourcegen_ast() {
+    let contents = sourcegen::ast(grammar()).unwrap();
+
+    ensure_file_contents(compiler_path("danubec_ast/src/generated.rs"), &contents);
+}
+
+#[test]
+fn sourcegen_node_kinds() {
+    let path = compiler_path("danubec_syntax/src/syntax_kind.rs");
+    let old = fs::read_to_string(&path).unwrap();
+    let (head, rest) = old
+        .split_once(&format!("{NODE_KINDS_MARKER}\n"))
+        .expect("syntax_kind.rs has the node kinds marker");
+    let (_, tail) = rest.split_once("\n}\n").unwrap();
+
+    let declared: HashSet<_> = head
+        .lines()
+        .filter_map(|line| line.trim().strip_suffix(','))
+        .collect();
+    for rule in &grammar().rules {
+        let RuleBody::Struct { kind, pattern } = &rule.body else {
+            continue;
+        };
+        assert!(
+            !declared.contains(kind.as_str()),
+            "{kind} is declared outside the generated node kinds",
+        );
+        pattern.visit(&mut |pattern| {
+            if let Pattern::Token { kind, .. } = pattern {
+                assert!(
+                    declared.contains(kind.as_str()),
+                    "{kind} is not a token kind"
+                );
+            }
+        });
+    }
+
+    let mut contents = format!("{head}{NODE_KINDS_MARKER}\n");
+    for kind in sourcegen::node_kinds(grammar()) {
+        contents.push_str(&format!("    {kind},\n"));
+    }
+    contents.push_str(&format!("}}\n{tail}"));
+
+    ensure_file_contents(path, &contents);
+}
+
+#[test]
+fn manual_accessors_are_defined() {
+    let source = fs::read_to_string(compiler_path("danubec_ast/src/lib.rs")).unwrap();
+
+    for rule in &grammar().rules {
+        let accessors = sourcegen::accessors(grammar(), rule).unwrap();
+        for accessor in accessors.manual {
+            let defined = source
+                .split_once(&format!("\nimpl {} {{\n", rule.name))
+                .and_then(|(_, body)| body.split_once("\n}\n"))
+                .is_some_and(|(body, _)| {
+                    let name = sourcegen::escape(&accessor.name);
+                    body.contains(&format!("pub fn {name}("))
+                });
+            assert!(
+                defined,
+                "`{}::{}` is ambiguous and must be defined in danubec_ast",
+                rule.name, accessor.name,
+            );
+        }
+    }
+}
+
+#[test]
+fn validate_reports_mismatch() {
+    fn node(children: &[SyntaxKind]) -> SyntaxNode {
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(SyntaxKind::MATCH_ARM_NODE.into());
+        for &kind in children {
+            match kind {
+                SyntaxKind::EQUAL | SyntaxKind::RIGHT_CHEVRON | SyntaxKind::WHITESPACE => {
+                    builder.token(kind.into(), " ")
+                }
+                _ => {
+                    builder.start_node(kind.into());
+                    builder.start_node(SyntaxKind::PATH_NODE.into());
+                    builder.start_node(SyntaxKind::PATH_SEGMENT_SELF_NODE.into());
+                    builder.token(SyntaxKind::SELF.into(), "self");
+                    builder.finish_node();
+                    builder.finish_node();
+                    builder.finish_node();
+                }
+            }
+        }
+        builder.finish_node();
+
+        SyntaxNode::new_root(builder.finish())
+    }
+
+    use SyntaxKind::*;
+
+    let arm = node(&[
+        PATH_PATTERN_NODE,
+        WHITESPACE,
+        EQUAL,
+        RIGHT_CHEVRON,
+        PATH_EXPRESSION_NODE,
+    ]);
+    assert_eq!(validate(&arm), Ok(()));
+
+    let arm = node(&[PATH_PATTERN_NODE, EQUAL, PATH_EXPRESSION_NODE]);
+    assert_eq!(
+        validate(&arm),
+        Err(
+            "MATCH_ARM_NODE@0..9: [PATH_PATTERN_NODE, EQUAL, PATH_EXPRESSION_NODE] does not match `MatchArm`"
+                .to_owned()
+        )
+    );
+}
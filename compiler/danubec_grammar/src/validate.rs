@@ -0,0 +1,129 @@
+use crate::{Grammar, Pattern, RuleBody, grammar};
+use danubec_syntax::{NodeOrToken, SyntaxElement, SyntaxNode};
+use std::collections::BTreeSet;
+
+/// Checks that `node` and all of its descendants match [`grammar`].
+///
+/// Returns a message describing the first node that does not.
+pub fn validate(node: &SyntaxNode) -> Result<(), String> {
+    validate_with(grammar(), node)
+}
+
+pub fn validate_with(grammar: &Grammar, node: &SyntaxNode) -> Result<(), String> {
+    let kind = kind_name(node.kind());
+    let Some(rule) = grammar.rule_for_kind(&kind) else {
+        return Err(format!(
+            "{kind}@{:?}: no rule has this kind",
+            node.text_range()
+        ));
+    };
+    let RuleBody::Struct { pattern, .. } = &rule.body else {
+        unreachable!("only struct rules have a kind");
+    };
+
+    let children: Vec<_> = node
+        .children_with_tokens()
+        .filter(|child| !child.kind().at_trivia())
+        .collect();
+    let matcher = Matcher {
+        grammar,
+        children: &children,
+    };
+    if !matcher.ends(pattern, 0).contains(&children.len()) {
+        let children: Vec<_> = children
+            .iter()
+            .map(|child| kind_name(child.kind()))
+            .collect();
+
+        return Err(format!(
+            "{kind}@{:?}: [{}] does not match `{}`",
+            node.text_range(),
+            children.join(", "),
+            rule.name,
+        ));
+    }
+
+    for child in node.children() {
+        if !is_composite(grammar, &child) {
+            validate_with(grammar, &child)?;
+        }
+    }
+
+    Ok(())
+}
+
+struct Matcher<'a> {
+    grammar: &'a Grammar,
+    children: &'a [SyntaxElement],
+}
+
+impl Matcher<'_> {
+    /// Every index where matching `pattern` from `start` can end.
+    fn ends(&self, pattern: &Pattern, start: usize) -> BTreeSet<usize> {
+        match pattern {
+            Pattern::Node { rule, .. } => match self.children.get(start) {
+                Some(NodeOrToken::Node(node))
+                    if self.grammar.kinds(rule).contains(&kind_name(node.kind())) =>
+                {
+                    BTreeSet::from([start + 1])
+                }
+                _ => BTreeSet::new(),
+            },
+            Pattern::Token { kind, .. } => match self.children.get(start) {
+                Some(NodeOrToken::Token(token)) if kind_name(token.kind()) == *kind => {
+                    BTreeSet::from([start + 1])
+                }
+                Some(NodeOrToken::Node(node))
+                    if kind_name(node.kind()) == *kind && is_composite(self.grammar, node) =>
+                {
+                    BTreeSet::from([start + 1])
+                }
+                _ => BTreeSet::new(),
+            },
+            Pattern::Sequence(patterns) => {
+                let mut ends = BTreeSet::from([start]);
+                for pattern in patterns {
+                    ends = ends
+                        .iter()
+                        .flat_map(|&end| self.ends(pattern, end))
+                        .collect();
+                }
+                ends
+            }
+            Pattern::Choice(patterns) => patterns
+                .iter()
+                .flat_map(|pattern| self.ends(pattern, start))
+                .collect(),
+            Pattern::Optional(pattern) => {
+                let mut ends = self.ends(pattern, start);
+                ends.insert(start);
+                ends
+            }
+            Pattern::Repeat(pattern) => {
+                let mut ends = BTreeSet::from([start]);
+                let mut frontier = vec![start];
+                while let Some(end) = frontier.pop() {
+                    for end in self.ends(pattern, end) {
+                        if ends.insert(end) {
+                            frontier.push(end);
+                        }
+                    }
+                }
+                ends
+            }
+        }
+    }
+}
+
+/// Operators made of several tokens, like `+=`, are nodes of the operator's
+/// token kind holding only tokens.
+fn is_composite(grammar: &Grammar, node: &SyntaxNode) -> bool {
+    grammar.rule_for_kind(&kind_name(node.kind())).is_none()
+        && node
+            .children_with_tokens()
+            .all(|child| child.as_token().is_some())
+}
+
+fn kind_name(kind: danubec_syntax::SyntaxKind) -> String {
+    format!("{kind:?}")
+}
@@ -53,28 +53,20 @@ pub struct Definition {
 #[derive(Debug)]
 pub enum DefinitionKind {
     Function {
-        type_parameters: Vec<TypeParameter>,
         parameters: Vec<FunctionParameter>,
         return_type: Option<TypeExpression>,
-        type_bounds: Vec<TypeBound>,
         body: Option<Vec<Statement>>,
     },
     Struct {
-        type_parameters: Vec<TypeParameter>,
-        type_bounds: Vec<TypeBound>,
         body: StructBody,
     },
     Enum {
-        type_parameters: Vec<TypeParameter>,
-        type_bounds: Vec<TypeBound>,
         variants: Vec<EnumVariant>,
     },
     Module {
         kind: ModuleDefinitionKind,
     },
     Trait {
-        type_parameters: Vec<TypeParameter>,
-        type_bounds: Vec<TypeBound>,
         definitions: HashMap<Symbol, Vec<DefinitionId>>,
     },
     Constant {
@@ -86,8 +78,6 @@ pub enum DefinitionKind {
         initializer: Expression,
     },
     Type {
-        type_parameters: Vec<TypeParameter>,
-        type_bounds: Vec<TypeBound>,
         initializer: Option<TypeExpression>,
     },
 }
@@ -96,10 +86,8 @@ pub enum DefinitionKind {
 pub struct Implement {
     pub attributes: Vec<AttributeId>,
     pub visibility: Visibility,
-    pub type_parameters: Vec<TypeParameter>,
     pub trait_type: Option<TypeExpression>,
     pub for_type: TypeExpression,
-    pub type_bounds: Vec<TypeBound>,
     pub definitions: HashMap<Symbol, Vec<DefinitionId>>,
     pub span: Span,
 }
@@ -112,6 +100,7 @@ pub enum ModuleDefinitionKind {
 
 #[derive(Debug, Clone)]
 pub enum Visibility {
+    Public,
     Krate,
     Super,
     Self_,
@@ -132,20 +121,6 @@ pub enum Binding {
     Local(LocalId),
 }
 
-#[derive(Debug)]
-pub struct TypeParameter {
-    pub r#type: TypeExpression,
-    pub constraints: Vec<TypeExpression>,
-    pub span: Span,
-}
-
-#[derive(Debug)]
-pub struct TypeBound {
-    pub r#type: TypeExpression,
-    pub constraints: Vec<TypeExpression>,
-    pub span: Span,
-}
-
 #[derive(Debug)]
 pub struct FunctionParameter {
     pub attributes: Vec<AttributeId>,
@@ -157,8 +132,8 @@ pub struct FunctionParameter {
 #[derive(Debug)]
 pub enum StructBody {
     Unit,
-    Named(Vec<(Visibility, Identifier, TypeExpression)>),
-    Unnamed(Vec<(Visibility, TypeExpression)>),
+    Named(Vec<(Vec<AttributeId>, Visibility, Identifier, TypeExpression)>),
+    Unnamed(Vec<(Vec<AttributeId>, Visibility, TypeExpression)>),
 }
 
 #[derive(Debug)]
@@ -359,6 +334,7 @@ pub enum StatementKind {
         initializer: Option<Expression>,
     },
     Expression {
+        attributes: Vec<AttributeId>,
         value: Expression,
     },
     Semicolon,
@@ -438,19 +414,78 @@ pub struct Literal {
 
 #[derive(Debug)]
 pub enum LiteralKind {
-    Boolean { value: bool },
-    Character { value: char },
-    Float { value: f64 },
-    Integer { value: i128 },
-    String { segments: Vec<StringSegment> },
+    Boolean {
+        value: bool,
+    },
+    Character {
+        value: char,
+    },
+    Float {
+        value: f64,
+        suffix: Option<FloatSuffix>,
+    },
+    Integer {
+        /// The value as written. A leading `-` is a separate unary expression.
+        value: u128,
+        suffix: Option<IntegerSuffix>,
+    },
+    String {
+        segments: Vec<StringSegment>,
+    },
+    /// `b'a'`
+    Byte {
+        value: u8,
+    },
+    /// `b"bytes"`
+    ByteString {
+        value: Vec<u8>,
+    },
+    /// `c"text"`, whose `value` does not include the terminating nul.
+    CString {
+        value: Vec<u8>,
+    },
+}
+
+/// The type written after an integer literal: `10u8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegerSuffix {
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+}
+
+/// The type written after a float literal, or after a decimal integer to make it one: `1.5f32`, `1f64`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatSuffix {
+    F32,
+    F64,
 }
 
 #[derive(Debug)]
 pub enum StringSegment {
-    Text { value: String },
-    Unicode { value: char },
-    Escape { value: char },
-    Interpolation { expression: Expression },
+    Text {
+        value: String,
+    },
+    /// `\u{1F600}`
+    Unicode {
+        value: char,
+    },
+    /// `\n`, `\x41`, `\$`, etc.
+    Escape {
+        value: char,
+    },
+    Interpolation {
+        expression: Expression,
+    },
 }
 
 #[derive(Debug)]
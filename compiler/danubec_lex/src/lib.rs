@@ -3,10 +3,14 @@
 #[cfg(test)]
 mod tests;
 
-use danubec_syntax::SyntaxKind;
+use danubec_syntax::{SyntaxKind, TextRange, TextSize};
+use std::collections::VecDeque;
 use unicode_ident::{is_xid_continue, is_xid_start};
 
-enum Mode {
+/// What the lexer is inside of. Modes are kept on a stack, so that strings and blocks can nest
+/// inside interpolations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
     Base {
         depth: usize,
         in_interpolation: bool,
@@ -20,291 +24,515 @@ enum Mode {
     },
 }
 
-pub fn lex<'lex>(source: &'lex str) -> Vec<(SyntaxKind, &'lex str)> {
-    let mut tokens = vec![];
-    let mut modes = vec![];
-    let mut chars = source.chars();
-    let mut index = 0;
-
-    macro_rules! source {
-        ($count:expr, $predicate:expr) => {{
-            let mut peekable = chars.clone();
-            let mut count = $count;
-            loop {
-                match peekable.next() {
-                    Some(c) if $predicate(c) => {
-                        count += c.len_utf8();
-                        chars.next();
-                    }
-                    _ => break,
-                }
+/// A malformed token and where it is in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub range: TextRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexErrorKind {
+    /// `'a` or `b'a`
+    UnterminatedCharacter,
+    /// `''`
+    EmptyCharacter,
+    /// `'ab'`
+    LongCharacter,
+    /// `"text` at the end of its line, or `"""text` at the end of the source
+    UnterminatedString,
+    /// `r#"text` at the end of the source
+    UnterminatedRawString,
+    /// `\u{41`
+    UnterminatedUnicodeEscape,
+    /// `\u{4g}`
+    InvalidUnicodeDigit,
+    /// `0b102`, `0o78`
+    InvalidDigit { radix: u32 },
+    /// `0x`, `0b_`
+    MissingDigits { radix: u32 },
+    /// A run of characters that cannot start a token: `€`, `$`
+    UnexpectedCharacter,
+}
+
+impl std::fmt::Display for LexErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LexErrorKind::UnterminatedCharacter => write!(f, "Unterminated character literal"),
+            LexErrorKind::EmptyCharacter => write!(f, "Empty character literal"),
+            LexErrorKind::LongCharacter => {
+                write!(f, "Character literal with more than one character")
             }
-            slice!(count)
-        }};
+            LexErrorKind::UnterminatedString => write!(f, "Unterminated string literal"),
+            LexErrorKind::UnterminatedRawString => write!(f, "Unterminated raw string literal"),
+            LexErrorKind::UnterminatedUnicodeEscape => write!(f, "Unterminated unicode escape"),
+            LexErrorKind::InvalidUnicodeDigit => write!(f, "Invalid digit in unicode escape"),
+            LexErrorKind::InvalidDigit { radix: 2 } => write!(f, "Invalid digit in binary literal"),
+            LexErrorKind::InvalidDigit { radix: 8 } => write!(f, "Invalid digit in octal literal"),
+            LexErrorKind::InvalidDigit { radix } => {
+                write!(f, "Invalid digit in base {} literal", radix)
+            }
+            LexErrorKind::MissingDigits { radix } => {
+                let prefix = match radix {
+                    2 => "0b",
+                    8 => "0o",
+                    _ => "0x",
+                };
+                write!(f, "Expected digits after base prefix `{}`", prefix)
+            }
+            LexErrorKind::UnexpectedCharacter => write!(f, "Unexpected character"),
+        }
     }
+}
 
-    macro_rules! peek {
-        () => {{ chars.clone().next() }};
-    }
+/// A token and where it is in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'source> {
+    pub kind: SyntaxKind,
+    pub text: &'source str,
+    pub range: TextRange,
+}
 
-    macro_rules! nth {
-        ($n:expr) => {{
-            let mut peekable = chars.clone();
-            for _ in 0..$n {
-                peekable.next();
-            }
-            peekable.next()
-        }};
+/// Lexes a source on demand, one token at a time.
+///
+/// The mode stack after most tokens can be saved with [`Lexer::modes`] and handed to
+/// [`Lexer::resume`] to lex on from that point later, such as from the start of an edited line.
+pub struct Lexer<'source> {
+    source: &'source str,
+    index: usize,
+    modes: Vec<Mode>,
+    /// Where the token that entered each of `modes` starts.
+    starts: Vec<TextSize>,
+    errors: Vec<LexError>,
+    /// Tokens lexed together, such as an escape's `\` and segment, waiting to be yielded.
+    pending: VecDeque<Token<'source>>,
+}
+
+impl<'source> Lexer<'source> {
+    pub fn new(source: &'source str) -> Self {
+        Self::resume(source, TextSize::from(0), vec![])
     }
 
-    macro_rules! many {
-        ($kind:ident, $count:expr, $predicate:expr) => {{
-            token!(SyntaxKind::$kind, source!($count, $predicate));
-        }};
+    /// Lexes `source` from `offset` in `modes`, which were saved when an earlier lexer reached
+    /// `offset`. Token ranges are still relative to the start of `source`, and errors about
+    /// strings opened before `offset` point at `offset`.
+    pub fn resume(source: &'source str, offset: TextSize, modes: Vec<Mode>) -> Self {
+        Self {
+            source,
+            index: usize::from(offset),
+            starts: vec![offset; modes.len()],
+            modes,
+            errors: vec![],
+            pending: VecDeque::new(),
+        }
     }
 
-    macro_rules! one {
-        ($kind:ident) => {{
-            token!(SyntaxKind::$kind, slice!(1));
-        }};
-        ($kind:ident, $count:expr) => {{
-            token!(SyntaxKind::$kind, slice!($count));
-        }};
+    /// The mode stack after the tokens yielded so far. Empty at the top level.
+    ///
+    /// `None` between tokens that are lexed together, such as the `\` and the rest of an
+    /// escape, since the stack alone cannot resume in the middle of them.
+    pub fn modes(&self) -> Option<&[Mode]> {
+        self.pending.is_empty().then_some(&self.modes[..])
     }
 
-    macro_rules! token {
-        ($kind:expr, $source:expr) => {{
-            tokens.push(($kind, $source));
-        }};
+    /// The errors found so far. Strings still open are only reported by [`Lexer::finish`], as
+    /// a line given to [`Lexer::resume`] may end inside one.
+    pub fn errors(&self) -> &[LexError] {
+        &self.errors
     }
 
-    macro_rules! slice {
-        ($count:expr) => {{
-            let start = index;
-            index += $count;
-            let end = index;
-            &source[start..end]
-        }};
+    /// Lexes the rest of the source and returns every error, including strings that were never
+    /// closed.
+    pub fn finish(mut self) -> Vec<LexError> {
+        while self.next().is_some() {}
+
+        let end = TextSize::of(self.source);
+        for (mode, &start) in self.modes.iter().zip(&self.starts) {
+            let kind = match mode {
+                Mode::InString { .. } => LexErrorKind::UnterminatedString,
+                Mode::InRawString { .. } => LexErrorKind::UnterminatedRawString,
+                Mode::Base { .. } => continue,
+            };
+            self.errors.push(LexError {
+                kind,
+                range: TextRange::new(start, end),
+            });
+        }
+
+        self.errors
     }
 
-    macro_rules! eat_one {
-        ($kind:ident if $predicate:expr) => {
-            match peek!() {
-                Some(c) if $predicate(c) => {
-                    chars.next();
+    /// Lexes the next few tokens into `pending`.
+    fn step(&mut self) {
+        let source = self.source;
+        let tokens = &mut self.pending;
+        let modes = &mut self.modes;
+        let starts = &mut self.starts;
+        let errors = &mut self.errors;
+        let mut chars = source[self.index..].chars();
+        let mut index = self.index;
+
+        macro_rules! source {
+            ($count:expr, $predicate:expr) => {{
+                let mut peekable = chars.clone();
+                let mut count = $count;
+                loop {
+                    match peekable.next() {
+                        Some(c) if $predicate(c) => {
+                            count += c.len_utf8();
+                            chars.next();
+                        }
+                        _ => break,
+                    }
+                }
+                slice!(count)
+            }};
+        }
 
-                    token!(SyntaxKind::$kind, slice!(c.len_utf8()));
+        macro_rules! peek {
+            () => {{ chars.clone().next() }};
+        }
+
+        macro_rules! nth {
+            ($n:expr) => {{
+                let mut peekable = chars.clone();
+                for _ in 0..$n {
+                    peekable.next();
                 }
-                _ => {}
+                peekable.next()
+            }};
+        }
+
+        macro_rules! many {
+            ($kind:ident, $count:expr, $predicate:expr) => {{
+                token!(SyntaxKind::$kind, source!($count, $predicate));
+            }};
+        }
+
+        macro_rules! one {
+            ($kind:ident) => {{
+                token!(SyntaxKind::$kind, slice!(1));
+            }};
+            ($kind:ident, $count:expr) => {{
+                token!(SyntaxKind::$kind, slice!($count));
+            }};
+        }
+
+        macro_rules! token {
+            ($kind:expr, $source:expr) => {{
+                // Every token ends where lexing has got to.
+                let text: &str = $source;
+                let start = TextSize::from((index - text.len()) as u32);
+                tokens.push_back(Token {
+                    kind: $kind,
+                    text,
+                    range: TextRange::at(start, TextSize::of(text)),
+                });
+            }};
+        }
+
+        macro_rules! slice {
+            ($count:expr) => {{
+                let start = index;
+                index += $count;
+                let end = index;
+                &source[start..end]
+            }};
+        }
+
+        // A type suffix directly after a number, such as `u8` in `10u8`
+        macro_rules! suffix {
+        () => {
+            if matches!(peek!(), Some(c) if is_identifier_start(c)) {
+                many!(NUMERIC_SUFFIX, 0, is_identifier_continue);
             }
         };
     }
 
-    macro_rules! starts_with {
-        ($prefix:expr) => {{ source[index..].starts_with($prefix) }};
-    }
+        // Enters `mode`, which the last token opened
+        macro_rules! push {
+            ($mode:expr) => {{
+                let start = tokens
+                    .back()
+                    .map_or(index, |token| usize::from(token.range.start()));
+                starts.push(TextSize::from(start as u32));
+                modes.push($mode);
+            }};
+        }
 
-    'lex: while peek!().is_some() {
-        let current_mode = modes.last().unwrap_or(&Mode::Base {
-            depth: 0,
-            in_interpolation: false,
-        });
-        match current_mode {
-            &Mode::Base {
-                depth,
-                in_interpolation,
-            } => {
-                let Some(c) = chars.next() else {
-                    break 'lex;
-                };
-                match c {
-                    // Single-quoted character
-                    '\'' if !matches!(peek!(), Some('\'' | '\\' | '\n' | '\t')) => {
-                        one!(CHARACTER_START, 1);
+        macro_rules! pop {
+            () => {{
+                starts.pop();
+                modes.pop();
+            }};
+        }
 
-                        if let Some(c) = chars.next() {
-                            one!(CHARACTER_SEGMENT, c.len_utf8());
-                        }
+        macro_rules! error {
+            ($kind:expr, $start:expr) => {
+                error!($kind, $start, index)
+            };
+            ($kind:expr, $start:expr, $end:expr) => {{
+                errors.push(LexError {
+                    kind: $kind,
+                    range: TextRange::new(
+                        TextSize::from($start as u32),
+                        TextSize::from($end as u32),
+                    ),
+                });
+            }};
+        }
 
-                        eat_one!(CHARACTER_END if |c| matches!(c, '\''));
-                    }
-                    // Escaped character
-                    '\'' if matches!(peek!(), Some('\\'))
-                        && matches!(nth!(1), Some('\\' | '\'' | '"' | 'n' | 't')) =>
-                    {
-                        chars.next(); // skip '\'
-                        chars.next(); // skip escaped char
-
-                        one!(CHARACTER_START, 1); // skip '\''
-                        one!(ESCAPE_START, 1); // skip '\'
-                        one!(ESCAPE_SEGMENT, 1); // skip escaped char
-                        eat_one!(CHARACTER_END if |c| matches!(c, '\''));
-                    }
-                    '\'' if matches!(peek!(), Some('\\'))
-                        && matches!(nth!(1), Some('u'))
-                        && matches!(nth!(2), Some('{')) =>
-                    {
-                        one!(CHARACTER_START, 1);
-
-                        chars.next(); // skip '\'
-                        chars.next(); // skip 'u'
-                        chars.next(); // skip '{'
-                        one!(UNICODE_START, 3);
-
-                        let source = source!(0, |c: char| c.is_ascii_hexdigit());
-                        if source.is_empty() {
-                            continue 'lex;
-                        }
-                        token!(SyntaxKind::UNICODE_SEGMENT, source);
+        // Reports the first digit in `segment`, which was just lexed, that is not valid in `radix`
+        macro_rules! invalid_digit {
+            ($segment:expr, $radix:expr) => {{
+                let segment: &str = $segment;
+                if let Some(offset) = segment.find(|c: char| !c.is_digit($radix)) {
+                    let start = index - segment.len() + offset;
+                    error!(
+                        LexErrorKind::InvalidDigit { radix: $radix },
+                        start,
+                        start + 1
+                    );
+                }
+            }};
+        }
 
-                        loop {
-                            let source = source!(0, |c: char| c == '_');
-                            if source.is_empty() {
-                                break;
-                            }
-                            token!(SyntaxKind::NUMERIC_SEPARATOR, source);
+        macro_rules! starts_with {
+            ($prefix:expr) => {{ source[index..].starts_with($prefix) }};
+        }
 
-                            let source = source!(0, |c: char| c.is_ascii_hexdigit());
-                            if source.is_empty() {
-                                break;
-                            }
-                            token!(SyntaxKind::UNICODE_SEGMENT, source);
-                        }
+        // An escape sequence of `count` bytes, including the `\`
+        macro_rules! escape {
+            ($count:expr) => {{
+                let mut consumed = 0;
+                while consumed < $count {
+                    consumed += chars.next().map_or($count, char::len_utf8);
+                }
 
-                        if let Some('}') = peek!() {
-                            chars.next(); // skip '}'
+                one!(ESCAPE_START, 1); // skip '\'
+                one!(ESCAPE_SEGMENT, $count - 1); // skip escaped chars
+            }};
+        }
 
-                            one!(UNICODE_END, 1);
-                            eat_one!(CHARACTER_END if |c| matches!(c, '\''));
+        // A Unicode escape sequence: `\u{1F600}`
+        macro_rules! unicode {
+            () => {{
+                let start = index;
+
+                chars.next(); // skip '\'
+                chars.next(); // skip 'u'
+                chars.next(); // skip '{'
+                one!(UNICODE_START, 3);
+
+                // Letters that are not hex digits are still part of the escape, so that
+                // `\u{zz}` is one bad escape rather than an unterminated one.
+                loop {
+                    let segment = source!(0, |c: char| c.is_ascii_alphanumeric());
+                    if !segment.is_empty() {
+                        token!(SyntaxKind::UNICODE_SEGMENT, segment);
+                        if let Some(offset) = segment.find(|c: char| !c.is_ascii_hexdigit()) {
+                            let start = index - segment.len() + offset;
+                            error!(LexErrorKind::InvalidUnicodeDigit, start, start + 1);
                         }
                     }
-                    // Error: Empty character
-                    '\'' => one!(CHARACTER_START),
-                    // Triple-quoted string
-                    '"' if matches!(peek!(), Some('"')) && matches!(nth!(1), Some('"')) => {
-                        chars.next(); // skip second '"'
-                        chars.next(); // skip third '"'
-
-                        one!(STRING_START, "\"\"\"".len());
-                        modes.push(Mode::InString {
-                            depth,
-                            multiline: true,
-                        });
+
+                    let separator = source!(0, |c| c == '_');
+                    if !separator.is_empty() {
+                        token!(SyntaxKind::NUMERIC_SEPARATOR, separator);
                     }
-                    // One-double-quoted string
-                    '"' => {
-                        one!(STRING_START);
-                        modes.push(Mode::InString {
-                            depth,
-                            multiline: false,
-                        });
+
+                    if segment.is_empty() && separator.is_empty() {
+                        break;
                     }
-                    // Binary number
-                    '0' if matches!(peek!(), Some('b')) => {
-                        chars.next(); // skip 'b'
+                }
 
-                        one!(BINARY_START, 2);
+                if let Some('}') = peek!() {
+                    chars.next(); // skip '}'
 
-                        loop {
-                            let segment = source!(0, |c| matches!(c, '0' | '1'));
-                            if !segment.is_empty() {
-                                token!(SyntaxKind::BINARY_SEGMENT, segment);
-                            }
+                    one!(UNICODE_END, 1);
+                } else {
+                    error!(LexErrorKind::UnterminatedUnicodeEscape, start);
+                }
+            }};
+        }
 
-                            let separator = source!(0, |c| c == '_');
-                            if !separator.is_empty() {
-                                token!(SyntaxKind::NUMERIC_SEPARATOR, separator);
-                            }
+        // The rest of a character or byte literal whose opening quote is at `start`
+        macro_rules! character {
+            ($start:expr) => {{
+                let content = index;
+                if starts_with!("\\u{") {
+                    unicode!();
+                } else if let Some(count) = escape(&source[index..]) {
+                    escape!(count);
+                } else if let Some(c) = peek!()
+                    && !matches!(c, '\'' | '\\' | '\n' | '\t')
+                {
+                    chars.next();
 
-                            if segment.is_empty() && separator.is_empty() {
-                                break;
-                            }
-                        }
+                    one!(CHARACTER_SEGMENT, c.len_utf8());
+                }
+
+                if let Some('\'') = peek!() {
+                    chars.next(); // skip '\''
+
+                    one!(CHARACTER_END);
+                    if index == content + 1 {
+                        error!(LexErrorKind::EmptyCharacter, $start);
                     }
-                    // Octal number
-                    '0' if matches!(peek!(), Some('o')) => {
-                        chars.next(); // skip 'o'
+                } else if closes(&source[index..]) {
+                    // Resynchronises on a closing quote later on the same line, so that it
+                    // does not open another literal.
+                    many!(ERROR, 0, |c| c != '\'');
+                    chars.next(); // skip '\''
+
+                    one!(CHARACTER_END);
+                    error!(LexErrorKind::LongCharacter, $start);
+                } else {
+                    error!(LexErrorKind::UnterminatedCharacter, $start);
+                }
+            }};
+        }
 
-                        one!(OCTAL_START, 2);
+        // A raw string after its `prefix` letters: `r#"`, `br#"`
+        macro_rules! raw {
+            ($kind:ident, $prefix:expr) => {{
+                let mut peekable = chars.clone();
+                let mut count = 0;
+                while let Some('#') = peekable.next() {
+                    count += 1;
+                    chars.next(); // skip '#'
+                }
 
-                        loop {
-                            let segment = source!(0, |c| matches!(c, '0'..='7'));
-                            if !segment.is_empty() {
-                                token!(SyntaxKind::OCTAL_SEGMENT, segment);
-                            }
+                if let Some('"') = peek!() {
+                    chars.next(); // skip '"'
 
-                            let separator = source!(0, |c| c == '_');
-                            if !separator.is_empty() {
-                                token!(SyntaxKind::NUMERIC_SEPARATOR, separator);
-                            }
+                    one!($kind, $prefix + count + 1);
+                    push!(Mode::InRawString {
+                        pattern: format!("\"{}", "#".repeat(count)),
+                    });
+                } else {
+                    one!($kind, $prefix + count);
+                }
+            }};
+        }
 
-                            if segment.is_empty() && separator.is_empty() {
-                                break;
-                            }
+        'lex: {
+            let current_mode = modes.last().unwrap_or(&Mode::Base {
+                depth: 0,
+                in_interpolation: false,
+            });
+            match current_mode {
+                &Mode::Base {
+                    depth,
+                    in_interpolation,
+                } => {
+                    let Some(c) = chars.next() else {
+                        break 'lex;
+                    };
+                    match c {
+                        // Character
+                        '\'' => {
+                            let start = index;
+
+                            one!(CHARACTER_START);
+                            character!(start);
                         }
-                    }
-                    // Hexadecimal number
-                    '0' if matches!(peek!(), Some('x')) => {
-                        chars.next(); // skip 'x'
+                        // Triple-quoted string
+                        '"' if matches!(peek!(), Some('"')) && matches!(nth!(1), Some('"')) => {
+                            chars.next(); // skip second '"'
+                            chars.next(); // skip third '"'
+
+                            one!(STRING_START, "\"\"\"".len());
+                            push!(Mode::InString {
+                                depth,
+                                multiline: true,
+                            });
+                        }
+                        // One-double-quoted string
+                        '"' => {
+                            one!(STRING_START);
+                            push!(Mode::InString {
+                                depth,
+                                multiline: false,
+                            });
+                        }
+                        // Binary number
+                        '0' if matches!(peek!(), Some('b')) => {
+                            chars.next(); // skip 'b'
 
-                        one!(HEX_START, 2);
+                            one!(BINARY_START, 2);
+                            let start = index - 2;
+                            let mut digits = false;
 
-                        loop {
-                            let segment = source!(0, |c: char| c.is_ascii_hexdigit());
-                            if !segment.is_empty() {
-                                token!(SyntaxKind::HEX_SEGMENT, segment);
-                            }
+                            loop {
+                                let segment = source!(0, is_numeric);
+                                if !segment.is_empty() {
+                                    digits = true;
+                                    token!(SyntaxKind::BINARY_SEGMENT, segment);
+                                    invalid_digit!(segment, 2);
+                                }
 
-                            let separator = source!(0, |c| c == '_');
-                            if !separator.is_empty() {
-                                token!(SyntaxKind::NUMERIC_SEPARATOR, separator);
+                                let separator = source!(0, |c| c == '_');
+                                if !separator.is_empty() {
+                                    token!(SyntaxKind::NUMERIC_SEPARATOR, separator);
+                                }
+
+                                if segment.is_empty() && separator.is_empty() {
+                                    break;
+                                }
                             }
 
-                            if segment.is_empty() && separator.is_empty() {
-                                break;
+                            if !digits {
+                                error!(LexErrorKind::MissingDigits { radix: 2 }, start);
                             }
+
+                            suffix!();
                         }
-                    }
-                    // Decimal or floating-point number
-                    '0'..='9' => {
-                        // Integer part
-                        let mut peekable = chars.clone();
-                        let mut count = 1;
-                        while let Some(_) = peekable.next().filter(|c| is_numeric(*c)) {
-                            count += 1;
-                            chars.next(); // skip digit
-                        }
+                        // Octal number
+                        '0' if matches!(peek!(), Some('o')) => {
+                            chars.next(); // skip 'o'
 
-                        one!(INTEGER_SEGMENT, count);
+                            one!(OCTAL_START, 2);
+                            let start = index - 2;
+                            let mut digits = false;
 
-                        loop {
-                            let segment = source!(0, |c| is_numeric(c));
-                            if !segment.is_empty() {
-                                token!(SyntaxKind::INTEGER_SEGMENT, segment);
-                            }
+                            loop {
+                                let segment = source!(0, is_numeric);
+                                if !segment.is_empty() {
+                                    digits = true;
+                                    token!(SyntaxKind::OCTAL_SEGMENT, segment);
+                                    invalid_digit!(segment, 8);
+                                }
 
-                            let separator = source!(0, |c| c == '_');
-                            if !separator.is_empty() {
-                                token!(SyntaxKind::NUMERIC_SEPARATOR, separator);
+                                let separator = source!(0, |c| c == '_');
+                                if !separator.is_empty() {
+                                    token!(SyntaxKind::NUMERIC_SEPARATOR, separator);
+                                }
+
+                                if segment.is_empty() && separator.is_empty() {
+                                    break;
+                                }
                             }
 
-                            if segment.is_empty() && separator.is_empty() {
-                                break;
+                            if !digits {
+                                error!(LexErrorKind::MissingDigits { radix: 8 }, start);
                             }
-                        }
 
-                        // Fractional part
-                        if matches!(peek!(), Some('.'))
-                            && matches!(nth!(1), Some(c) if is_numeric(c) || c == '_')
-                        {
-                            chars.next(); // skip '.'
+                            suffix!();
+                        }
+                        // Hexadecimal number
+                        '0' if matches!(peek!(), Some('x')) => {
+                            chars.next(); // skip 'x'
 
-                            one!(FRACTION_START);
+                            one!(HEX_START, 2);
+                            let start = index - 2;
+                            let mut digits = false;
 
                             loop {
-                                let segment = source!(0, |c| is_numeric(c));
+                                let segment = source!(0, |c: char| c.is_ascii_hexdigit());
                                 if !segment.is_empty() {
-                                    token!(SyntaxKind::FRACTION_SEGMENT, segment);
+                                    digits = true;
+                                    token!(SyntaxKind::HEX_SEGMENT, segment);
                                 }
 
                                 let separator = source!(0, |c| c == '_');
@@ -316,24 +544,29 @@ pub fn lex<'lex>(source: &'lex str) -> Vec<(SyntaxKind, &'lex str)> {
                                     break;
                                 }
                             }
-                        }
-
-                        // Exponential part
-                        if matches!(peek!(), Some('e') | Some('E')) {
-                            chars.next(); // skip 'e' or 'E'
-
-                            one!(EXPONENT_START);
 
-                            if matches!(peek!(), Some('+') | Some('-')) {
-                                chars.next(); // skip '+' or '-'
+                            if !digits {
+                                error!(LexErrorKind::MissingDigits { radix: 16 }, start);
+                            }
 
-                                one!(EXPONENT_SIGN);
+                            suffix!();
+                        }
+                        // Decimal or floating-point number
+                        '0'..='9' => {
+                            // Integer part
+                            let mut peekable = chars.clone();
+                            let mut count = 1;
+                            while peekable.next().filter(|c| is_numeric(*c)).is_some() {
+                                count += 1;
+                                chars.next(); // skip digit
                             }
 
+                            one!(INTEGER_SEGMENT, count);
+
                             loop {
-                                let segment = source!(0, |c| is_numeric(c));
+                                let segment = source!(0, is_numeric);
                                 if !segment.is_empty() {
-                                    token!(SyntaxKind::EXPONENT_SEGMENT, segment);
+                                    token!(SyntaxKind::INTEGER_SEGMENT, segment);
                                 }
 
                                 let separator = source!(0, |c| c == '_');
@@ -345,298 +578,388 @@ pub fn lex<'lex>(source: &'lex str) -> Vec<(SyntaxKind, &'lex str)> {
                                     break;
                                 }
                             }
+
+                            // Fractional part
+                            if matches!(peek!(), Some('.'))
+                                && matches!(nth!(1), Some(c) if is_numeric(c) || c == '_')
+                            {
+                                chars.next(); // skip '.'
+
+                                one!(FRACTION_START);
+
+                                loop {
+                                    let segment = source!(0, is_numeric);
+                                    if !segment.is_empty() {
+                                        token!(SyntaxKind::FRACTION_SEGMENT, segment);
+                                    }
+
+                                    let separator = source!(0, |c| c == '_');
+                                    if !separator.is_empty() {
+                                        token!(SyntaxKind::NUMERIC_SEPARATOR, separator);
+                                    }
+
+                                    if segment.is_empty() && separator.is_empty() {
+                                        break;
+                                    }
+                                }
+                            }
+
+                            // Exponential part
+                            if matches!(peek!(), Some('e') | Some('E')) {
+                                chars.next(); // skip 'e' or 'E'
+
+                                one!(EXPONENT_START);
+
+                                if matches!(peek!(), Some('+') | Some('-')) {
+                                    chars.next(); // skip '+' or '-'
+
+                                    one!(EXPONENT_SIGN);
+                                }
+
+                                loop {
+                                    let segment = source!(0, is_numeric);
+                                    if !segment.is_empty() {
+                                        token!(SyntaxKind::EXPONENT_SEGMENT, segment);
+                                    }
+
+                                    let separator = source!(0, |c| c == '_');
+                                    if !separator.is_empty() {
+                                        token!(SyntaxKind::NUMERIC_SEPARATOR, separator);
+                                    }
+
+                                    if segment.is_empty() && separator.is_empty() {
+                                        break;
+                                    }
+                                }
+                            }
+
+                            suffix!();
                         }
-                    }
-                    // Raw string
-                    'r' if matches!(peek!(), Some('"'))
-                        || (matches!(peek!(), Some('#')) && matches!(nth!(1), Some('"' | '#'))) =>
-                    {
-                        let mut peekable = chars.clone();
-                        let mut count = 0;
-                        while let Some('#') = peekable.next() {
-                            count += 1;
-                            chars.next(); // skip '#'
+                        // Raw string
+                        'r' if matches!(peek!(), Some('"'))
+                            || (matches!(peek!(), Some('#'))
+                                && matches!(nth!(1), Some('"' | '#'))) =>
+                        {
+                            raw!(RAW_STRING_START, 1);
                         }
+                        // Byte
+                        'b' if matches!(peek!(), Some('\'')) => {
+                            chars.next(); // skip '\''
 
-                        if let Some('"') = peek!() {
+                            let start = index;
+
+                            one!(BYTE_START, 2);
+                            character!(start);
+                        }
+                        // Byte string
+                        'b' if matches!(peek!(), Some('"')) => {
                             chars.next(); // skip '"'
 
-                            let source = &source[index..index + 1 + count + 1];
-                            token!(SyntaxKind::RAW_STRING_START, source);
+                            one!(BYTE_STRING_START, 2);
+                            push!(Mode::InString {
+                                depth,
+                                multiline: false,
+                            });
+                        }
+                        // Raw byte string
+                        'b' if matches!(peek!(), Some('r'))
+                            && (matches!(nth!(1), Some('"'))
+                                || (matches!(nth!(1), Some('#'))
+                                    && matches!(nth!(2), Some('"' | '#')))) =>
+                        {
+                            chars.next(); // skip 'r'
 
-                            index += 1 + count + 1;
+                            raw!(RAW_BYTE_STRING_START, 2);
+                        }
+                        // C string
+                        'c' if matches!(peek!(), Some('"')) => {
+                            chars.next(); // skip '"'
 
-                            modes.push(Mode::InRawString {
-                                pattern: format!("\"{}", "#".repeat(count)),
+                            one!(C_STRING_START, 2);
+                            push!(Mode::InString {
+                                depth,
+                                multiline: false,
                             });
-                        } else {
-                            let source = &source[index..index + 1 + count];
-                            index += 1 + count;
-                            token!(SyntaxKind::RAW_STRING_START, source);
                         }
-                    }
-                    // Raw Identifier
-                    'r' if matches!(peek!(), Some('#')) => {
-                        chars.next(); // skip '#'
-                        one!(RAW_IDENTIFIER_START, 2);
-
-                        let source = source!(0, is_identifier_continue);
-                        if !source.is_empty() {
-                            token!(SyntaxKind::IDENTIFIER, source);
+                        // Raw Identifier
+                        'r' if matches!(peek!(), Some('#')) => {
+                            chars.next(); // skip '#'
+                            one!(RAW_IDENTIFIER_START, 2);
+
+                            let source = source!(0, is_identifier_continue);
+                            if !source.is_empty() {
+                                token!(SyntaxKind::IDENTIFIER, source);
+                            }
                         }
-                    }
-                    c if is_identifier_start(c) => {
-                        let source = source!(c.len_utf8(), is_identifier_continue);
-                        token!(keyword(source), source);
-                    }
-                    c if is_whitespace(c) => many!(WHITESPACE, c.len_utf8(), is_whitespace),
-                    c if is_tab(c) => many!(TAB, c.len_utf8(), is_tab),
-                    '\n' => one!(NEW_LINE),
-                    '-' => one!(HYPHEN),
-                    ',' => one!(COMMA),
-                    ';' => one!(SEMICOLON),
-                    ':' => one!(COLON),
-                    '!' => one!(EXCLAMATION),
-                    '?' => one!(QUESTION),
-                    '.' => one!(DOT),
-                    '(' => one!(LEFT_PAREN),
-                    ')' => one!(RIGHT_PAREN),
-                    '[' => one!(LEFT_BRACKET),
-                    ']' => one!(RIGHT_BRACKET),
-                    // Block start
-                    '{' => {
-                        one!(LEFT_BRACE);
-                        modes.push(Mode::Base {
-                            depth: depth + 1,
-                            in_interpolation: false,
-                        });
-                    }
-                    // Interpolation end
-                    '}' if in_interpolation => {
-                        one!(INTERPOLATION_END);
-                        modes.pop();
-                    }
-                    // Block end
-                    '}' => {
-                        one!(RIGHT_BRACE);
-                        modes.pop();
-                    }
-                    '@' => one!(AT),
-                    '*' => one!(ASTERISK),
-                    // Doc Comment
-                    '/' if matches!(peek!(), Some('/')) && matches!(nth!(1), Some('/')) => {
-                        chars.next(); // skip second '/'
-                        chars.next(); // skip third '/'
-
-                        let mut count = 3;
-                        while matches!(peek!(), Some(' ')) {
-                            count += 1;
-                            chars.next(); // skip additional ' '
+                        c if is_identifier_start(c) => {
+                            let source = source!(c.len_utf8(), is_identifier_continue);
+                            token!(keyword(source), source);
                         }
-                        one!(DOC_COMMENT_START, count);
-
-                        let source = source!(0, |c| !matches!(c, '\n'));
-                        if !source.is_empty() {
-                            token!(SyntaxKind::DOC_COMMENT_SEGMENT, source);
+                        c if is_whitespace(c) => many!(WHITESPACE, c.len_utf8(), is_whitespace),
+                        c if is_tab(c) => many!(TAB, c.len_utf8(), is_tab),
+                        '\n' => one!(NEW_LINE),
+                        '-' => one!(HYPHEN),
+                        ',' => one!(COMMA),
+                        ';' => one!(SEMICOLON),
+                        ':' => one!(COLON),
+                        '!' => one!(EXCLAMATION),
+                        '?' => one!(QUESTION),
+                        '.' => one!(DOT),
+                        '(' => one!(LEFT_PAREN),
+                        ')' => one!(RIGHT_PAREN),
+                        '[' => one!(LEFT_BRACKET),
+                        ']' => one!(RIGHT_BRACKET),
+                        // Block start
+                        '{' => {
+                            one!(LEFT_BRACE);
+                            push!(Mode::Base {
+                                depth: depth + 1,
+                                in_interpolation: false,
+                            });
                         }
-                    }
-                    // Line comment
-                    '/' if matches!(peek!(), Some('/')) => {
-                        chars.next(); // skip second '/'
-
-                        let mut count = 2;
-                        while matches!(peek!(), Some(' ')) {
-                            count += 1;
-                            chars.next(); // skip additional '/'
+                        // Interpolation end
+                        '}' if in_interpolation => {
+                            one!(INTERPOLATION_END);
+                            pop!();
+                        }
+                        // Block end
+                        '}' => {
+                            one!(RIGHT_BRACE);
+                            pop!();
                         }
-                        one!(LINE_COMMENT_START, count);
+                        '@' => one!(AT),
+                        '*' => one!(ASTERISK),
+                        // Doc Comment
+                        '/' if matches!(peek!(), Some('/')) && matches!(nth!(1), Some('/')) => {
+                            chars.next(); // skip second '/'
+                            chars.next(); // skip third '/'
+
+                            let mut count = 3;
+                            while matches!(peek!(), Some(' ')) {
+                                count += 1;
+                                chars.next(); // skip additional ' '
+                            }
+                            one!(DOC_COMMENT_START, count);
 
-                        let source = source!(0, |c| !matches!(c, '\n'));
-                        if !source.is_empty() {
-                            token!(SyntaxKind::LINE_COMMENT_SEGMENT, source);
+                            let source = source!(0, |c| !matches!(c, '\n'));
+                            if !source.is_empty() {
+                                token!(SyntaxKind::DOC_COMMENT_SEGMENT, source);
+                            }
+                        }
+                        // Line comment
+                        '/' if matches!(peek!(), Some('/')) => {
+                            chars.next(); // skip second '/'
+
+                            let mut count = 2;
+                            while matches!(peek!(), Some(' ')) {
+                                count += 1;
+                                chars.next(); // skip additional '/'
+                            }
+                            one!(LINE_COMMENT_START, count);
+
+                            let source = source!(0, |c| !matches!(c, '\n'));
+                            if !source.is_empty() {
+                                token!(SyntaxKind::LINE_COMMENT_SEGMENT, source);
+                            }
+                        }
+                        '/' => one!(SLASH),
+                        '&' => one!(AMPERSAND),
+                        '#' => one!(HASH),
+                        '%' => one!(PERCENT),
+                        '^' => one!(CARET),
+                        '+' => one!(PLUS),
+                        '<' => one!(LEFT_CHEVRON),
+                        '=' => one!(EQUAL),
+                        '>' => one!(RIGHT_CHEVRON),
+                        '|' => one!(PIPE),
+                        '~' => one!(TILDE),
+                        other => {
+                            let start = index;
+
+                            many!(ERROR, other.len_utf8(), unexpected);
+                            error!(LexErrorKind::UnexpectedCharacter, start);
                         }
                     }
-                    '/' => one!(SLASH),
-                    '&' => one!(AMPERSAND),
-                    '#' => one!(HASH),
-                    '%' => one!(PERCENT),
-                    '^' => one!(CARET),
-                    '+' => one!(PLUS),
-                    '<' => one!(LEFT_CHEVRON),
-                    '=' => one!(EQUAL),
-                    '>' => one!(RIGHT_CHEVRON),
-                    '|' => one!(PIPE),
-                    '~' => one!(TILDE),
-                    other => many!(ERROR, other.len_utf8(), unexpected),
                 }
-            }
-            Mode::InString {
-                multiline: true, ..
-            } if starts_with!("\"\"\"") => {
-                chars.next(); // skip first '"'
-                chars.next(); // skip second '"'
-                chars.next(); // skip third '"'
-
-                one!(STRING_END, "\"\"\"".len());
-                modes.pop();
-            }
-            Mode::InString {
-                multiline: false, ..
-            } if starts_with!("\"") => {
-                chars.next(); // skip '"'
-
-                one!(STRING_END);
-                modes.pop();
-            }
-            Mode::InString {
-                multiline: false, ..
-            } if starts_with!("\n") => {
-                modes.pop();
-            }
-            Mode::InString { depth, .. } if starts_with!("${") => {
-                chars.next(); // skip '$'
-                chars.next(); // skip '{'
-
-                one!(INTERPOLATION_START, "${".len());
-                modes.push(Mode::Base {
-                    depth: depth + 1,
-                    in_interpolation: true,
-                });
-            }
-            Mode::InString { .. }
-                if starts_with!("\\\\")
-                    || starts_with!("\\\'")
-                    || starts_with!("\\\"")
-                    || starts_with!("\\n")
-                    || starts_with!("\\t") =>
-            {
-                chars.next(); // skip '\'
-                chars.next(); // skip escaped char
-
-                one!(ESCAPE_START, 1); // skip '\'
-                one!(ESCAPE_SEGMENT, 1); // skip escaped char
-            }
-            Mode::InString { .. } if starts_with!("\\u{") => {
-                chars.next(); // skip '\'
-                chars.next(); // skip 'u'
-                chars.next(); // skip '{'
-                one!(UNICODE_START, 3);
+                Mode::InString {
+                    multiline: true, ..
+                } if starts_with!("\"\"\"") => {
+                    chars.next(); // skip first '"'
+                    chars.next(); // skip second '"'
+                    chars.next(); // skip third '"'
+
+                    one!(STRING_END, "\"\"\"".len());
+                    pop!();
+                }
+                Mode::InString {
+                    multiline: false, ..
+                } if starts_with!("\"") => {
+                    chars.next(); // skip '"'
 
-                let source = source!(0, |c: char| c.is_ascii_hexdigit());
-                if source.is_empty() {
-                    continue 'lex;
+                    one!(STRING_END);
+                    pop!();
+                }
+                Mode::InString {
+                    multiline: false, ..
+                } if starts_with!("\n") => {
+                    let start = starts.last().map_or(0, |start| usize::from(*start));
+                    error!(LexErrorKind::UnterminatedString, start);
+                    pop!();
+                }
+                Mode::InString { depth, .. } if starts_with!("${") => {
+                    chars.next(); // skip '$'
+                    chars.next(); // skip '{'
+
+                    one!(INTERPOLATION_START, "${".len());
+                    push!(Mode::Base {
+                        depth: depth + 1,
+                        in_interpolation: true,
+                    });
+                }
+                Mode::InString { .. } if starts_with!("\\u{") => unicode!(),
+                Mode::InString { .. } if escape(&source[index..]).is_some() => {
+                    let count = escape(&source[index..]).unwrap_or_default();
+                    escape!(count);
                 }
-                token!(SyntaxKind::UNICODE_SEGMENT, source);
+                Mode::InString {
+                    multiline: false, ..
+                } => {
+                    let mut count = 0;
+                    let mut source = &source[index + count..];
+                    loop {
+                        if source.starts_with('"')
+                            || source.starts_with('\n')
+                            || source.starts_with("${")
+                            || escape(source).is_some()
+                            || source.starts_with("\\u{")
+                        {
+                            break;
+                        }
 
-                loop {
-                    let source = source!(0, |c: char| c == '_');
-                    if source.is_empty() {
-                        break;
+                        if let Some(c) = chars.next() {
+                            count += c.len_utf8();
+                            source = &source[c.len_utf8()..];
+                        } else {
+                            break;
+                        }
                     }
-                    token!(SyntaxKind::NUMERIC_SEPARATOR, source);
 
-                    let source = source!(0, |c: char| c.is_ascii_hexdigit());
-                    if source.is_empty() {
-                        break;
-                    }
-                    token!(SyntaxKind::UNICODE_SEGMENT, source);
+                    one!(STRING_SEGMENT, count);
                 }
+                Mode::InString {
+                    multiline: true, ..
+                } => {
+                    let mut count = 0;
+                    let mut source = &source[index + count..];
+                    loop {
+                        if source.starts_with("\"\"\"")
+                            || source.starts_with("${")
+                            || escape(source).is_some()
+                            || source.starts_with("\\u{")
+                        {
+                            break;
+                        }
 
-                if let Some('}') = peek!() {
-                    chars.next(); // skip '}'
+                        if let Some(c) = chars.next() {
+                            count += c.len_utf8();
+                            source = &source[c.len_utf8()..];
+                        } else {
+                            break;
+                        }
+                    }
 
-                    one!(UNICODE_END, 1);
+                    one!(STRING_SEGMENT, count);
                 }
-            }
-            Mode::InString {
-                multiline: false, ..
-            } => {
-                let mut count = 0;
-                let mut source = &source[index + count..];
-                loop {
-                    if source.starts_with('"')
-                        || source.starts_with('\n')
-                        || source.starts_with("${")
-                        || source.starts_with("\\\\")
-                        || source.starts_with("\\\'")
-                        || source.starts_with("\\\"")
-                        || source.starts_with("\\n")
-                        || source.starts_with("\\t")
-                        || source.starts_with("\\u{")
-                    {
-                        break;
+                Mode::InRawString { pattern } if starts_with!(pattern) => {
+                    for _ in 0..pattern.len() {
+                        chars.next();
                     }
 
-                    if let Some(c) = chars.next() {
-                        count += c.len_utf8();
-                        source = &source[c.len_utf8()..];
-                    } else {
-                        break;
-                    }
+                    one!(RAW_STRING_END, pattern.len());
+                    pop!();
                 }
+                Mode::InRawString { pattern } => {
+                    let mut count = 0;
+                    let mut source = &source[index + count..];
+                    loop {
+                        if source.starts_with(pattern) {
+                            break;
+                        }
 
-                one!(STRING_SEGMENT, count);
-            }
-            Mode::InString {
-                multiline: true, ..
-            } => {
-                let mut count = 0;
-                let mut source = &source[index + count..];
-                loop {
-                    if source.starts_with("\"\"\"")
-                        || source.starts_with("${")
-                        || source.starts_with("\\\\")
-                        || source.starts_with("\\\'")
-                        || source.starts_with("\\\"")
-                        || source.starts_with("\\n")
-                        || source.starts_with("\\t")
-                        || source.starts_with("\\u{")
-                    {
-                        break;
+                        if let Some(c) = chars.next() {
+                            count += c.len_utf8();
+                            source = &source[c.len_utf8()..];
+                        } else {
+                            break;
+                        }
                     }
 
-                    if let Some(c) = chars.next() {
-                        count += c.len_utf8();
-                        source = &source[c.len_utf8()..];
-                    } else {
-                        break;
+                    if count != 0 {
+                        one!(RAW_STRING_SEGMENT, count);
                     }
                 }
-
-                one!(STRING_SEGMENT, count);
             }
-            Mode::InRawString { pattern } if starts_with!(pattern) => {
-                for _ in 0..pattern.len() {
-                    chars.next();
-                }
+        }
 
-                one!(RAW_STRING_END, pattern.len());
-                modes.pop();
-            }
-            Mode::InRawString { pattern } => {
-                let mut count = 0;
-                let mut source = &source[index + count..];
-                loop {
-                    if source.starts_with(pattern) {
-                        break;
-                    }
+        self.index = index;
+    }
+}
 
-                    if let Some(c) = chars.next() {
-                        count += c.len_utf8();
-                        source = &source[c.len_utf8()..];
-                    } else {
-                        break;
-                    }
-                }
+impl<'source> Iterator for Lexer<'source> {
+    type Item = Token<'source>;
 
-                if count != 0 {
-                    one!(STRING_SEGMENT, count);
-                }
-            }
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && self.index < self.source.len() {
+            self.step();
         }
+
+        self.pending.pop_front()
+    }
+}
+
+pub fn lex(source: &str) -> (Vec<(SyntaxKind, &str)>, Vec<LexError>) {
+    let mut lexer = Lexer::new(source);
+    let tokens = lexer
+        .by_ref()
+        .map(|token| (token.kind, token.text))
+        .collect();
+
+    (tokens, lexer.finish())
+}
+
+/// The length in bytes of the escape sequence at the start of `source`, including the `\`.
+///
+/// Characters and strings share one set of escapes:
+///
+/// | Escape     | Value                                     |
+/// |------------|-------------------------------------------|
+/// | `\\`       | backslash                                 |
+/// | `\'`       | single quote                              |
+/// | `\"`       | double quote                              |
+/// | `\$`       | dollar sign, so `\${` is not interpolated |
+/// | `\n`       | line feed                                 |
+/// | `\r`       | carriage return                           |
+/// | `\t`       | tab                                       |
+/// | `\0`       | nul                                       |
+/// | `\xNN`     | ASCII character, or any byte in bytes     |
+/// | `\u{NNNN}` | Unicode scalar value, lexed separately    |
+///
+/// Any other character after a `\` is lexed as an escape too, so that lowering can report it.
+fn escape(source: &str) -> Option<usize> {
+    let mut chars = source.strip_prefix('\\')?.chars();
+    match chars.next()? {
+        '\n' => None,
+        'x' => Some(2 + chars.take(2).take_while(|c| c.is_ascii_hexdigit()).count()),
+        c => Some(1 + c.len_utf8()),
     }
+}
 
-    tokens
+/// Whether `source`, the rest of a character literal after its content, reaches a `'`
+/// before any whitespace.
+fn closes(source: &str) -> bool {
+    source
+        .find(|c: char| c == '\'' || c.is_whitespace())
+        .is_some_and(|offset| offset > 0 && source[offset..].starts_with('\''))
 }
 
 const fn is_whitespace(c: char) -> bool {
@@ -655,12 +978,13 @@ fn is_identifier_continue(c: char) -> bool {
     matches!(c, '_') || is_xid_continue(c) || is_numeric(c)
 }
 
+/// Whether `c` cannot start a token, and so continues a run of `ERROR`.
 fn unexpected(c: char) -> bool {
-    is_numeric(c) || is_identifier_start(c) || is_punctuation(c)
+    !(is_numeric(c) || is_identifier_start(c) || is_punctuation(c) || matches!(c, '\'' | '"'))
 }
 
 const fn is_numeric(c: char) -> bool {
-    matches!(c, '0'..='9')
+    c.is_ascii_digit()
 }
 
 const fn is_punctuation(c: char) -> bool {
@@ -1,9 +1,9 @@
-use crate::lex;
+use crate::{Lexer, Mode, SyntaxKind, lex};
 
 #[test]
 fn punctuations() {
     let source = "\n\t~!@#%^&*-+=|:;,./?{}[]()<>";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -11,7 +11,7 @@ fn punctuations() {
 #[test]
 fn r#char() {
     let source = "'a' '\\'' '\\\\' '\\n' '\\t' '한' '\\u{AC00}' '\\u{AC_00}' '\\u{AC00_}'";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -19,7 +19,7 @@ fn r#char() {
 #[test]
 fn empty_string() {
     let source = r#""" """""""#;
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -27,7 +27,7 @@ fn empty_string() {
 #[test]
 fn non_multiline_string() {
     let source = r#""Hello, World!" "a${b}c" "\'" "\\" "\\" "\n" "\t" "\u{AC00}""#;
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -35,7 +35,7 @@ fn non_multiline_string() {
 #[test]
 fn multiline_string() {
     let source = r#""""Hello, "World"!""" """a${b}c""""#;
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -43,7 +43,7 @@ fn multiline_string() {
 #[test]
 fn raw_string() {
     let source = r####"r"Hello, World!" r#"Hello, "World"!""# r##"r#"Hello, "World"!""## r###"r##"Hello, "World"!""###"####;
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -51,7 +51,15 @@ fn raw_string() {
 #[test]
 fn similar_block() {
     let source = "\"a${b{c\"d${e}f\"g}h}i\"";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
+
+    insta::assert_debug_snapshot!(tokens);
+}
+
+#[test]
+fn string_after_identifier_in_interpolation() {
+    let source = "\"a${b{x\"d${e}f\"g}h}i\"";
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -59,7 +67,7 @@ fn similar_block() {
 #[test]
 fn binary() {
     let source = "0b0101 0b_0101 0b__0101 0b01_01 0b01__01 0b0101_ 0b0101__";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -68,7 +76,7 @@ fn binary() {
 fn octal() {
     let source =
         "0o01234567 0o_01234567 0o__01234567 0o01234_567 0o01234__567 0o01234567_ 0o01234567__";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -76,7 +84,7 @@ fn octal() {
 #[test]
 fn hex() {
     let source = "0x0123456789ABCDEFabcdef 0x_0123456789ABCDEFabcdef 0x__0123456789ABCDEFabcdef 0x01234_56789ABCDEFabcdef 0x01234__56789ABCDEFabcdef 0x0123456789ABCDEFabcdef_ 0x0123456789ABCDEFabcdef__";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -84,7 +92,7 @@ fn hex() {
 #[test]
 fn integer() {
     let source = "0 1 1234567890 0_1_2_3_4_5_6_7_8_9_0 0__1__2__3__4__5__6__7__8__9__0 1234567890_ 1234567890__";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -92,7 +100,31 @@ fn integer() {
 #[test]
 fn float() {
     let source = "0.0 1.0 1234567890.0987654321 0_1_2_3_4_5_6_7_8_9_0.0_9_8_7_6_5_4_3_2_1 0__1__2__3__4__5__6__7__8__9__0.0__9__8__7__6__5__4__3__2__1 1234567890.0987654321_ 1234567890.0987654321__ 1.0e10 1.0E10 1.0e+10 1.0E+10 1.0e-10 1.0E-10 1.0e+10_9_87 1.0E+10__9__8__7 1.0e10_9_87 1.0E10__9__8__7 1e10 1E10 1e+10 1E+10 1e-10 1E-10 1e+10_9_87 1E+10__9__8__7 1e10_9_87 1E10__9__8__7";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
+
+    insta::assert_debug_snapshot!(tokens);
+}
+
+#[test]
+fn numeric_suffix() {
+    let source = "10u8 1_000_i64 1.5f32 1e3f64 1f32 0b1010u8 0o17i16 0xFFi64 0x1f32 1.0u8 2abc";
+    let (tokens, _) = lex(source);
+
+    insta::assert_debug_snapshot!(tokens);
+}
+
+#[test]
+fn byte_and_c_string() {
+    let source = r##"b'a' b'\x7F' b"ab\n" br#"raw"# c"hi" bc"##;
+    let (tokens, _) = lex(source);
+
+    insta::assert_debug_snapshot!(tokens);
+}
+
+#[test]
+fn escapes() {
+    let source = r#"'\r' '\0' '\x41' '\$' '\q' '\é' "a\$b\${c}\x7F\u{1F600}\x4""#;
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -100,7 +132,7 @@ fn float() {
 #[test]
 fn identifier() {
     let source = "_ _foo _123 _foo123 foo foo_ foo123 foo123_ foo_foo foo_123 한글";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -108,7 +140,7 @@ fn identifier() {
 #[test]
 fn raw_identifier() {
     let source = "r#abc";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -116,7 +148,7 @@ fn raw_identifier() {
 #[test]
 fn keywords() {
     let source = "as await break const continue crate else enum false fn for if impl in let loop match mut mod pub return Self self static struct super trait true type use where while yield";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
 
     insta::assert_debug_snapshot!(tokens);
 }
@@ -124,7 +156,98 @@ fn keywords() {
 #[test]
 fn line_comment() {
     let source = "// This is a line comment\n// This is another line comment";
-    let tokens = lex(source);
+    let (tokens, _) = lex(source);
+
+    insta::assert_debug_snapshot!(tokens);
+}
+
+#[test]
+fn token_ranges() {
+    let source = "let s = \"a${b}\";";
+    let tokens: Vec<_> = Lexer::new(source).collect();
 
     insta::assert_debug_snapshot!(tokens);
 }
+
+#[test]
+fn resume_by_line() {
+    let source = "let s = \"\"\"${a}\n${b}\n\"\"\";\nlet d = 1;\n";
+
+    // Lex one line at a time, as a highlighter would.
+    let mut modes = vec![];
+    let mut states = vec![];
+    let mut by_line = vec![];
+    for line in source.split_inclusive('\n') {
+        let mut lexer = Lexer::resume(line, 0.into(), modes);
+        by_line.extend(lexer.by_ref().map(|token| (token.kind, token.text)));
+        modes = lexer.modes().unwrap().to_vec();
+        states.push(modes.clone());
+    }
+    assert_eq!(by_line, lex(source).0);
+    assert_eq!(
+        states[0],
+        [Mode::InString {
+            depth: 0,
+            multiline: true
+        }]
+    );
+    assert!(states[2].is_empty());
+
+    // Relex the rest of the source from the start of the third line.
+    let offset = source.match_indices('\n').nth(1).unwrap().0 + 1;
+    let rest: Vec<_> = Lexer::resume(source, (offset as u32).into(), states[1].clone()).collect();
+    let all: Vec<_> = Lexer::new(source).collect();
+    assert_eq!(rest, all[all.len() - rest.len()..]);
+    assert_eq!(usize::from(rest[0].range.start()), offset);
+}
+
+#[test]
+fn modes_between_tokens() {
+    let source = "\"a\\nb\"";
+    let mut lexer = Lexer::new(source);
+    let mut states = vec![];
+    while let Some(token) = lexer.next() {
+        states.push((token.kind, lexer.modes().map(<[Mode]>::len)));
+    }
+
+    // Resuming right after the `\\` would lex `n` as text.
+    assert_eq!(
+        states,
+        [
+            (SyntaxKind::STRING_START, Some(1)),
+            (SyntaxKind::STRING_SEGMENT, Some(1)),
+            (SyntaxKind::ESCAPE_START, None),
+            (SyntaxKind::ESCAPE_SEGMENT, Some(1)),
+            (SyntaxKind::STRING_SEGMENT, Some(1)),
+            (SyntaxKind::STRING_END, Some(0)),
+        ]
+    );
+}
+
+#[test]
+fn missing_digits() {
+    let source = "0b 0o_ 0x 0xu8 0x1";
+    let (_, errors) = lex(source);
+    let errors: Vec<_> = errors
+        .iter()
+        .map(|error| (error.kind.to_string(), &source[error.range]))
+        .collect();
+
+    assert_eq!(
+        errors,
+        [
+            ("Expected digits after base prefix `0b`".to_owned(), "0b"),
+            ("Expected digits after base prefix `0o`".to_owned(), "0o_"),
+            ("Expected digits after base prefix `0x`".to_owned(), "0x"),
+            ("Expected digits after base prefix `0x`".to_owned(), "0x"),
+        ]
+    );
+}
+
+#[test]
+fn errors() {
+    let source = "'' 'a \"text\n0b102 0o78 €€ $ '\\u{41'\n'\\u{zz}' 'ab' \"\\u{4g}\"\nr#\"raw";
+    let (_, errors) = lex(source);
+
+    insta::assert_debug_snapshot!(errors);
+}
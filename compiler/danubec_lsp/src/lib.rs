@@ -0,0 +1,496 @@
+#![warn(clippy::all)]
+
+#[cfg(test)]
+mod tests;
+
+use danubec_ast as ast;
+use danubec_semantic::{
+    CfgSet, Context, Database, Env, Outline, OutlineKind, Revision, STD_ROOT, SyntaxError,
+};
+use danubec_symbol::FileId;
+use danubec_syntax::{AstNode, SyntaxKind, SyntaxNode};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, DocumentSymbol, DocumentSymbolResponse, GotoDefinitionResponse,
+    Hover, HoverContents, HoverProviderCapability, InitializeParams, Location, MarkupContent,
+    MarkupKind, OneOf, Position, PublishDiagnosticsParams, Range, ServerCapabilities, SymbolKind,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, Uri,
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{DocumentSymbolRequest, GotoDefinition, HoverRequest, Request as _},
+};
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+/// What the server offers: whole documents are synced, and diagnostics are published on
+/// every change and save.
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                ..Default::default()
+            },
+        )),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..Default::default()
+    }
+}
+
+/// Serves `connection` until the client asks to shut down.
+pub fn run(connection: &Connection) -> Result<()> {
+    let capabilities = serde_json::to_value(capabilities())?;
+    let params = connection.initialize(capabilities)?;
+    let _: InitializeParams = serde_json::from_value(params)?;
+
+    let mut server = Server::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = server.request(request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                for notification in server.notification(notification)? {
+                    connection
+                        .sender
+                        .send(Message::Notification(notification))?;
+                }
+            }
+            Message::Response(_) => {
+                //
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct Server {
+    db: Database,
+    std: PathBuf,
+    /// The documents that were last given diagnostics, to clear them once they have none.
+    published: BTreeSet<PathBuf>,
+    analysis: Option<Analysis>,
+}
+
+/// The result of [`danubec_semantic::semantic`] for a crate root, as of a revision.
+struct Analysis {
+    root: PathBuf,
+    revision: Revision,
+    env: Env,
+    errors: Vec<(Option<FileId>, SyntaxError)>,
+}
+
+impl Server {
+    fn new() -> Self {
+        let mut db = Database::new();
+        let std = db.std();
+
+        Self {
+            db,
+            std,
+            published: BTreeSet::new(),
+            analysis: None,
+        }
+    }
+
+    fn request(&mut self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            DocumentSymbolRequest::METHOD => params::<DocumentSymbolRequest>(request.params)
+                .and_then(|params| {
+                    let symbols = path(&params.text_document.uri).map(|path| self.symbols(&path));
+                    serde_json::to_value(symbols.map(DocumentSymbolResponse::Nested))
+                }),
+            GotoDefinition::METHOD => params::<GotoDefinition>(request.params).and_then(|params| {
+                let position = params.text_document_position_params;
+                let location = path(&position.text_document.uri)
+                    .and_then(|path| self.definition(&path, position.position));
+                serde_json::to_value(location.map(GotoDefinitionResponse::Scalar))
+            }),
+            HoverRequest::METHOD => params::<HoverRequest>(request.params).and_then(|params| {
+                let position = params.text_document_position_params;
+                let hover = path(&position.text_document.uri)
+                    .and_then(|path| self.hover(&path, position.position));
+                serde_json::to_value(hover)
+            }),
+            method => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("Unsupported request `{}`", method),
+                );
+            }
+        };
+
+        match result {
+            Ok(result) => Response::new_ok(id, result),
+            Err(error) => Response::new_err(id, ErrorCode::InvalidParams as i32, error.to_string()),
+        }
+    }
+
+    fn notification(&mut self, notification: Notification) -> Result<Vec<Notification>> {
+        let params = notification.params;
+        let changed = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let document =
+                    serde_json::from_value::<DidOpenTextDocumentParams>(params)?.text_document;
+                path(&document.uri).map(|path| self.set_source(path, document.text))
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = serde_json::from_value(params)?;
+                // Only whole documents are synced, so the last change has all of the text.
+                let text = params
+                    .content_changes
+                    .into_iter()
+                    .last()
+                    .map(|change| change.text);
+                path(&params.text_document.uri)
+                    .zip(text)
+                    .map(|(path, text)| self.set_source(path, text))
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: DidSaveTextDocumentParams = serde_json::from_value(params)?;
+                path(&params.text_document.uri)
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = serde_json::from_value(params)?;
+                // Unsaved changes are dropped, so the file reads as it is on disk again.
+                if let Some(path) = path(&params.text_document.uri)
+                    && let Ok(text) = std::fs::read_to_string(&path)
+                {
+                    self.set_source(path, text);
+                }
+                None
+            }
+            _ => None,
+        };
+
+        Ok(changed
+            .map(|path| self.diagnostics(&path))
+            .unwrap_or_default())
+    }
+
+    fn set_source(&mut self, path: PathBuf, text: String) -> PathBuf {
+        let file = self.db.file(path.clone());
+        self.db.set_source(file, text);
+
+        path
+    }
+
+    /// Analyses the crate that `path` belongs to, reusing the last analysis if nothing
+    /// changed since.
+    fn analyse(&mut self, path: &Path) -> &Analysis {
+        let root = root(path);
+        let revision = self.db.revision();
+        let fresh = self
+            .analysis
+            .as_ref()
+            .is_some_and(|analysis| analysis.root == root && analysis.revision == revision);
+        if !fresh {
+            let context = Context {
+                root: root.clone(),
+                cfg: CfgSet::new(),
+                std: Some(self.std.clone()),
+            };
+            let (env, _, diagnostic) = danubec_semantic::semantic(&mut self.db, context);
+            let errors = self.db.diagnostics(&diagnostic);
+            self.analysis = Some(Analysis {
+                root,
+                revision,
+                env,
+                errors,
+            });
+        }
+
+        self.analysis.as_ref().unwrap()
+    }
+
+    /// Diagnostics for every file of the crate that `path` belongs to. Reports that point
+    /// into no file are shown at the start of `path`.
+    fn diagnostics(&mut self, path: &Path) -> Vec<Notification> {
+        let errors = self.analyse(path).errors.clone();
+
+        let mut files: Vec<(PathBuf, Vec<lsp_types::Diagnostic>)> = vec![];
+        for (file, error) in errors {
+            let file_path = match file.and_then(|file| self.db.fs().path(file)) {
+                Some(file_path) => file_path.clone(),
+                None => path.to_path_buf(),
+            };
+            if file_path.starts_with(STD_ROOT) {
+                continue;
+            }
+            // Without a file, the labels cannot be placed, and all is put at the start.
+            let source = file.and_then(|file| self.db.source(file));
+            let diagnostic = diagnostic(&error, source.as_deref().unwrap_or_default());
+            match files.iter_mut().find(|(path, _)| *path == file_path) {
+                Some((_, diagnostics)) => diagnostics.push(diagnostic),
+                None => files.push((file_path, vec![diagnostic])),
+            }
+        }
+
+        let published = std::mem::take(&mut self.published);
+        let cleared: Vec<_> = published
+            .into_iter()
+            .filter(|path| files.iter().all(|(file, _)| file != path))
+            .map(|path| (path, vec![]))
+            .collect();
+        self.published = files.iter().map(|(path, _)| path.clone()).collect();
+
+        files
+            .into_iter()
+            .chain(cleared)
+            .filter_map(|(path, diagnostics)| {
+                let params = PublishDiagnosticsParams {
+                    uri: uri(&path)?,
+                    diagnostics,
+                    version: None,
+                };
+                Some(Notification::new(
+                    PublishDiagnostics::METHOD.to_owned(),
+                    params,
+                ))
+            })
+            .collect()
+    }
+
+    fn symbols(&mut self, path: &Path) -> Vec<DocumentSymbol> {
+        let file = self.db.file(path.to_path_buf());
+        let source = self.db.source(file).unwrap_or_default();
+
+        self.db
+            .outline(file)
+            .into_iter()
+            .map(|outline| symbol(outline, &source))
+            .collect()
+    }
+
+    fn definition(&mut self, path: &Path, position: Position) -> Option<Location> {
+        let (_, definition) = self.reference(path, position)?;
+        let definition = &self.analysis.as_ref()?.env[definition];
+        let name = definition.definition.name.span.text_range();
+        let file = definition.file;
+
+        let target = self.db.fs().path(file)?.clone();
+        let source = self.db.source(file)?;
+
+        Some(Location {
+            uri: uri(&target)?,
+            range: range(&source, name.start().into()..name.end().into()),
+        })
+    }
+
+    fn hover(&mut self, path: &Path, position: Position) -> Option<Hover> {
+        let (span, definition) = self.reference(path, position)?;
+        let definition = &self.analysis.as_ref()?.env[definition];
+        let node = definition.definition.span;
+        let file = definition.file;
+
+        let root = self.db.parse(file)?.syntax();
+        let signature = signature(&node.try_to_node(&root)?);
+
+        let source = self.db.source(self.db.fs().lookup(path)?)?;
+        let name = span.text_range();
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```danube\n{}\n```", signature),
+            }),
+            range: Some(range(&source, name.start().into()..name.end().into())),
+        })
+    }
+
+    /// The definition that the name at `position` in `path` refers to.
+    fn reference(
+        &mut self,
+        path: &Path,
+        position: Position,
+    ) -> Option<(danubec_syntax::Span, danubec_symbol::DefinitionId)> {
+        let file = self.db.file(path.to_path_buf());
+        let source = self.db.source(file)?;
+        let offset = offset(&source, position);
+
+        self.analyse(path).env.reference_at(file, offset)
+    }
+}
+
+fn params<R>(params: serde_json::Value) -> serde_json::Result<R::Params>
+where
+    R: lsp_types::request::Request,
+{
+    serde_json::from_value(params)
+}
+
+/// The crate that `path` belongs to: the nearest `Danube.toml` above it, or else the file
+/// on its own.
+fn root(path: &Path) -> PathBuf {
+    path.ancestors()
+        .skip(1)
+        .map(|directory| directory.join(danubec_manifest::FILE_NAME))
+        .find(|manifest| manifest.is_file())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+fn diagnostic(error: &SyntaxError, source: &str) -> lsp_types::Diagnostic {
+    let severity = match error.severity {
+        Some(miette::Severity::Warning) => lsp_types::DiagnosticSeverity::WARNING,
+        Some(miette::Severity::Advice) => lsp_types::DiagnosticSeverity::HINT,
+        Some(miette::Severity::Error) | None => lsp_types::DiagnosticSeverity::ERROR,
+    };
+    let span = error
+        .labels
+        .first()
+        .map_or(0..0, |(range, _)| range.clone());
+
+    lsp_types::Diagnostic {
+        range: range(source, span),
+        severity: Some(severity),
+        source: Some("danubec".to_owned()),
+        message: error.message.clone(),
+        ..Default::default()
+    }
+}
+
+#[allow(deprecated)]
+fn symbol(outline: Outline, source: &str) -> DocumentSymbol {
+    let kind = match outline.kind {
+        OutlineKind::Function => SymbolKind::FUNCTION,
+        OutlineKind::Struct => SymbolKind::STRUCT,
+        OutlineKind::Field => SymbolKind::FIELD,
+        OutlineKind::Enum => SymbolKind::ENUM,
+        OutlineKind::Variant => SymbolKind::ENUM_MEMBER,
+        OutlineKind::Module => SymbolKind::MODULE,
+        OutlineKind::Trait => SymbolKind::INTERFACE,
+        OutlineKind::Constant | OutlineKind::Static => SymbolKind::CONSTANT,
+        OutlineKind::Type => SymbolKind::TYPE_PARAMETER,
+        OutlineKind::Implement => SymbolKind::OBJECT,
+    };
+    let children: Vec<_> = outline
+        .children
+        .into_iter()
+        .map(|child| symbol(child, source))
+        .collect();
+
+    DocumentSymbol {
+        name: outline.name,
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: range(source, outline.range),
+        selection_range: range(source, outline.selection),
+        children: (!children.is_empty()).then_some(children),
+    }
+}
+
+/// The text of a definition up to its body, which is all of it for definitions without
+/// one.
+fn signature(node: &SyntaxNode) -> String {
+    let start = node.text_range().start();
+    let end = if let Some(function) = ast::FunctionDefinition::cast(node.clone()) {
+        match function.body() {
+            Some(ast::FunctionBodyKind::Block(body)) => Some(body.syntax().text_range().start()),
+            _ => None,
+        }
+    } else if matches!(
+        node.kind(),
+        SyntaxKind::MODULE_DEFINITION_NODE
+            | SyntaxKind::TRAIT_DEFINITION_NODE
+            | SyntaxKind::IMPLEMENT_DEFINITION_NODE
+    ) {
+        node.descendants_with_tokens()
+            .find(|element| element.kind() == SyntaxKind::LEFT_BRACE)
+            .map(|element| element.text_range().start())
+    } else {
+        None
+    };
+
+    let text = node.text().to_string();
+    let end = end.map_or(text.len(), |end| usize::from(end - start));
+
+    text[..end].trim().to_owned()
+}
+
+/// The path of a `file://` URI.
+fn path(uri: &Uri) -> Option<PathBuf> {
+    if uri.scheme()?.as_str() != "file" {
+        return None;
+    }
+    let path = uri.path().as_estr().decode().into_string().ok()?;
+
+    Some(PathBuf::from(path.as_ref()))
+}
+
+/// The `file://` URI of an absolute `path`.
+fn uri(path: &Path) -> Option<Uri> {
+    let mut uri = String::from("file://");
+    for byte in path.to_str()?.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char);
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    Uri::from_str(&uri).ok()
+}
+
+/// Byte offsets to LSP positions, which count UTF-16 code units. Whitespace at the end is
+/// left out, as the spans of nodes take the trivia after them.
+fn range(source: &str, range: std::ops::Range<usize>) -> Range {
+    let end = source
+        .get(range.clone())
+        .map_or(range.end, |text| range.start + text.trim_end().len());
+
+    Range {
+        start: position(source, range.start),
+        end: position(source, end),
+    }
+}
+
+fn position(source: &str, offset: usize) -> Position {
+    let offset = offset.min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count();
+    let start = before.rfind('\n').map_or(0, |index| index + 1);
+    let character = before[start..].encode_utf16().count();
+
+    Position::new(line as u32, character as u32)
+}
+
+fn offset(source: &str, position: Position) -> usize {
+    let start: usize = source
+        .split_inclusive('\n')
+        .take(position.line as usize)
+        .map(str::len)
+        .sum();
+    let mut units = 0;
+    for (index, character) in source[start..].char_indices() {
+        if units >= position.character as usize || character == '\n' {
+            return start + index;
+        }
+        units += character.len_utf16();
+    }
+
+    source.len()
+}
@@ -0,0 +1,16 @@
+use lsp_server::Connection;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let (connection, io_threads) = Connection::stdio();
+    let result = danubec_lsp::run(&connection);
+    drop(connection);
+
+    match result.and_then(|()| Ok(io_threads.join()?)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("danubec-lsp: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use crate::run;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    DidChangeTextDocumentParams, DidOpenTextDocumentParams, DocumentSymbolParams,
+    GotoDefinitionParams, HoverParams, InitializeParams, InitializedParams, Position,
+    PublishDiagnosticsParams, TextDocumentContentChangeEvent, TextDocumentIdentifier,
+    TextDocumentItem, TextDocumentPositionParams, Uri, VersionedTextDocumentIdentifier,
+    notification::{
+        DidChangeTextDocument, DidOpenTextDocument, Exit, Initialized,
+        Notification as LspNotification, PublishDiagnostics,
+    },
+    request::{
+        DocumentSymbolRequest, GotoDefinition, HoverRequest, Initialize, Request as LspRequest,
+        Shutdown,
+    },
+};
+use serde_json::{Value, json};
+use std::{str::FromStr, thread::JoinHandle, time::Duration};
+
+/// A client talking to a server on another thread over an in-memory connection.
+struct Client {
+    connection: Connection,
+    server: JoinHandle<crate::Result<()>>,
+    next: i32,
+}
+
+impl Client {
+    fn new() -> Self {
+        let (server, connection) = Connection::memory();
+        let server = std::thread::spawn(move || run(&server));
+        let mut client = Self {
+            connection,
+            server,
+            next: 0,
+        };
+
+        client.request::<Initialize>(InitializeParams::default());
+        client.notify::<Initialized>(InitializedParams {});
+
+        client
+    }
+
+    fn request<R: LspRequest>(&mut self, params: R::Params) -> Response {
+        self.next += 1;
+        let id = RequestId::from(self.next);
+        let request = Request::new(id.clone(), R::METHOD.to_owned(), params);
+        self.connection
+            .sender
+            .send(Message::Request(request))
+            .unwrap();
+
+        match self.receive() {
+            Message::Response(response) if response.id == id => response,
+            message => panic!("expected a response to {}, got {:?}", R::METHOD, message),
+        }
+    }
+
+    /// The result of a request that is expected to succeed.
+    fn result<R: LspRequest>(&mut self, params: R::Params) -> Value {
+        let response = self.request::<R>(params);
+        assert!(response.error.is_none(), "{:?}", response.error);
+
+        response.result.unwrap()
+    }
+
+    fn notify<N: LspNotification>(&self, params: N::Params) {
+        let notification = Notification::new(N::METHOD.to_owned(), params);
+        self.connection
+            .sender
+            .send(Message::Notification(notification))
+            .unwrap();
+    }
+
+    fn open(&self, uri: &Uri, text: &str) {
+        self.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(
+                uri.clone(),
+                "danube".to_owned(),
+                0,
+                text.to_owned(),
+            ),
+        });
+    }
+
+    fn diagnostics(&self) -> PublishDiagnosticsParams {
+        match self.receive() {
+            Message::Notification(notification)
+                if notification.method == PublishDiagnostics::METHOD =>
+            {
+                serde_json::from_value(notification.params).unwrap()
+            }
+            message => panic!("expected diagnostics, got {:?}", message),
+        }
+    }
+
+    fn receive(&self) -> Message {
+        self.connection
+            .receiver
+            .recv_timeout(Duration::from_secs(30))
+            .unwrap()
+    }
+
+    fn shutdown(mut self) {
+        self.result::<Shutdown>(());
+        self.notify::<Exit>(());
+
+        self.server.join().unwrap().unwrap();
+    }
+}
+
+fn uri(path: &str) -> Uri {
+    Uri::from_str(&format!("file:///danubec-lsp-tests/{}", path)).unwrap()
+}
+
+fn position(uri: &Uri, line: u32, character: u32) -> TextDocumentPositionParams {
+    TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier::new(uri.clone()),
+        position: Position::new(line, character),
+    }
+}
+
+#[test]
+fn publishes_diagnostics() {
+    let client = Client::new();
+    let lib = uri("diagnostics/lib.dnb");
+    let module = uri("diagnostics/a.dnb");
+
+    client.open(&module, "const A: u8 = 300;\n");
+    let diagnostics = client.diagnostics();
+    assert_eq!(diagnostics.uri, module);
+
+    client.open(&lib, "mod a;\n");
+    let diagnostics = client.diagnostics();
+    assert_eq!(diagnostics.uri, module);
+    let [diagnostic] = &diagnostics.diagnostics[..] else {
+        panic!("{:?}", diagnostics);
+    };
+    assert_eq!(
+        serde_json::to_value(diagnostic.range).unwrap(),
+        json!({
+            "start": { "line": 0, "character": 14 },
+            "end": { "line": 0, "character": 17 },
+        })
+    );
+
+    client.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier::new(lib.clone(), 1),
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "fn f() {}\n".to_owned(),
+        }],
+    });
+    let diagnostics = client.diagnostics();
+    assert_eq!(diagnostics.uri, module);
+    assert!(diagnostics.diagnostics.is_empty());
+
+    client.shutdown();
+}
+
+#[test]
+fn document_symbols() {
+    let mut client = Client::new();
+    let lib = uri("symbols/lib.dnb");
+    client.open(
+        &lib,
+        "struct Point { x: i32 }\nimpl Point {\n    fn x(x: i32) -> i32 { x }\n}\n",
+    );
+
+    let symbols = client.result::<DocumentSymbolRequest>(DocumentSymbolParams {
+        text_document: TextDocumentIdentifier::new(lib.clone()),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let names = |symbols: &Value| -> Vec<(String, u64)> {
+        symbols
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|symbol| {
+                let name = symbol["name"].as_str().unwrap().to_owned();
+                (name, symbol["kind"].as_u64().unwrap())
+            })
+            .collect()
+    };
+    assert_eq!(
+        names(&symbols),
+        [("Point".to_owned(), 23), ("impl Point".to_owned(), 19)]
+    );
+    assert_eq!(names(&symbols[0]["children"]), [("x".to_owned(), 8)]);
+    assert_eq!(names(&symbols[1]["children"]), [("x".to_owned(), 12)]);
+    assert_eq!(
+        symbols[1]["range"],
+        json!({
+            "start": { "line": 1, "character": 0 },
+            "end": { "line": 3, "character": 1 },
+        })
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn definition_and_hover() {
+    let mut client = Client::new();
+    let lib = uri("definition/lib.dnb");
+    let module = uri("definition/shapes.dnb");
+    client.open(
+        &module,
+        "pub struct Point;\n\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n",
+    );
+    client.open(
+        &lib,
+        "mod shapes;\nuse shapes::Point;\n\nfn origin() -> Point {\n    shapes::add(1, 2);\n    Point\n}\n",
+    );
+
+    let definition = client.result::<GotoDefinition>(GotoDefinitionParams {
+        text_document_position_params: position(&lib, 3, 17),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    assert_eq!(
+        definition,
+        json!({
+            "uri": module.as_str(),
+            "range": {
+                "start": { "line": 0, "character": 11 },
+                "end": { "line": 0, "character": 16 },
+            },
+        })
+    );
+
+    let hover = client.result::<HoverRequest>(HoverParams {
+        text_document_position_params: position(&lib, 4, 13),
+        work_done_progress_params: Default::default(),
+    });
+    assert_eq!(
+        hover["contents"]["value"],
+        "```danube\nfn add(a: i32, b: i32) -> i32\n```"
+    );
+    assert_eq!(
+        hover["range"],
+        json!({
+            "start": { "line": 4, "character": 12 },
+            "end": { "line": 4, "character": 15 },
+        })
+    );
+
+    // Nothing is named on an empty line.
+    let nothing = client.result::<HoverRequest>(HoverParams {
+        text_document_position_params: position(&lib, 2, 0),
+        work_done_progress_params: Default::default(),
+    });
+    assert_eq!(nothing, Value::Null);
+
+    client.shutdown();
+}
+
+#[test]
+fn unsupported_request() {
+    let mut client = Client::new();
+
+    let response = client.request::<lsp_types::request::References>(lsp_types::ReferenceParams {
+        text_document_position: position(&uri("unsupported/lib.dnb"), 0, 0),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        context: lsp_types::ReferenceContext {
+            include_declaration: true,
+        },
+    });
+    assert_eq!(
+        response.error.map(|error| error.code),
+        Some(ErrorCode::MethodNotFound as i32)
+    );
+
+    client.shutdown();
+}
@@ -0,0 +1,355 @@
+#[macro_use]
+extern crate miette;
+
+#[cfg(test)]
+mod tests;
+
+use danubec_diagnostic::Diagnostic;
+use std::path::PathBuf;
+
+pub const FILE_NAME: &str = "Danube.toml";
+
+pub const DEFAULT_ENTRY: &str = "src/lib.dnb";
+
+/// The contents of a `Danube.toml`.
+///
+/// Only a subset of TOML is understood: `[table]` headers, `key = "string"` pairs,
+/// single-line inline tables and `#` comments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    pub package: Package,
+    pub dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    /// Relative to the directory of the manifest.
+    pub entry: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    /// The name the dependency is known by in `::name::item` paths.
+    pub name: String,
+    /// Directory containing the dependency's manifest, relative to this one.
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Table {
+    Root,
+    Package,
+    Dependencies,
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    String(String),
+    Table(Vec<(String, Value)>),
+}
+
+struct Cursor<'source> {
+    source: &'source str,
+    offset: usize,
+}
+
+pub fn parse(source: &str, diagnostic: &mut Diagnostic) -> Option<Manifest> {
+    let errors = diagnostic.iter().count();
+
+    let mut table = Table::Root;
+    let mut name = None;
+    let mut version = None;
+    let mut entry = None;
+    let mut dependencies: Vec<Dependency> = vec![];
+
+    for (index, line) in source.lines().enumerate() {
+        let line_number = index + 1;
+        let mut cursor = Cursor::new(line);
+        cursor.skip_whitespace();
+        if cursor.at_end() {
+            continue;
+        }
+
+        if cursor.eat('[') {
+            let header = cursor.key();
+            if !cursor.eat(']') || !cursor.at_end() {
+                diagnostic.report(miette!("Expected `]` at line {}", line_number));
+                continue;
+            }
+
+            table = match header.as_str() {
+                "package" => Table::Package,
+                "dependencies" => Table::Dependencies,
+                _ => {
+                    diagnostic.report(miette!(
+                        "Unknown table `[{}]` at line {}",
+                        header,
+                        line_number
+                    ));
+                    Table::Unknown
+                }
+            };
+            continue;
+        }
+
+        let (key, value) = match cursor.pair() {
+            Ok(pair) if cursor.at_end() => pair,
+            Ok(_) => {
+                diagnostic.report(miette!("Expected end of line at line {}", line_number));
+                continue;
+            }
+            Err(error) => {
+                diagnostic.report(miette!("{} at line {}", error, line_number));
+                continue;
+            }
+        };
+
+        match table {
+            Table::Root => {
+                diagnostic.report(miette!(
+                    "Key `{}` outside of a table at line {}",
+                    key,
+                    line_number
+                ));
+            }
+            Table::Package => {
+                let slot = match key.as_str() {
+                    "name" => &mut name,
+                    "version" => &mut version,
+                    "entry" => &mut entry,
+                    _ => {
+                        diagnostic.report(miette!(
+                            "Unknown key `{}` in `[package]` at line {}",
+                            key,
+                            line_number
+                        ));
+                        continue;
+                    }
+                };
+                let Value::String(value) = value else {
+                    diagnostic.report(miette!(
+                        "Expected a string for `{}` at line {}",
+                        key,
+                        line_number
+                    ));
+                    continue;
+                };
+                if slot.replace(value).is_some() {
+                    diagnostic.report(miette!("Duplicate key `{}` at line {}", key, line_number));
+                }
+            }
+            Table::Dependencies => {
+                let Some(path) = dependency_path(value) else {
+                    diagnostic.report(miette!(
+                        "Expected `{} = {{ path = \"...\" }}` at line {}",
+                        key,
+                        line_number
+                    ));
+                    continue;
+                };
+                if !is_identifier(&key) {
+                    diagnostic.report(miette!(
+                        "Dependency name `{}` is not an identifier at line {}",
+                        key,
+                        line_number
+                    ));
+                    continue;
+                }
+                if dependencies.iter().any(|dependency| dependency.name == key) {
+                    diagnostic.report(miette!(
+                        "Duplicate dependency `{}` at line {}",
+                        key,
+                        line_number
+                    ));
+                    continue;
+                }
+
+                dependencies.push(Dependency {
+                    name: key,
+                    path: PathBuf::from(path),
+                });
+            }
+            Table::Unknown => {
+                //
+            }
+        }
+    }
+
+    let Some(name) = name else {
+        diagnostic.report(miette!("Missing `name` in `[package]`"));
+        return None;
+    };
+    if !is_identifier(&name) {
+        diagnostic.report(miette!("Package name `{}` is not an identifier", name));
+    }
+    let Some(version) = version else {
+        diagnostic.report(miette!("Missing `version` in `[package]`"));
+        return None;
+    };
+    if !is_version(&version) {
+        diagnostic.report(miette!(
+            "Version `{}` is not of the form `major.minor.patch`",
+            version
+        ));
+    }
+
+    if diagnostic.iter().count() > errors {
+        return None;
+    }
+
+    Some(Manifest {
+        package: Package {
+            name,
+            version,
+            entry: PathBuf::from(entry.as_deref().unwrap_or(DEFAULT_ENTRY)),
+        },
+        dependencies,
+    })
+}
+
+fn dependency_path(value: Value) -> Option<String> {
+    match value {
+        Value::Table(mut pairs) if pairs.len() == 1 && pairs[0].0 == "path" => match pairs.pop() {
+            Some((_, Value::String(path))) => Some(path),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_version(version: &str) -> bool {
+    let parts: Vec<_> = version.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+impl<'source> Cursor<'source> {
+    const fn new(source: &'source str) -> Self {
+        Self { source, offset: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.offset..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(expected) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether only whitespace and a comment are left.
+    fn at_end(&mut self) -> bool {
+        self.skip_whitespace();
+        matches!(self.peek(), None | Some('#'))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.bump();
+        }
+    }
+
+    fn key(&mut self) -> String {
+        self.skip_whitespace();
+        let start = self.offset;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            self.bump();
+        }
+
+        self.source[start..self.offset].to_owned()
+    }
+
+    fn pair(&mut self) -> Result<(String, Value), String> {
+        let key = self.key();
+        if key.is_empty() {
+            return Err("Expected key".to_owned());
+        }
+        if !self.eat('=') {
+            return Err(format!("Expected `=` after `{}`", key));
+        }
+        let value = self.value()?;
+
+        Ok((key, value))
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') => self.string().map(Value::String),
+            Some('{') => self.table().map(Value::Table),
+            _ => Err("Expected a string or an inline table".to_owned()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.bump(); // eat '"'
+
+        let mut string = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(string),
+                Some('\\') => match self.bump() {
+                    Some('"') => string.push('"'),
+                    Some('\\') => string.push('\\'),
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some(c) => return Err(format!("Unknown escape `\\{}`", c)),
+                    None => return Err("Unterminated string".to_owned()),
+                },
+                Some(c) => string.push(c),
+                None => return Err("Unterminated string".to_owned()),
+            }
+        }
+    }
+
+    fn table(&mut self) -> Result<Vec<(String, Value)>, String> {
+        self.bump(); // eat '{'
+
+        let mut pairs = vec![];
+        if self.eat('}') {
+            return Ok(pairs);
+        }
+
+        loop {
+            let (key, value) = self.pair()?;
+            if pairs.iter().any(|(k, _)| k == &key) {
+                return Err(format!("Duplicate key `{}`", key));
+            }
+            pairs.push((key, value));
+
+            if self.eat('}') {
+                return Ok(pairs);
+            }
+            if !self.eat(',') {
+                return Err("Expected `,` or `}`".to_owned());
+            }
+        }
+    }
+}
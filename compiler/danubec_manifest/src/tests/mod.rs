@@ -0,0 +1,70 @@
+use danubec_diagnostic::Diagnostic;
+
+fn errors(source: &str) -> Vec<String> {
+    let mut diagnostic = Diagnostic::new();
+    assert_eq!(crate::parse(source, &mut diagnostic), None);
+
+    diagnostic.iter().map(|report| report.to_string()).collect()
+}
+
+#[test]
+fn manifest() {
+    let source = r#"
+# The application.
+[package]
+name = "app"
+version = "0.1.0"
+entry = "src/main.dnb" # relative to this file
+
+[dependencies]
+util = { path = "../util" }
+std = { path = "../../library/std" }
+"#;
+    let mut diagnostic = Diagnostic::new();
+    let manifest = crate::parse(source, &mut diagnostic);
+    assert!(diagnostic.is_empty());
+
+    insta::assert_debug_snapshot!(manifest);
+}
+
+#[test]
+fn default_entry() {
+    let source = r#"
+[package]
+name = "util"
+version = "1.2.3"
+"#;
+    let mut diagnostic = Diagnostic::new();
+    let manifest = crate::parse(source, &mut diagnostic).unwrap();
+
+    assert_eq!(manifest.package.entry.to_str(), Some(crate::DEFAULT_ENTRY));
+    assert!(manifest.dependencies.is_empty());
+}
+
+#[test]
+fn invalid() {
+    let source = r#"
+edition = "2024"
+[package
+[package]
+name = "my-app"
+name = "app"
+version = "1.0"
+authors = "someone"
+[dependencies]
+util = "../util"
+util2 = { path = "../util", version = "1.0.0" }
+dup = { path = "a" }
+dup = { path = "b" }
+broken = { path = "unterminated }
+[profile]
+opt = "3"
+"#;
+
+    insta::assert_debug_snapshot!(errors(source));
+}
+
+#[test]
+fn missing_package() {
+    insta::assert_debug_snapshot!(errors("[dependencies]\n"));
+}
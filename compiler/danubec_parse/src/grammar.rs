@@ -55,6 +55,11 @@ impl<'source> Context<'source> {
         self.tokens.bump();
     }
 
+    #[inline]
+    fn remaining(&self) -> usize {
+        self.tokens.remaining()
+    }
+
     #[inline]
     fn at(&self, kind: SyntaxKind) -> bool {
         self.nth_at(0, kind)
@@ -153,6 +158,27 @@ pub(crate) fn root(p: &mut Context) {
     p.complete(m, ROOT_NODE);
 }
 
+/// Parses a single node with `entry` and reports any trailing input.
+/// On error, everything is wrapped in one `ERROR_NODE`, so the fragment
+/// always has a single root.
+pub(crate) fn fragment<T>(p: &mut Context, entry: impl FnOnce(&mut Context) -> T) {
+    let m = p.start();
+    let reports = p.diagnostic.len();
+
+    entry(p);
+
+    if !p.at(END_OF_FILE) {
+        while !p.at(END_OF_FILE) {
+            p.bump();
+        }
+        p.report(vec![m], miette!("Expected end of input"));
+    } else if p.diagnostic.len() != reports {
+        p.complete(m, ERROR_NODE);
+    } else {
+        m.terminate();
+    }
+}
+
 pub(crate) fn top_level_attributes(p: &mut Context) {
     while at!(p, [HASH, EXCLAMATION]) {
         top_level_attribute(p);
@@ -197,7 +223,7 @@ pub(crate) fn attribute_argument(p: &mut Context) -> CompleteMarker {
 
             expression(p);
 
-            p.complete(m, ATTRIBUTE_ARGUMENT_NODE)
+            p.complete(m, EXPRESSION_ATTRIBUTE_ARGUMENT_NODE)
         }
         _ if at_path(p) => {
             let m = p.start();
@@ -241,11 +267,12 @@ const DEFINITION_START: [SyntaxKind; 12] = [
 
 pub(crate) fn definition(p: &mut Context) {
     let m = p.start();
-    let m1 = p.start();
 
     attributes(p);
     visibility_modifier(p);
 
+    let m1 = p.start();
+
     match p.nth(0) {
         kind if matches!(kind, FN) => {
             function_definition(p, m1);
@@ -301,10 +328,9 @@ pub(crate) fn visibility_modifier(p: &mut Context) -> CompleteMarker {
             );
         }
         path(p);
+        expect!(p, RIGHT_PAREN, [m], ')');
     }
 
-    expect!(p, RIGHT_PAREN, [m], ')');
-
     p.complete(m, VISIBILITY_NODE)
 }
 
@@ -557,6 +583,7 @@ pub(crate) fn associated_item(p: &mut Context) -> CompleteMarker {
     let m = p.start();
 
     attributes(p);
+    visibility_modifier(p);
 
     let m1 = p.start();
 
@@ -590,7 +617,7 @@ pub(crate) fn function_parameter(p: &mut Context) -> CompleteMarker {
 
     attributes(p);
 
-    if !at_path(p) {
+    if !at_path(p) && !p.at(MUT) {
         return p.report(vec![m], miette!("Expected function parameter name"));
     }
     pattern(p);
@@ -695,6 +722,7 @@ pub(crate) fn enum_variant(p: &mut Context) {
     let m = p.start();
 
     attributes(p);
+    visibility_modifier(p);
     identifier(p);
     if p.at(EQUAL) {
         enum_variant_scalar(p, m);
@@ -925,11 +953,6 @@ pub(crate) fn path_pattern(p: &mut Context, m: Marker) -> CompleteMarker {
 }
 
 pub(crate) fn named_pattern(p: &mut Context, m: Marker) -> CompleteMarker {
-    if !at_path(p) {
-        return p.report(vec![m], miette!("Expected path"));
-    }
-
-    path(p);
     expect!(p, LEFT_BRACE, [m], '{');
     while !current!(p, [RIGHT_BRACE, END_OF_FILE]) {
         named_pattern_field(p);
@@ -957,11 +980,6 @@ pub(crate) fn named_pattern_field(p: &mut Context) -> CompleteMarker {
 }
 
 pub(crate) fn unnamed_pattern(p: &mut Context, m: Marker) -> CompleteMarker {
-    if !at_path(p) {
-        return p.report(vec![m], miette!("Expected path"));
-    }
-
-    path(p);
     expect!(p, LEFT_PAREN, [m], '(');
     while !current!(p, [RIGHT_PAREN, END_OF_FILE]) {
         pattern(p);
@@ -1115,6 +1133,10 @@ const LITERAL_FIRST: Tokens = tokens![
     CHARACTER_START,
     STRING_START,
     RAW_STRING_START,
+    BYTE_START,
+    BYTE_STRING_START,
+    RAW_BYTE_STRING_START,
+    C_STRING_START,
     INTEGER_SEGMENT,
     BINARY_START,
     OCTAL_START,
@@ -1201,7 +1223,8 @@ pub(crate) fn unary_expression(p: &mut Context) -> CompleteMarker {
         p.complete(m1, UNARY_OPERATOR_NODE);
     }
 
-    expression(p);
+    // Binds tighter than any binary operator, but not than `?`, calls, indexing and fields.
+    expression_bp(p, 24);
 
     p.complete(m, UNARY_EXPRESSION_NODE)
 }
@@ -1352,8 +1375,8 @@ pub(crate) fn assignment_expression(p: &mut Context, m: Marker, _bp: usize) -> C
     p.complete(m, ASSIGNMENT_EXPRESSION_NODE)
 }
 
-pub(crate) fn binary_expression(p: &mut Context, m: Marker, _bp: usize) -> CompleteMarker {
-    expression_bp(p, 0);
+pub(crate) fn binary_expression(p: &mut Context, m: Marker, bp: usize) -> CompleteMarker {
+    expression_bp(p, bp);
 
     p.complete(m, BINARY_EXPRESSION_NODE)
 }
@@ -1435,6 +1458,10 @@ pub(crate) fn primary_expression(p: &mut Context) -> CompleteMarker {
                 | CHARACTER_START
                 | STRING_START
                 | RAW_STRING_START
+                | BYTE_START
+                | BYTE_STRING_START
+                | RAW_BYTE_STRING_START
+                | C_STRING_START
                 | INTEGER_SEGMENT
                 | BINARY_START
                 | OCTAL_START
@@ -1659,8 +1686,19 @@ pub(crate) fn statement(p: &mut Context) -> CompleteMarker {
         return p.complete(m, LET_STATEMENT_NODE);
     }
 
+    if !p.at(HASH) && DEFINITION_START.contains(&p.nth(0)) {
+        definition(p);
+        return p.complete(m, DEFINITION_STATEMENT_NODE);
+    }
+
     if !p.at(HASH) {
+        let remaining = p.remaining();
         expression(p);
+        if p.remaining() == remaining {
+            // The error is already reported, skip the token to make progress.
+            p.bump();
+            return p.complete(m, ERROR_NODE);
+        }
         p.eat(SEMICOLON);
 
         return p.complete(m, EXPRESSION_STATEMENT_NODE);
@@ -1685,6 +1723,10 @@ pub(crate) fn literal(p: &mut Context) -> CompleteMarker {
         kind if matches!(kind, CHARACTER_START) => character_literal(p),
         kind if matches!(kind, STRING_START) => string_literal(p),
         kind if matches!(kind, RAW_STRING_START) => raw_string_literal(p),
+        kind if matches!(kind, BYTE_START) => byte_literal(p),
+        kind if matches!(kind, BYTE_STRING_START) => byte_string_literal(p),
+        kind if matches!(kind, RAW_BYTE_STRING_START) => raw_byte_string_literal(p),
+        kind if matches!(kind, C_STRING_START) => c_string_literal(p),
         kind if matches!(kind, INTEGER_SEGMENT) => numeric_literal(p),
         kind if matches!(kind, BINARY_START) => binary_literal(p),
         kind if matches!(kind, OCTAL_START) => octal_literal(p),
@@ -1709,21 +1751,44 @@ pub(crate) fn character_literal(p: &mut Context) -> CompleteMarker {
 
     expect!(p, CHARACTER_START, [m], '\'');
 
-    if p.at(CHARACTER_SEGMENT) {
-        p.bump(); // eat character segment
-    } else if p.at(ESCAPE_START) {
-        p.bump(); // eat '\'
-        expect!(p, ESCAPE_SEGMENT, [m], "escape sequence");
+    character_literal_rest(p, m, CHARACTER_LITERAL_NODE)
+}
+
+pub(crate) fn byte_literal(p: &mut Context) -> CompleteMarker {
+    let m = p.start();
+
+    expect!(p, BYTE_START, [m], "b'");
+
+    character_literal_rest(p, m, BYTE_LITERAL_NODE)
+}
+
+/// The content and closing quote of a character or byte literal: `a'`, `\n'`, `\u{1F600}'`
+fn character_literal_rest(p: &mut Context, m: Marker, kind: SyntaxKind) -> CompleteMarker {
+    let m1 = p.start();
+    if p.eat(CHARACTER_SEGMENT) {
+        p.complete(m1, CHARACTER_LITERAL_ONE_NODE);
+    } else if p.eat(ESCAPE_START) {
+        expect!(p, ESCAPE_SEGMENT, [m1, m], "escape sequence");
+        p.complete(m1, CHARACTER_LITERAL_ESCAPE_NODE);
+    } else if p.eat(UNICODE_START) {
+        unicode_segments(p);
+        p.eat(UNICODE_END);
+        p.complete(m1, CHARACTER_LITERAL_UNICODE_NODE);
     } else {
-        return p.report(
-            vec![m],
-            miette!("Expected character segment or escape sequence"),
-        );
+        // The lexer reports empty and unterminated characters.
+        m1.terminate();
+    }
+
+    if p.at(ERROR) {
+        // The lexer reports characters after the first one.
+        let m2 = p.start();
+        p.bump();
+        p.complete(m2, ERROR_NODE);
     }
 
-    expect!(p, CHARACTER_END, [m], '\'');
+    p.eat(CHARACTER_END);
 
-    p.complete(m, CHARACTER_LITERAL_NODE)
+    p.complete(m, kind)
 }
 
 pub(crate) fn string_literal(p: &mut Context) -> CompleteMarker {
@@ -1731,6 +1796,27 @@ pub(crate) fn string_literal(p: &mut Context) -> CompleteMarker {
 
     expect!(p, STRING_START, [m], '"');
 
+    string_literal_rest(p, m, STRING_LITERAL_NODE)
+}
+
+pub(crate) fn byte_string_literal(p: &mut Context) -> CompleteMarker {
+    let m = p.start();
+
+    expect!(p, BYTE_STRING_START, [m], "b\"");
+
+    string_literal_rest(p, m, BYTE_STRING_LITERAL_NODE)
+}
+
+pub(crate) fn c_string_literal(p: &mut Context) -> CompleteMarker {
+    let m = p.start();
+
+    expect!(p, C_STRING_START, [m], "c\"");
+
+    string_literal_rest(p, m, C_STRING_LITERAL_NODE)
+}
+
+/// The segments and closing quote of a string, byte string or C string literal.
+fn string_literal_rest(p: &mut Context, m: Marker, kind: SyntaxKind) -> CompleteMarker {
     while !current!(p, [STRING_END, END_OF_FILE]) {
         let m1 = p.start();
 
@@ -1750,7 +1836,7 @@ pub(crate) fn string_literal(p: &mut Context) -> CompleteMarker {
         }
 
         if p.eat(ESCAPE_START) {
-            expect!(p, ESCAPE_START, [m1, m], '\\');
+            expect!(p, ESCAPE_SEGMENT, [m1, m], "escape sequence");
 
             p.complete(m1, STRING_LITERAL_ESCAPE_NODE);
 
@@ -1758,12 +1844,8 @@ pub(crate) fn string_literal(p: &mut Context) -> CompleteMarker {
         }
 
         if p.eat(UNICODE_START) {
-            while p.eat(UNICODE_SEGMENT) {
-                if !p.eat(NUMERIC_SEPARATOR) {
-                    break;
-                }
-            }
-            expect!(p, UNICODE_END, [m1, m], '}');
+            unicode_segments(p);
+            p.eat(UNICODE_END);
 
             p.complete(m1, STRING_LITERAL_UNICODE_NODE);
 
@@ -1775,21 +1857,49 @@ pub(crate) fn string_literal(p: &mut Context) -> CompleteMarker {
         break;
     }
 
-    expect!(p, STRING_END, [m], '"');
+    // The lexer reports unterminated strings.
+    p.eat(STRING_END);
 
-    p.complete(m, STRING_LITERAL_NODE)
+    p.complete(m, kind)
+}
+
+fn unicode_segments(p: &mut Context) {
+    while p.eat(UNICODE_SEGMENT) {
+        if !p.eat(NUMERIC_SEPARATOR) {
+            break;
+        }
+    }
 }
 
 pub(crate) fn raw_string_literal(p: &mut Context) -> CompleteMarker {
     let m = p.start();
 
     expect!(p, RAW_STRING_START, [m], "string start");
-    expect!(p, RAW_STRING_SEGMENT, [m], "string segment");
-    expect!(p, RAW_STRING_END, [m], "string end");
+    raw_string_segment(p);
+    p.eat(RAW_STRING_END);
 
     p.complete(m, STRING_LITERAL_NODE)
 }
 
+pub(crate) fn raw_byte_string_literal(p: &mut Context) -> CompleteMarker {
+    let m = p.start();
+
+    expect!(p, RAW_BYTE_STRING_START, [m], "byte string start");
+    raw_string_segment(p);
+    p.eat(RAW_STRING_END);
+
+    p.complete(m, BYTE_STRING_LITERAL_NODE)
+}
+
+/// The verbatim text of a raw string, which is empty in `r""`.
+fn raw_string_segment(p: &mut Context) {
+    if p.at(RAW_STRING_SEGMENT) {
+        let m = p.start();
+        p.bump(); // eat raw string segment
+        p.complete(m, STRING_LITERAL_TEXT_NODE);
+    }
+}
+
 pub(crate) fn numeric_literal(p: &mut Context) -> CompleteMarker {
     let m = p.start();
 
@@ -1803,6 +1913,7 @@ pub(crate) fn numeric_literal(p: &mut Context) -> CompleteMarker {
     }
 
     if !current!(p, [FRACTION_START, EXPONENT_START]) {
+        p.eat(NUMERIC_SUFFIX);
         return p.complete(m, INTEGER_LITERAL_NODE);
     }
 
@@ -1825,6 +1936,8 @@ pub(crate) fn numeric_literal(p: &mut Context) -> CompleteMarker {
         }
     }
 
+    p.eat(NUMERIC_SUFFIX);
+
     p.complete(m, FLOAT_LITERAL_NODE)
 }
 
@@ -1832,12 +1945,9 @@ pub(crate) fn binary_literal(p: &mut Context) -> CompleteMarker {
     let m = p.start();
 
     expect!(p, BINARY_START, [m], "0b");
-    expect!(p, BINARY_SEGMENT, [m], "binary literal");
-    while p.eat(NUMERIC_SEPARATOR) {
-        if !p.eat(BINARY_SEGMENT) {
-            break;
-        }
-    }
+    // The lexer reports a prefix without digits.
+    while p.eat(BINARY_SEGMENT) || p.eat(NUMERIC_SEPARATOR) {}
+    p.eat(NUMERIC_SUFFIX);
 
     p.complete(m, BINARY_NUMERIC_LITERAL_NODE)
 }
@@ -1846,12 +1956,9 @@ pub(crate) fn octal_literal(p: &mut Context) -> CompleteMarker {
     let m = p.start();
 
     expect!(p, OCTAL_START, [m], "0o");
-    expect!(p, OCTAL_SEGMENT, [m], "octal literal");
-    while p.eat(NUMERIC_SEPARATOR) {
-        if !p.eat(OCTAL_SEGMENT) {
-            break;
-        }
-    }
+    // The lexer reports a prefix without digits.
+    while p.eat(OCTAL_SEGMENT) || p.eat(NUMERIC_SEPARATOR) {}
+    p.eat(NUMERIC_SUFFIX);
 
     p.complete(m, OCTAL_NUMERIC_LITERAL_NODE)
 }
@@ -1860,12 +1967,9 @@ pub(crate) fn hex_literal(p: &mut Context) -> CompleteMarker {
     let m = p.start();
 
     expect!(p, HEX_START, [m], "0x");
-    expect!(p, HEX_SEGMENT, [m], "hexadecimal literal");
-    while p.eat(NUMERIC_SEPARATOR) {
-        if !p.eat(HEX_SEGMENT) {
-            break;
-        }
-    }
+    // The lexer reports a prefix without digits.
+    while p.eat(HEX_SEGMENT) || p.eat(NUMERIC_SEPARATOR) {}
+    p.eat(NUMERIC_SUFFIX);
 
     p.complete(m, HEX_NUMERIC_LITERAL_NODE)
 }
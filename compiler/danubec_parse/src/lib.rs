@@ -10,9 +10,11 @@ mod tokens;
 mod tests;
 
 pub mod parse;
+pub mod reparse;
 
 mod event;
 mod grammar;
 mod token_stream;
 
 pub use parse::*;
+pub use reparse::*;
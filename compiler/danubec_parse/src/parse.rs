@@ -1,27 +1,66 @@
 use crate::{
     event::Event,
-    grammar::{Context, root},
+    grammar::{
+        Context, definition, expression, fragment, pattern, root, statement, type_expression,
+    },
 };
 use danubec_diagnostic::Diagnostic;
 use danubec_lex::lex;
 use danubec_syntax::{GreenNodeBuilder, SyntaxKind, SyntaxNode};
 
 pub fn parse(source: &str, diagnostic: &mut Diagnostic) -> SyntaxNode {
-    let mut parse = |tokens: &[_]| {
-        let tokens: Vec<_> = tokens.iter().map(|&(kind, _)| kind).collect();
-        let mut context = Context::new(&tokens, diagnostic);
-        root(&mut context);
+    parse_with(source, diagnostic, root)
+}
+
+/// Parses `source` as a single expression, e.g. `a + 1`.
+pub fn parse_expression(source: &str, diagnostic: &mut Diagnostic) -> SyntaxNode {
+    parse_with(source, diagnostic, |p| fragment(p, expression))
+}
+
+/// Parses `source` as a single type, e.g. `(i32, [String])`.
+pub fn parse_type(source: &str, diagnostic: &mut Diagnostic) -> SyntaxNode {
+    parse_with(source, diagnostic, |p| fragment(p, type_expression))
+}
+
+/// Parses `source` as a single pattern, e.g. `(a, [b, _])`.
+pub fn parse_pattern(source: &str, diagnostic: &mut Diagnostic) -> SyntaxNode {
+    parse_with(source, diagnostic, |p| fragment(p, pattern))
+}
+
+/// Parses `source` as a single statement, e.g. `let a = 1;`.
+pub fn parse_statement(source: &str, diagnostic: &mut Diagnostic) -> SyntaxNode {
+    parse_with(source, diagnostic, |p| fragment(p, statement))
+}
 
-        context.finish()
-    };
+/// Parses `source` as a single definition, e.g. `fn foo() {}`.
+pub fn parse_definition(source: &str, diagnostic: &mut Diagnostic) -> SyntaxNode {
+    parse_with(source, diagnostic, |p| fragment(p, definition))
+}
+
+fn parse_with(
+    source: &str,
+    diagnostic: &mut Diagnostic,
+    entry: impl FnOnce(&mut Context),
+) -> SyntaxNode {
+    let (tokens, errors) = lex(source);
+    for error in errors {
+        let range = usize::from(error.range.start())..usize::from(error.range.end());
+        diagnostic.report(miette!(
+            labels = vec![miette::LabeledSpan::underline(range)],
+            "{}",
+            error.kind
+        ));
+    }
 
-    let tokens = lex(source);
-    let events = parse(&tokens);
+    let kinds: Vec<_> = tokens.iter().map(|&(kind, _)| kind).collect();
+    let mut context = Context::new(&kinds, diagnostic);
+    entry(&mut context);
+    let events = context.finish();
 
     build(&tokens, events)
 }
 
-fn build(mut tokens: &[(SyntaxKind, &str)], mut events: Vec<Event>) -> SyntaxNode {
+pub(crate) fn build(mut tokens: &[(SyntaxKind, &str)], mut events: Vec<Event>) -> SyntaxNode {
     let mut builder = GreenNodeBuilder::new();
 
     macro_rules! advance {
@@ -0,0 +1,181 @@
+use crate::{
+    grammar::{self, Context},
+    parse::{build, parse},
+};
+use danubec_diagnostic::Diagnostic;
+use danubec_lex::lex;
+use danubec_syntax::{
+    GreenNode, GreenToken, NodeOrToken,
+    SyntaxKind::{self, *},
+    SyntaxNode, SyntaxToken, TextRange, TextSize,
+};
+
+/// Replaces the text in `range` with `insert`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: TextRange,
+    pub insert: String,
+}
+
+impl TextEdit {
+    pub fn new(range: TextRange, insert: impl Into<String>) -> Self {
+        Self {
+            range,
+            insert: insert.into(),
+        }
+    }
+
+    /// Applies the edit to `text`, which starts at `offset` in the edited source.
+    /// The edit must lie within `text`.
+    pub fn apply(&self, text: &str, offset: TextSize) -> String {
+        let start = usize::from(self.range.start() - offset);
+        let end = usize::from(self.range.end() - offset);
+
+        let mut edited = String::with_capacity(text.len() - (end - start) + self.insert.len());
+        edited.push_str(&text[..start]);
+        edited.push_str(&self.insert);
+        edited.push_str(&text[end..]);
+
+        edited
+    }
+}
+
+/// Applies `edit` to the tree `old` produced by [`parse`], reusing every green
+/// subtree the edit does not touch.
+///
+/// Tries, in order:
+/// - relexing the single token containing the edit, if it stays one token of the same kind;
+/// - reparsing the smallest `BLOCK_EXPRESSION_NODE` or `DEFINITION_NODE` strictly
+///   containing the edit, if the new text parses cleanly as the same node;
+/// - parsing the whole edited source.
+///
+/// The result is always the tree a full parse of the edited source would produce.
+///
+/// Reports no diagnostics, on any path: the reused subtrees carry no record of the
+/// errors found in them. Callers that need the errors of the edited source must
+/// [`parse`] it again.
+pub fn reparse(old: &SyntaxNode, edit: TextEdit) -> SyntaxNode {
+    if let Some(node) = relex(old, &edit) {
+        return node;
+    }
+    if let Some(node) = reparse_node(old, &edit) {
+        return node;
+    }
+
+    let source = edit.apply(&old.text().to_string(), old.text_range().start());
+
+    parse(&source, &mut Diagnostic::new())
+}
+
+fn relex(root: &SyntaxNode, edit: &TextEdit) -> Option<SyntaxNode> {
+    let token = root.covering_element(edit.range).into_token()?;
+    let kind = token.kind();
+    if !matches!(kind, IDENTIFIER | WHITESPACE | INTEGER_SEGMENT) {
+        return None;
+    }
+
+    let text = edit.apply(token.text(), token.text_range().start());
+    let previous = token.prev_token();
+    let next = token.next_token();
+    let source = surround(&previous, &text, &next);
+    if !matches!(lex_between(&source, &previous, &next)?[..], [(k, _)] if k == kind) {
+        return None;
+    }
+
+    let green = token.replace_with(GreenToken::new(kind.into(), &text));
+
+    Some(SyntaxNode::new_root(green))
+}
+
+fn reparse_node(root: &SyntaxNode, edit: &TextEdit) -> Option<SyntaxNode> {
+    let node = match root.covering_element(edit.range) {
+        NodeOrToken::Node(node) => node,
+        NodeOrToken::Token(token) => token.parent()?,
+    };
+    // Keeping the first and last characters keeps the opening token, though
+    // the last token may still grow, e.g. into a line comment.
+    let node = node.ancestors().find(|node| {
+        let range = node.text_range();
+
+        matches!(node.kind(), BLOCK_EXPRESSION_NODE | DEFINITION_NODE)
+            && range.start() < edit.range.start()
+            && edit.range.end() < range.end()
+    })?;
+
+    let text = edit.apply(&node.text().to_string(), node.text_range().start());
+    let previous = node.first_token()?.prev_token();
+    let next = node.last_token()?.next_token();
+    let source = surround(&previous, &text, &next);
+    let green = fragment(&lex_between(&source, &previous, &next)?, node.kind())?;
+
+    Some(SyntaxNode::new_root(node.replace_with(green)))
+}
+
+fn surround(previous: &Option<SyntaxToken>, text: &str, next: &Option<SyntaxToken>) -> String {
+    let mut source = String::new();
+    if let Some(token) = previous {
+        source.push_str(token.text());
+    }
+    source.push_str(text);
+    if let Some(token) = next {
+        source.push_str(token.text());
+    }
+
+    source
+}
+
+/// Lexes `source` built by [`surround`] and returns the tokens between
+/// `previous` and `next`, unless the new text merges with them, e.g. `x`
+/// becoming `b` in front of `'a'`.
+fn lex_between<'source>(
+    source: &'source str,
+    previous: &Option<SyntaxToken>,
+    next: &Option<SyntaxToken>,
+) -> Option<Vec<(SyntaxKind, &'source str)>> {
+    let (mut tokens, errors) = lex(source);
+    if !errors.is_empty() {
+        return None;
+    }
+
+    if let Some(next) = next {
+        if tokens.pop()? != (next.kind(), next.text()) {
+            return None;
+        }
+    }
+    if let Some(previous) = previous {
+        if tokens.first()? != &(previous.kind(), previous.text()) {
+            return None;
+        }
+        tokens.remove(0);
+    }
+
+    Some(tokens)
+}
+
+/// Parses `tokens` as a single `kind` node, rejecting any parse error, since
+/// recovery may depend on the surrounding tokens.
+fn fragment(tokens: &[(SyntaxKind, &str)], kind: SyntaxKind) -> Option<GreenNode> {
+    if tokens.first().is_none_or(|(kind, _)| kind.at_trivia()) {
+        return None;
+    }
+
+    let mut diagnostic = Diagnostic::new();
+    let kinds: Vec<_> = tokens.iter().map(|&(kind, _)| kind).collect();
+    let mut context = Context::new(&kinds, &mut diagnostic);
+    match kind {
+        BLOCK_EXPRESSION_NODE => grammar::fragment(&mut context, grammar::block_expression),
+        DEFINITION_NODE => grammar::fragment(&mut context, grammar::definition),
+        _ => return None,
+    }
+    let events = context.finish();
+    if !diagnostic.is_empty() {
+        return None;
+    }
+
+    let node = build(tokens, events);
+    if node.kind() != kind {
+        return None;
+    }
+
+    Some(node.green().into_owned())
+}
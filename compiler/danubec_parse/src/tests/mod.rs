@@ -1,13 +1,24 @@
 use danubec_diagnostic::Diagnostic;
 use danubec_syntax::SyntaxNode;
 
+/// Parses `source`, checking clean trees against the grammar.
 fn parse(source: &str) -> (SyntaxNode, Diagnostic) {
     let mut diagnostic = Diagnostic::new();
     let node = crate::parse(source, &mut diagnostic);
 
+    if diagnostic.is_empty() {
+        validate(source, &node);
+    }
+
     (node, diagnostic)
 }
 
+fn validate(source: &str, node: &SyntaxNode) {
+    if let Err(error) = danubec_grammar::validate(node) {
+        panic!("{source:?}: {error}");
+    }
+}
+
 #[test]
 fn top_level_attribute() {
     let source = r#"
@@ -238,6 +249,21 @@ fn foo() {
     insta::assert_debug_snapshot!(node);
 }
 
+#[test]
+fn operator_precedence() {
+    let source = r#"
+fn foo() {
+    a - b + c * d;
+    -a + b.c;
+    x = a == b && c < d;
+}
+"#;
+    let (node, diagnostic) = parse(&source);
+
+    assert!(diagnostic.is_empty());
+    insta::assert_debug_snapshot!(node);
+}
+
 #[test]
 fn primary_expression() {
     let source = r#"
@@ -277,3 +303,254 @@ fn foo() {
     assert!(diagnostic.is_empty());
     insta::assert_debug_snapshot!(node);
 }
+
+#[test]
+fn pattern() {
+    let source = r#"
+fn foo() {
+    match x {
+        Some(a) => {},
+        Foo { a: b, c: (d, _) } => {},
+        ::a::B => {},
+        [mut e, 1] => {},
+    }
+}"#;
+    let (node, diagnostic) = parse(&source);
+
+    assert!(diagnostic.is_empty(), "{diagnostic:?}");
+    insta::assert_debug_snapshot!(node);
+}
+
+#[test]
+fn local_definition() {
+    let source = r#"
+fn foo(mut a: i32) {
+    fn bar() {}
+    pub struct Baz;
+}"#;
+    let (node, diagnostic) = parse(&source);
+
+    assert!(diagnostic.is_empty(), "{diagnostic:?}");
+    insta::assert_debug_snapshot!(node);
+}
+
+#[test]
+fn misplaced_visibility() {
+    let source = r#"
+enum Foo {
+    pub Bar,
+}
+impl Foo {
+    pub fn baz() {}
+}"#;
+    let (node, diagnostic) = parse(&source);
+
+    assert!(diagnostic.is_empty(), "{diagnostic:?}");
+    insta::assert_debug_snapshot!(node);
+}
+
+#[test]
+fn numeric_suffix() {
+    let source = "const A: u8 = 10u8 + 0xFFu8;\nconst B = 1.5f32;\n";
+    let (node, diagnostic) = parse(source);
+
+    assert!(diagnostic.is_empty());
+    insta::assert_debug_snapshot!(node);
+}
+
+#[test]
+fn byte_and_c_string() {
+    let source = r##"const A = b'\x7F';
+const B = b"a\nb";
+const C = br#"raw"#;
+const D = c"hi\u{1F600}";
+"##;
+    let (node, diagnostic) = parse(source);
+
+    assert!(diagnostic.is_empty());
+    insta::assert_debug_snapshot!(node);
+}
+
+#[test]
+fn unterminated_string() {
+    let source = "const A = \"text;\nconst B = '';\n";
+    let (node, diagnostic) = parse(source);
+
+    let reports: Vec<_> = diagnostic.iter().map(|report| report.to_string()).collect();
+    assert_eq!(
+        reports,
+        [
+            "Unterminated string literal",
+            "Empty character literal",
+            "Expected `;`"
+        ]
+    );
+    insta::assert_debug_snapshot!(node);
+}
+
+#[test]
+fn statement_without_progress() {
+    // A statement that consumes nothing skips one token as an `ERROR_NODE`, so
+    // the block goes on with the next statement.
+    let source = "fn foo() {\n    ) a;\n}\n";
+    let (node, diagnostic) = parse(source);
+
+    let reports: Vec<_> = diagnostic.iter().map(|report| report.to_string()).collect();
+    assert_eq!(reports, ["Expected expression"]);
+    insta::assert_debug_snapshot!(node);
+}
+
+fn reparse(source: &str, edit: crate::TextEdit) -> SyntaxNode {
+    let (old, _) = parse(source);
+    let node = crate::reparse(&old, edit.clone());
+
+    let (expected, _) = parse(&edit.apply(source, 0.into()));
+    assert_eq!(
+        format!("{node:#?}"),
+        format!("{expected:#?}"),
+        "reparsing {source:?} with {edit:?}"
+    );
+
+    node
+}
+
+#[test]
+fn reparse_reuses_untouched_subtrees() {
+    use danubec_syntax::TextRange;
+
+    let source = "fn foo() {\n    let a = 1;\n}\n\nfn bar() {\n    a + 2;\n}\n";
+    let (old, _) = parse(source);
+    let bar = |node: &SyntaxNode| {
+        let definition = node.children().nth(1).unwrap();
+
+        &*definition.green() as *const _
+    };
+
+    // Relexes the identifier `a`.
+    let edit = crate::TextEdit::new(TextRange::new(19.into(), 20.into()), "abc");
+    let node = reparse(source, edit.clone());
+    assert_eq!(bar(&crate::reparse(&old, edit)), bar(&old));
+    assert_eq!(node.to_string(), source.replacen("let a", "let abc", 1));
+
+    // Reparses the body of `foo`.
+    let edit = crate::TextEdit::new(TextRange::empty(25.into()), "\n    foo();");
+    let node = reparse(source, edit.clone());
+    assert_eq!(bar(&crate::reparse(&old, edit)), bar(&old));
+    assert!(node.to_string().contains("foo();"));
+
+    // Unbalances the braces, which needs a full parse.
+    let edit = crate::TextEdit::new(TextRange::empty(25.into()), "{");
+    reparse(source, edit);
+}
+
+#[test]
+fn reparse_agrees_with_parse() {
+    use danubec_syntax::{TextRange, TextSize};
+
+    const SOURCES: [&str; 3] = [
+        "fn foo(a: i32) -> i32 {\n    let b = a + 1;\n    if b > 2 { b } else { 0 }\n}\n",
+        "struct Foo { a: i32 }\n\nimpl Foo {\n    fn bar(self) { self.a; }\n}\n",
+        "const A = \"a${b}c\";\n// comment\nfn baz() { let c = 'x'; 0x1F; }\n",
+    ];
+    const INSERTS: [&str; 18] = [
+        "",
+        "a",
+        "b",
+        "x1",
+        "_",
+        "0",
+        "12",
+        " ",
+        "\n",
+        "{",
+        "}",
+        ";",
+        "(",
+        "\"",
+        "'",
+        "//",
+        "fn",
+        "let y = 2;",
+    ];
+
+    // xorshift, so the edits are the same on every run.
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut random = |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+
+        (state % bound as u64) as usize
+    };
+
+    for source in SOURCES {
+        for _ in 0..1000 {
+            let start = random(source.len() + 1);
+            let end = (start + random(4)).min(source.len());
+            let range = TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32));
+            let edit = crate::TextEdit::new(range, INSERTS[random(INSERTS.len())]);
+
+            reparse(source, edit);
+        }
+    }
+}
+
+#[test]
+fn fragments() {
+    use danubec_syntax::SyntaxKind;
+
+    type Entry = fn(&str, &mut Diagnostic) -> SyntaxNode;
+
+    let fragments: [(Entry, &str); 5] = [
+        (crate::parse_expression, "a + b * 2"),
+        (crate::parse_type, "(i32, [String])"),
+        (crate::parse_pattern, "(a, [b, _])"),
+        (crate::parse_statement, "let a: i32 = 1;"),
+        (crate::parse_definition, "pub fn foo() {}"),
+    ];
+    let mut kinds = vec![];
+    for (entry, source) in fragments {
+        let mut diagnostic = Diagnostic::new();
+        let node = entry(source, &mut diagnostic);
+
+        assert!(diagnostic.is_empty(), "{source}: {diagnostic:?}");
+        assert_eq!(node.to_string(), source);
+        validate(source, &node);
+        kinds.push(node.kind());
+    }
+    assert_eq!(
+        kinds,
+        [
+            SyntaxKind::BINARY_EXPRESSION_NODE,
+            SyntaxKind::TUPLE_TYPE_NODE,
+            SyntaxKind::TUPLE_PATTERN_NODE,
+            SyntaxKind::LET_STATEMENT_NODE,
+            SyntaxKind::DEFINITION_NODE,
+        ]
+    );
+}
+
+#[test]
+fn fragment_trailing_input() {
+    let mut diagnostic = Diagnostic::new();
+    let node = crate::parse_expression("a + 1 b", &mut diagnostic);
+
+    let reports: Vec<_> = diagnostic.iter().map(|report| report.to_string()).collect();
+    assert_eq!(reports, ["Expected end of input"]);
+    insta::assert_debug_snapshot!(node);
+}
+
+#[test]
+fn standard_library() {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../library/std/src");
+    for entry in std::fs::read_dir(root).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|extension| extension != "dnb") {
+            continue;
+        }
+
+        let source = std::fs::read_to_string(&path).unwrap();
+        let (_, diagnostic) = parse(&source);
+        assert!(diagnostic.is_empty(), "{}: {diagnostic:?}", path.display());
+    }
+}
@@ -29,6 +29,11 @@ impl<'source> TokenStream<'source> {
             .unwrap_or(SyntaxKind::END_OF_FILE)
     }
 
+    // Get the number of tokens left, including trivia
+    pub fn remaining(&self) -> usize {
+        self.tokens.len()
+    }
+
     pub fn bump(&mut self) {
         self.advance();
         self.trivia();
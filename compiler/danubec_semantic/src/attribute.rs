@@ -0,0 +1,613 @@
+use danubec_diagnostic::Diagnostic;
+use danubec_hir as hir;
+use danubec_symbol::SymbolInterner;
+use miette::{LabeledSpan, Severity};
+use std::{ops::Range, path::PathBuf};
+
+/// The argument shape an attribute accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Template {
+    /// `#[name]`
+    Word,
+    /// `#[name = "value"]`
+    NameValue,
+    /// `#[name(a, b)]`
+    List,
+}
+
+/// What an attribute is written on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// `#![...]` in the root file of a crate.
+    Krate,
+    /// `#![...]` in any other file, or `#[...]` on `mod`.
+    Module,
+    Function,
+    Struct,
+    Enum,
+    Trait,
+    Implement,
+    Constant,
+    Static,
+    Type,
+    Use,
+    /// A struct field or a field of an enum variant.
+    Field,
+    Variant,
+    Parameter,
+    /// A statement in a block, other than a definition.
+    Statement,
+    /// A block expression.
+    Expression,
+}
+
+#[derive(Debug)]
+pub struct BuiltinAttribute {
+    pub name: &'static str,
+    pub templates: &'static [Template],
+    pub targets: &'static [Target],
+    /// Whether the attribute may be given more than once on the same target.
+    pub repeatable: bool,
+}
+
+/// The meaning of a built-in attribute, for later passes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Builtin {
+    NoPrelude,
+    Edition(String),
+    Lint {
+        level: LintLevel,
+        lints: Vec<String>,
+    },
+    Test,
+    /// The expected failure message, if any.
+    ShouldFail(Option<String>),
+    Inline(Inline),
+    Deprecated(Deprecation),
+    Cfg(Cfg),
+    Path(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inline {
+    Hint,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Deprecation {
+    pub since: Option<String>,
+    pub note: Option<String>,
+}
+
+/// A `#[cfg(...)]` predicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cfg {
+    /// `name`
+    Flag(String),
+    /// `name = "value"`
+    KeyValue(String, String),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    Not(Box<Cfg>),
+}
+
+pub const EDITIONS: [&str; 1] = ["2024"];
+
+const ITEMS: &[Target] = &[
+    Target::Module,
+    Target::Function,
+    Target::Struct,
+    Target::Enum,
+    Target::Trait,
+    Target::Implement,
+    Target::Constant,
+    Target::Static,
+    Target::Type,
+    Target::Use,
+];
+
+const ANYWHERE: &[Target] = &[
+    Target::Krate,
+    Target::Module,
+    Target::Function,
+    Target::Struct,
+    Target::Enum,
+    Target::Trait,
+    Target::Implement,
+    Target::Constant,
+    Target::Static,
+    Target::Type,
+    Target::Use,
+    Target::Field,
+    Target::Variant,
+    Target::Parameter,
+    Target::Statement,
+    Target::Expression,
+];
+
+/// Whatever can be left out of a build.
+const CONFIGURABLE: &[Target] = &[
+    Target::Module,
+    Target::Function,
+    Target::Struct,
+    Target::Enum,
+    Target::Trait,
+    Target::Implement,
+    Target::Constant,
+    Target::Static,
+    Target::Type,
+    Target::Use,
+    Target::Field,
+    Target::Variant,
+    Target::Parameter,
+    Target::Statement,
+];
+
+pub const BUILTIN_ATTRIBUTES: [BuiltinAttribute; 11] = [
+    BuiltinAttribute {
+        name: "no_prelude",
+        templates: &[Template::Word],
+        targets: &[Target::Krate],
+        repeatable: false,
+    },
+    BuiltinAttribute {
+        name: "edition",
+        templates: &[Template::NameValue],
+        targets: &[Target::Krate],
+        repeatable: false,
+    },
+    BuiltinAttribute {
+        name: "allow",
+        templates: &[Template::List],
+        targets: ANYWHERE,
+        repeatable: true,
+    },
+    BuiltinAttribute {
+        name: "warn",
+        templates: &[Template::List],
+        targets: ANYWHERE,
+        repeatable: true,
+    },
+    BuiltinAttribute {
+        name: "deny",
+        templates: &[Template::List],
+        targets: ANYWHERE,
+        repeatable: true,
+    },
+    BuiltinAttribute {
+        name: "test",
+        templates: &[Template::Word],
+        targets: &[Target::Function],
+        repeatable: false,
+    },
+    BuiltinAttribute {
+        name: "should_fail",
+        templates: &[Template::Word, Template::NameValue],
+        targets: &[Target::Function],
+        repeatable: false,
+    },
+    BuiltinAttribute {
+        name: "inline",
+        templates: &[Template::Word, Template::List],
+        targets: &[Target::Function],
+        repeatable: false,
+    },
+    BuiltinAttribute {
+        name: "deprecated",
+        templates: &[Template::Word, Template::NameValue, Template::List],
+        targets: ITEMS,
+        repeatable: false,
+    },
+    BuiltinAttribute {
+        name: "cfg",
+        templates: &[Template::List],
+        targets: CONFIGURABLE,
+        repeatable: true,
+    },
+    BuiltinAttribute {
+        name: "path",
+        templates: &[Template::NameValue],
+        targets: &[Target::Module],
+        repeatable: false,
+    },
+];
+
+/// Checks an attribute against the registry and interprets it.
+///
+/// `range` is where the attribute is written, `previous` are the attributes already
+/// accepted on the same target, and `inner` tells `#![...]` from `#[...]` in messages.
+/// Unknown attributes are only warned about, so that they can be given meaning later.
+pub fn check(
+    argument: &hir::AttributeArgument,
+    range: &Range<usize>,
+    target: Target,
+    previous: &[Builtin],
+    inner: bool,
+    symbols: &SymbolInterner,
+    diagnostic: &mut Diagnostic,
+) -> Option<Builtin> {
+    let hash = if inner { "#!" } else { "#" };
+    let Some(path) = argument_path(argument) else {
+        diagnostic.report(miette!(
+            labels = labels(range),
+            "Expected an attribute name: `{}[name]`",
+            hash
+        ));
+        return None;
+    };
+    let name = path_name(path, symbols);
+    let Some(builtin) = BuiltinAttribute::lookup(&name) else {
+        diagnostic.report(miette!(
+            labels = labels(range),
+            severity = Severity::Warning,
+            "Unknown attribute `{}[{}]`",
+            hash,
+            name
+        ));
+        return None;
+    };
+
+    let template = match &argument.kind {
+        hir::AttributeArgumentKind::KeyValue { value: None, .. } => Template::Word,
+        hir::AttributeArgumentKind::KeyValue { value: Some(_), .. } => Template::NameValue,
+        _ => Template::List,
+    };
+    if !builtin.templates.contains(&template) {
+        diagnostic.report(builtin.malformed(hash, range));
+        return None;
+    }
+
+    if !builtin.targets.contains(&target) {
+        if builtin.targets == [Target::Krate] {
+            diagnostic.report(miette!(
+                labels = labels(range),
+                "`{}[{}]` is only allowed at the crate root",
+                hash,
+                name
+            ));
+        } else {
+            diagnostic.report(miette!(
+                labels = labels(range),
+                "`{}[{}]` cannot be applied to {}",
+                hash,
+                name,
+                target.description()
+            ));
+        }
+        return None;
+    }
+
+    if !builtin.repeatable && previous.iter().any(|previous| previous.name() == name) {
+        diagnostic.report(miette!(
+            labels = labels(range),
+            "Duplicate attribute `{}[{}]`",
+            hash,
+            name
+        ));
+        return None;
+    }
+
+    builtin.interpret(argument, range, hash, symbols, diagnostic)
+}
+
+impl BuiltinAttribute {
+    pub fn lookup(name: &str) -> Option<&'static BuiltinAttribute> {
+        BUILTIN_ATTRIBUTES
+            .iter()
+            .find(|attribute| attribute.name == name)
+    }
+
+    fn malformed(&self, hash: &str, range: &Range<usize>) -> miette::Report {
+        let usages: Vec<_> = self
+            .templates
+            .iter()
+            .map(|template| match template {
+                Template::Word => format!("`{}[{}]`", hash, self.name),
+                Template::NameValue => format!("`{}[{} = \"...\"]`", hash, self.name),
+                Template::List => format!("`{}[{}(...)]`", hash, self.name),
+            })
+            .collect();
+
+        miette!(
+            labels = labels(range),
+            "Malformed `{}[{}]`, expected {}",
+            hash,
+            self.name,
+            usages.join(" or ")
+        )
+    }
+
+    fn interpret(
+        &self,
+        argument: &hir::AttributeArgument,
+        range: &Range<usize>,
+        hash: &str,
+        symbols: &SymbolInterner,
+        diagnostic: &mut Diagnostic,
+    ) -> Option<Builtin> {
+        let (value, arguments) = match &argument.kind {
+            hir::AttributeArgumentKind::KeyValue { value, .. } => (value.as_ref(), &[][..]),
+            hir::AttributeArgumentKind::Nested { arguments, .. } => (None, &arguments[..]),
+            hir::AttributeArgumentKind::Expression { .. } => (None, &[][..]),
+        };
+        let string = match value {
+            Some(value) => match string_value(value) {
+                Some(string) => Some(string),
+                None => {
+                    diagnostic.report(self.malformed(hash, range));
+                    return None;
+                }
+            },
+            None => None,
+        };
+
+        let builtin = match self.name {
+            "no_prelude" => Builtin::NoPrelude,
+            "edition" => {
+                let edition = string?;
+                if !EDITIONS.contains(&edition.as_str()) {
+                    diagnostic.report(miette!(
+                        labels = labels(range),
+                        "Unknown edition `{}`, expected one of: {}",
+                        edition,
+                        EDITIONS.join(", ")
+                    ));
+                    return None;
+                }
+
+                Builtin::Edition(edition)
+            }
+            "allow" | "warn" | "deny" => {
+                let level = match self.name {
+                    "allow" => LintLevel::Allow,
+                    "warn" => LintLevel::Warn,
+                    _ => LintLevel::Deny,
+                };
+                let lints: Option<Vec<_>> = arguments
+                    .iter()
+                    .map(|argument| word(argument, symbols))
+                    .collect();
+                let Some(lints) = lints else {
+                    diagnostic.report(self.malformed(hash, range));
+                    return None;
+                };
+
+                Builtin::Lint { level, lints }
+            }
+            "test" => Builtin::Test,
+            "should_fail" => Builtin::ShouldFail(string),
+            "inline" => {
+                let inline = match arguments {
+                    [] if matches!(argument.kind, hir::AttributeArgumentKind::KeyValue { .. }) => {
+                        Some(Inline::Hint)
+                    }
+                    [argument] => match word(argument, symbols).as_deref() {
+                        Some("always") => Some(Inline::Always),
+                        Some("never") => Some(Inline::Never),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let Some(inline) = inline else {
+                    diagnostic.report(miette!(labels = labels(range),
+                        "Malformed `{}[inline]`, expected `{}[inline]`, `{}[inline(always)]` or `{}[inline(never)]`",
+                        hash,
+                        hash,
+                        hash,
+                        hash
+                    ));
+                    return None;
+                };
+
+                Builtin::Inline(inline)
+            }
+            "deprecated" => {
+                let mut deprecation = Deprecation {
+                    since: None,
+                    note: string,
+                };
+                for argument in arguments {
+                    let (key, value) = match &argument.kind {
+                        hir::AttributeArgumentKind::KeyValue {
+                            key,
+                            value: Some(value),
+                        } => (path_name(key, symbols), string_value(value)),
+                        _ => (String::new(), None),
+                    };
+                    let slot = match key.as_str() {
+                        "since" => &mut deprecation.since,
+                        "note" => &mut deprecation.note,
+                        _ => {
+                            diagnostic.report(miette!(labels = labels(range),
+                                "Expected `since = \"...\"` or `note = \"...\"` in `{}[deprecated(...)]`",
+                                hash
+                            ));
+                            return None;
+                        }
+                    };
+                    let Some(value) = value else {
+                        diagnostic.report(miette!(
+                            labels = labels(range),
+                            "Expected a string for `{}` in `{}[deprecated(...)]`",
+                            key,
+                            hash
+                        ));
+                        return None;
+                    };
+                    *slot = Some(value);
+                }
+
+                Builtin::Deprecated(deprecation)
+            }
+            "cfg" => {
+                let predicate = match arguments {
+                    [argument] => cfg(argument, symbols),
+                    _ => None,
+                };
+                let Some(predicate) = predicate else {
+                    diagnostic.report(miette!(labels = labels(range),
+                        "Malformed `{}[cfg(...)]`, expected a single predicate: `name`, `name = \"value\"`, `all(...)`, `any(...)` or `not(...)`",
+                        hash
+                    ));
+                    return None;
+                };
+
+                Builtin::Cfg(predicate)
+            }
+            "path" => Builtin::Path(PathBuf::from(string?)),
+            _ => unreachable!("unregistered built-in attribute `{}`", self.name),
+        };
+
+        Some(builtin)
+    }
+}
+
+impl Builtin {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Builtin::NoPrelude => "no_prelude",
+            Builtin::Edition(_) => "edition",
+            Builtin::Lint {
+                level: LintLevel::Allow,
+                ..
+            } => "allow",
+            Builtin::Lint {
+                level: LintLevel::Warn,
+                ..
+            } => "warn",
+            Builtin::Lint {
+                level: LintLevel::Deny,
+                ..
+            } => "deny",
+            Builtin::Test => "test",
+            Builtin::ShouldFail(_) => "should_fail",
+            Builtin::Inline(_) => "inline",
+            Builtin::Deprecated(_) => "deprecated",
+            Builtin::Cfg(_) => "cfg",
+            Builtin::Path(_) => "path",
+        }
+    }
+}
+
+impl Target {
+    pub const fn description(self) -> &'static str {
+        match self {
+            Target::Krate => "a crate",
+            Target::Module => "a module",
+            Target::Function => "a function",
+            Target::Struct => "a struct",
+            Target::Enum => "an enum",
+            Target::Trait => "a trait",
+            Target::Implement => "an impl block",
+            Target::Constant => "a constant",
+            Target::Static => "a static",
+            Target::Type => "a type alias",
+            Target::Use => "a use declaration",
+            Target::Field => "a field",
+            Target::Variant => "an enum variant",
+            Target::Parameter => "a function parameter",
+            Target::Statement => "a statement",
+            Target::Expression => "an expression",
+        }
+    }
+}
+
+fn labels(range: &Range<usize>) -> Vec<LabeledSpan> {
+    vec![LabeledSpan::underline(range.clone())]
+}
+
+/// `name` in an argument list, without a value.
+fn word(argument: &hir::AttributeArgument, symbols: &SymbolInterner) -> Option<String> {
+    match &argument.kind {
+        hir::AttributeArgumentKind::KeyValue { key, value: None } => Some(path_name(key, symbols)),
+        _ => None,
+    }
+}
+
+fn cfg(argument: &hir::AttributeArgument, symbols: &SymbolInterner) -> Option<Cfg> {
+    match &argument.kind {
+        hir::AttributeArgumentKind::KeyValue { key, value: None } => {
+            Some(Cfg::Flag(path_name(key, symbols)))
+        }
+        hir::AttributeArgumentKind::KeyValue {
+            key,
+            value: Some(value),
+        } => Some(Cfg::KeyValue(path_name(key, symbols), string_value(value)?)),
+        hir::AttributeArgumentKind::Nested { path, arguments } => {
+            let arguments: Option<Vec<_>> = arguments
+                .iter()
+                .map(|argument| cfg(argument, symbols))
+                .collect();
+            match (path_name(path, symbols).as_str(), arguments?) {
+                ("all", arguments) => Some(Cfg::All(arguments)),
+                ("any", arguments) => Some(Cfg::Any(arguments)),
+                ("not", mut arguments) if arguments.len() == 1 => {
+                    Some(Cfg::Not(Box::new(arguments.pop()?)))
+                }
+                _ => None,
+            }
+        }
+        hir::AttributeArgumentKind::Expression { .. } => None,
+    }
+}
+
+/// The path naming an attribute: `name` in `#[name]`, `#[name = value]` and `#[name(...)]`.
+pub fn argument_path(argument: &hir::AttributeArgument) -> Option<&hir::Path> {
+    match &argument.kind {
+        hir::AttributeArgumentKind::KeyValue { key, .. } => Some(key),
+        hir::AttributeArgumentKind::Nested { path, .. } => Some(path),
+        hir::AttributeArgumentKind::Expression { .. } => None,
+    }
+}
+
+pub fn path_name(path: &hir::Path, symbols: &SymbolInterner) -> String {
+    let segments: Vec<_> = path
+        .segments
+        .iter()
+        .map(|segment| match &segment.kind {
+            hir::PathSegmentKind::Root => "",
+            hir::PathSegmentKind::Self_ => "self",
+            hir::PathSegmentKind::Super_ => "super",
+            hir::PathSegmentKind::Krate => "crate",
+            hir::PathSegmentKind::Identifier(identifier) => &symbols[identifier.symbol],
+        })
+        .collect();
+
+    segments.join("::")
+}
+
+/// The contents of a string literal without interpolations.
+pub fn string_value(expression: &hir::Expression) -> Option<String> {
+    let hir::ExpressionKind::Literal {
+        value:
+            hir::Literal {
+                kind: hir::LiteralKind::String { segments },
+                ..
+            },
+    } = &expression.kind
+    else {
+        return None;
+    };
+
+    let mut value = String::new();
+    for segment in segments {
+        match segment {
+            hir::StringSegment::Text { value: text } => value.push_str(text),
+            hir::StringSegment::Unicode { value: c } | hir::StringSegment::Escape { value: c } => {
+                value.push(*c)
+            }
+            hir::StringSegment::Interpolation { .. } => return None,
+        }
+    }
+
+    Some(value)
+}
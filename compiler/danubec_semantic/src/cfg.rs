@@ -0,0 +1,82 @@
+use crate::attribute::Cfg;
+use std::collections::BTreeSet;
+
+/// The options `#[cfg(...)]` predicates are evaluated against: flags such as `test`, and
+/// key-value pairs such as `feature = "x"`, which may be set more than once with different values.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CfgSet {
+    options: BTreeSet<(String, Option<String>)>,
+}
+
+impl CfgSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn flag(mut self, name: impl Into<String>) -> Self {
+        self.insert(name, None);
+        self
+    }
+
+    #[inline]
+    pub fn value(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.insert(name, Some(value.into()));
+        self
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: Option<String>) {
+        self.options.insert((name.into(), value));
+    }
+
+    /// Adds an option written as on the command line: `name` or `name="value"`.
+    pub fn parse(&mut self, option: &str) -> Result<(), String> {
+        let (name, value) = match option.split_once('=') {
+            Some((name, value)) => {
+                let value = value.trim();
+                let Some(value) = value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                else {
+                    return Err(format!(
+                        "Expected a quoted value in `{}`, e.g. `{}=\"{}\"`",
+                        option,
+                        name.trim(),
+                        value
+                    ));
+                };
+
+                (name.trim(), Some(value.to_owned()))
+            }
+            None => (option.trim(), None),
+        };
+
+        let mut chars = name.chars();
+        let identifier = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !identifier {
+            return Err(format!("Expected an identifier in `{}`", option));
+        }
+
+        self.insert(name, value);
+
+        Ok(())
+    }
+
+    pub fn contains(&self, name: &str, value: Option<&str>) -> bool {
+        self.options
+            .contains(&(name.to_owned(), value.map(str::to_owned)))
+    }
+
+    pub fn enabled(&self, cfg: &Cfg) -> bool {
+        match cfg {
+            Cfg::Flag(name) => self.contains(name, None),
+            Cfg::KeyValue(name, value) => self.contains(name, Some(value)),
+            Cfg::All(cfgs) => cfgs.iter().all(|cfg| self.enabled(cfg)),
+            Cfg::Any(cfgs) => cfgs.iter().any(|cfg| self.enabled(cfg)),
+            Cfg::Not(cfg) => !self.enabled(cfg),
+        }
+    }
+}
@@ -1,67 +1,111 @@
 use crate::{
+    CfgSet, Database,
+    attribute::{self, Builtin, Target},
     env::{Env, Namespace, Scope, ScopeKind},
-    fs::Fs,
+    fs::ModuleError,
+    primitive::{self, Primitive},
 };
 use danubec_ast as ast;
 use danubec_diagnostic::Diagnostic;
 use danubec_hir as hir;
-use danubec_parse::parse;
 use danubec_symbol::{
-    AttributeId, DefinitionId, FileId, ModuleId, ScopeId, Symbol, SymbolInterner,
+    AttributeId, DefinitionId, FileId, KrateId, ModuleId, ScopeId, Symbol, SymbolInterner,
+};
+use danubec_syntax::{AstNode, Span, SyntaxKind, SyntaxNode, SyntaxToken};
+use miette::NamedSource;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    num::IntErrorKind,
+    ops::Range,
+    path::PathBuf,
 };
-use danubec_syntax::{AstNode, Span};
-use std::collections::{HashMap, VecDeque};
 
 pub fn collect(
-    fs: &mut Fs,
+    db: &mut Database,
     env: &mut Env,
     symbols: &mut SymbolInterner,
     diagnostic: &mut Diagnostic,
-    root: FileId,
+    cfg: &CfgSet,
+    krate: KrateId,
 ) {
-    let root = env.module(root, None);
+    let root = env[krate].root;
 
     let mut queue = VecDeque::new();
     queue.push_back(root);
 
+    let mut files = HashSet::new();
+    files.insert(env[root].file);
+
     while let Some(module) = queue.pop_front() {
         let file = env[module].file;
-        let Some(source) = fs.source(file) else {
-            diagnostic.report(miette!("File not found: {:?}", fs.path(file)));
+        let Some(parse) = db.parse(file) else {
+            diagnostic.report(miette!("File not found: {:?}", db.fs().path(file)));
             continue;
         };
-        let node = parse(&source, diagnostic);
-        let node = ast::Root::cast(node).unwrap();
+        if let Some(source) = db.source(file) {
+            let path = db.fs().path(file).map(|path| path.display().to_string());
+            env.source(file, NamedSource::new(path.unwrap_or_default(), source));
+        }
+        let start = diagnostic.len();
+        for error in parse.errors() {
+            diagnostic.report(error.report());
+        }
+        let node = ast::Root::cast(parse.syntax()).unwrap();
 
-        {
-            let mut collector = DefinitionCollector::new(file, module, env, symbols, diagnostic);
-            collector.root(node.clone());
+        let external_modules = {
+            let mut collector =
+                DefinitionCollector::new(file, module, cfg, env, symbols, diagnostic);
+            collector.root(node);
+            collector.external_modules
+        };
+        if let Some(source) = env.source_code(file) {
+            diagnostic.with_source_code(start, source);
         }
 
-        for (definition, name) in external_modules(node, symbols) {
-            let Some(child_file) = fs.module(file, &symbols[name.symbol]) else {
-                diagnostic.report(miette!("Module '{}' not found", &symbols[name.symbol]));
-                continue;
+        for ExternalModule {
+            module: parent,
+            inline,
+            name,
+            path,
+        } in external_modules
+        {
+            let directory: PathBuf = inline.iter().map(|symbol| &symbols[*symbol]).collect();
+            let child_file = match path {
+                Some(path) => db.module_at(file, &directory.join(path)),
+                None => {
+                    let path = directory.join(&symbols[name.symbol]);
+                    db.module(file, &path.to_string_lossy())
+                }
             };
+            let child_file = match child_file {
+                Ok(child_file) => child_file,
+                Err(ModuleError::NotFound { tried }) => {
+                    diagnostic.report(miette!(
+                        "Module '{}' not found, tried {}",
+                        &symbols[name.symbol],
+                        paths(&tried)
+                    ));
+                    continue;
+                }
+                Err(ModuleError::Ambiguous { candidates }) => {
+                    diagnostic.report(miette!(
+                        "Module '{}' is ambiguous, found both {}",
+                        &symbols[name.symbol],
+                        paths(&candidates)
+                    ));
+                    continue;
+                }
+            };
+            if !files.insert(child_file) {
+                diagnostic.report(miette!(
+                    "Module '{}' refers to {:?}, which is already loaded as another module",
+                    &symbols[name.symbol],
+                    db.fs().path(child_file)
+                ));
+                continue;
+            }
 
-            let definition = env.definition(crate::env::Definition {
-                scope: env[module].scope,
-                definition: hir::Definition {
-                    attributes: vec![],
-                    visibility: hir::Visibility::Private,
-                    name,
-                    kind: hir::DefinitionKind::Module {
-                        kind: hir::ModuleDefinitionKind::External,
-                    },
-                    span: Span::new(definition.syntax()),
-                },
-                file,
-            });
-            let scope = env[module].scope;
-            env[scope].definition((Namespace::Type, name.symbol), definition);
-
-            let parent = module;
-            let child = env.module(child_file, Some(parent));
+            let child = env.module(krate, child_file, Some(parent));
             env[parent].children.insert(name.symbol, child);
 
             queue.push_back(child);
@@ -69,36 +113,126 @@ pub fn collect(
     }
 }
 
-fn external_modules(
-    node: ast::Root,
-    symbols: &mut SymbolInterner,
-) -> Vec<(ast::Definition, hir::Identifier)> {
-    use danubec_syntax::AstNode;
-
-    node.definitions()
-        .filter_map(|definition| match definition.kind() {
-            Some(ast::DefinitionKind::Module(module))
-                if matches!(module.kind(), Some(ast::ModuleDefinitionKind::External(_))) =>
-            {
-                let name = module.name()?;
-                let segment = name.segment()?;
-                let identifier = segment.identifier()?;
-                let identifier = hir::Identifier {
-                    symbol: symbols.intern(identifier.text()),
-                    span: Span::new(name.syntax()),
-                };
+enum NumericSuffix {
+    Integer(hir::IntegerSuffix),
+    Float(hir::FloatSuffix),
+}
 
-                Some((definition, identifier))
-            }
-            _ => None,
+/// The digits of a numeric literal, without its prefix, separators and suffix.
+fn digits(node: &SyntaxNode) -> String {
+    node.children_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| {
+            matches!(
+                token.kind(),
+                SyntaxKind::INTEGER_SEGMENT
+                    | SyntaxKind::FRACTION_START
+                    | SyntaxKind::FRACTION_SEGMENT
+                    | SyntaxKind::EXPONENT_START
+                    | SyntaxKind::EXPONENT_SIGN
+                    | SyntaxKind::EXPONENT_SEGMENT
+                    | SyntaxKind::BINARY_SEGMENT
+                    | SyntaxKind::OCTAL_SEGMENT
+                    | SyntaxKind::HEX_SEGMENT
+            )
         })
+        .map(|token| token.text().to_owned())
         .collect()
 }
 
+fn invalid_suffix(token: &SyntaxToken, literal: &str, label: &str) -> miette::Report {
+    let range = token.text_range();
+    let span = usize::from(range.start())..usize::from(range.end());
+
+    miette!(
+        labels = vec![miette::LabeledSpan::at(span, label)],
+        "Invalid suffix `{}` on {} literal",
+        token.text(),
+        literal
+    )
+}
+
+/// The value of an escape sequence after its `\`: `n`, `x7F`, etc.
+fn escape_value(text: &str) -> Option<u8> {
+    match text {
+        "\\" => Some(b'\\'),
+        "'" => Some(b'\''),
+        "\"" => Some(b'"'),
+        "$" => Some(b'$'),
+        "n" => Some(b'\n'),
+        "r" => Some(b'\r'),
+        "t" => Some(b'\t'),
+        "0" => Some(b'\0'),
+        text => match text.strip_prefix('x') {
+            Some(digits) if digits.len() == 2 => u8::from_str_radix(digits, 16).ok(),
+            _ => None,
+        },
+    }
+}
+
+/// The range of `node` without its trailing trivia.
+pub(crate) fn written(node: &SyntaxNode) -> Range<usize> {
+    let range = node.text_range();
+    let end = node
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .filter(|token| !token.kind().at_trivia())
+        .last()
+        .map_or(range.end(), |token| token.text_range().end());
+
+    usize::from(range.start())..usize::from(end)
+}
+
+/// A report whose `label` points at `range` in the source.
+fn labelled(range: rowan::TextRange, label: &str, message: String) -> miette::Report {
+    let span = usize::from(range.start())..usize::from(range.end());
+
+    miette!(
+        labels = vec![miette::LabeledSpan::at(span, label)],
+        "{}",
+        message
+    )
+}
+
+fn invalid_escape(node: &SyntaxNode, segment: &SyntaxToken, literal: &str) -> miette::Report {
+    let label = if segment.text().starts_with('x') {
+        "expected two hex digits"
+    } else {
+        "unknown escape"
+    };
+
+    labelled(
+        node.text_range(),
+        label,
+        format!(
+            "Invalid escape sequence `\\{}` in {} literal",
+            segment.text(),
+            literal
+        ),
+    )
+}
+
+fn paths(paths: &[PathBuf]) -> String {
+    let paths: Vec<_> = paths.iter().map(|path| format!("{:?}", path)).collect();
+    paths.join(" and ")
+}
+
+/// A `mod name;` whose file is looked up once its parent file is collected.
+struct ExternalModule {
+    module: ModuleId,
+    /// Inline modules between the file's module and `module`.
+    inline: Vec<Symbol>,
+    name: hir::Identifier,
+    path: Option<PathBuf>,
+}
+
 struct DefinitionCollector<'lowering> {
     file: FileId,
     module: ModuleId,
+    inline: Vec<Symbol>,
     scopes: Vec<ScopeId>,
+    external_modules: Vec<ExternalModule>,
+    cfg: &'lowering CfgSet,
     env: &'lowering mut Env,
     symbols: &'lowering mut SymbolInterner,
     diagnostic: &'lowering mut Diagnostic,
@@ -108,6 +242,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
     fn new(
         file: FileId,
         module: ModuleId,
+        cfg: &'lowering CfgSet,
         env: &'lowering mut Env,
         symbols: &'lowering mut SymbolInterner,
         diagnostic: &'lowering mut Diagnostic,
@@ -117,7 +252,10 @@ impl<'lowering> DefinitionCollector<'lowering> {
         Self {
             file,
             module,
+            inline: vec![],
             scopes,
+            external_modules: vec![],
+            cfg,
             env,
             symbols,
             diagnostic,
@@ -136,6 +274,21 @@ impl<'lowering> DefinitionCollector<'lowering> {
         result
     }
 
+    fn with_module<T, F>(&mut self, module: ModuleId, name: Symbol, f: F) -> T
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        let parent = std::mem::replace(&mut self.module, module);
+        self.inline.push(name);
+        self.scopes.push(self.env[module].scope);
+        let result = f(self);
+        self.scopes.pop();
+        self.inline.pop();
+        self.module = parent;
+
+        result
+    }
+
     fn current_scope(&self) -> ScopeId {
         self.scopes.last().copied().unwrap()
     }
@@ -154,21 +307,89 @@ impl<'lowering> DefinitionCollector<'lowering> {
 
 impl<'lowering> DefinitionCollector<'lowering> {
     pub fn root(&mut self, node: ast::Root) {
-        let _ = self.with_scope(ScopeKind::Module, |this| -> Result<(), ()> {
-            for attribute in node.attributes() {
-                this.top_level_attribute(attribute);
-            }
+        for attribute in node.attributes() {
+            self.top_level_attribute(attribute);
+        }
+        if !self.enabled(&self.env[self.module].builtins) {
+            return;
+        }
 
-            for definition in node.definitions() {
-                this.definition(definition)?;
+        for definition in node.definitions() {
+            if self.definition(definition).is_err() {
+                break;
             }
-
-            Ok(())
-        });
+        }
     }
 
     fn top_level_attribute(&mut self, node: ast::TopLevelAttribute) {
-        std::todo!();
+        let Some(argument) = node.argument() else {
+            self.diagnostic
+                .report(miette!("Attribute without an argument"));
+            return;
+        };
+        let Ok(argument) = self.attribute_argument(argument) else {
+            return;
+        };
+        let attribute = hir::TopLevelAttribute {
+            argument,
+            span: Span::new(node.syntax()),
+        };
+
+        let target = match self.env[self.module].parent {
+            None => Target::Krate,
+            Some(_) => Target::Module,
+        };
+        if let Some(builtin) = attribute::check(
+            &attribute.argument,
+            &written(node.syntax()),
+            target,
+            &self.env[self.module].builtins,
+            true,
+            self.symbols,
+            self.diagnostic,
+        ) {
+            self.env[self.module].builtins.push(builtin);
+        }
+
+        self.env[self.module].attributes.push(attribute);
+    }
+
+    /// Whether every `#[cfg(...)]` among `builtins` holds.
+    fn enabled<'a>(&self, builtins: impl IntoIterator<Item = &'a Builtin>) -> bool {
+        builtins.into_iter().all(|builtin| match builtin {
+            Builtin::Cfg(cfg) => self.cfg.enabled(cfg),
+            _ => true,
+        })
+    }
+
+    /// Lowers the attributes written on a `target`, interpreting the built-in ones and
+    /// reporting misuse.
+    fn attributes(
+        &mut self,
+        nodes: impl Iterator<Item = ast::Attribute>,
+        target: Target,
+    ) -> Result<Vec<AttributeId>, ()> {
+        let mut attributes = vec![];
+        let mut builtins = vec![];
+        for node in nodes {
+            let range = written(node.syntax());
+            let attribute = self.attribute(node)?;
+            if let Some(builtin) = attribute::check(
+                &self.env[attribute].argument,
+                &range,
+                target,
+                &builtins,
+                false,
+                self.symbols,
+                self.diagnostic,
+            ) {
+                builtins.push(builtin.clone());
+                self.env.builtin(attribute, builtin);
+            }
+            attributes.push(attribute);
+        }
+
+        Ok(attributes)
     }
 
     fn attribute(&mut self, node: ast::Attribute) -> Result<AttributeId, ()> {
@@ -212,17 +433,12 @@ impl<'lowering> DefinitionCollector<'lowering> {
                     binding: hir::Binding::Unresolved,
                 };
 
-                let Some(value) = node.value() else {
-                    self.diagnostic
-                        .report(miette!("Attribute key-value argument without a value"));
-                    return Err(());
+                let value = match node.value() {
+                    Some(value) => Some(self.expression(value)?),
+                    None => None,
                 };
-                let value = self.expression(value)?;
 
-                hir::AttributeArgumentKind::KeyValue {
-                    key,
-                    value: Some(value),
-                }
+                hir::AttributeArgumentKind::KeyValue { key, value }
             }
             ast::AttributeArgument::Nested(node) => {
                 let Some(path) = node.path() else {
@@ -251,23 +467,29 @@ impl<'lowering> DefinitionCollector<'lowering> {
     fn associated_definition(
         &mut self,
         node: ast::AssociatedDefinition,
-    ) -> Result<(Symbol, DefinitionId), ()> {
-        let mut attributes = vec![];
-        for attribute in node.attributes() {
-            attributes.push(self.attribute(attribute)?);
-        }
-
-        let visibility = self.visibility(node.visibility());
+    ) -> Result<Option<(Symbol, DefinitionId)>, ()> {
+        // Associated definitions have no visibility of their own.
+        let visibility = hir::Visibility::Private;
 
         let Some(definition) = node.kind() else {
             self.diagnostic
                 .report(miette!("Trait definition without a kind"));
             return Err(());
         };
+        let target = match definition {
+            ast::AssociatedDefinitionKind::Function(_) => Target::Function,
+            ast::AssociatedDefinitionKind::Constant(_) => Target::Constant,
+            ast::AssociatedDefinitionKind::Type(_) => Target::Type,
+        };
+        let attributes = self.attributes(node.attributes(), target)?;
+        if !self.enabled(self.env.builtins(&attributes)) {
+            return Ok(None);
+        }
+
         let (symbol, definition) =
             self.associated_definition_kind(definition, attributes, visibility)?;
 
-        Ok((symbol, definition))
+        Ok(Some((symbol, definition)))
     }
 
     fn associated_definition_kind(
@@ -290,17 +512,39 @@ impl<'lowering> DefinitionCollector<'lowering> {
     }
 
     fn definition(&mut self, node: ast::Definition) -> Result<Option<DefinitionId>, ()> {
-        let mut attributes = vec![];
-        for attribute in node.attributes() {
-            attributes.push(self.attribute(attribute)?);
-        }
+        self.definition_with(vec![], None, node)
+    }
 
-        let visibility = self.visibility(node.visibility());
+    /// A definition, with the attributes and visibility of the statement it is written in.
+    fn definition_with(
+        &mut self,
+        outer: Vec<ast::Attribute>,
+        visibility: Option<ast::Visibility>,
+        node: ast::Definition,
+    ) -> Result<Option<DefinitionId>, ()> {
+        let visibility = self.visibility(visibility.or_else(|| node.visibility()));
 
         let Some(kind) = node.kind() else {
             self.diagnostic.report(miette!("Definition without a kind"));
             return Err(());
         };
+        let target = match &kind {
+            ast::DefinitionKind::Function(_) => Target::Function,
+            ast::DefinitionKind::Struct(_) => Target::Struct,
+            ast::DefinitionKind::Enum(_) => Target::Enum,
+            ast::DefinitionKind::Module(_) => Target::Module,
+            ast::DefinitionKind::Trait(_) => Target::Trait,
+            ast::DefinitionKind::Constant(_) => Target::Constant,
+            ast::DefinitionKind::Static(_) => Target::Static,
+            ast::DefinitionKind::Type(_) => Target::Type,
+            ast::DefinitionKind::Use(_) => Target::Use,
+            ast::DefinitionKind::Implement(_) => Target::Implement,
+        };
+        let attributes = self.attributes(outer.into_iter().chain(node.attributes()), target)?;
+        if !self.enabled(self.env.builtins(&attributes)) {
+            return Ok(None);
+        }
+
         let definition = match kind {
             ast::DefinitionKind::Function(node) => {
                 Some(self.function_definition(node, attributes, visibility)?.1)
@@ -311,6 +555,11 @@ impl<'lowering> DefinitionCollector<'lowering> {
             ast::DefinitionKind::Enum(node) => {
                 Some(self.enum_definition(node, attributes, visibility)?)
             }
+            ast::DefinitionKind::Module(node)
+                if matches!(node.kind(), Some(ast::ModuleDefinitionKind::External(_))) =>
+            {
+                Some(self.external_module_definition(node, attributes, visibility)?)
+            }
             ast::DefinitionKind::Module(node) => {
                 Some(self.module_definition(node, attributes, visibility)?)
             }
@@ -351,10 +600,6 @@ impl<'lowering> DefinitionCollector<'lowering> {
         };
         let name = self.identifier(name)?;
 
-        let type_parameters = self.type_parameters(node.type_parameters())?;
-
-        let type_bounds = self.type_bounds(node.type_bounds())?;
-
         let parameters = self.function_parameters(node.parameters())?;
 
         let return_type = match node.return_type() {
@@ -386,10 +631,8 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 visibility,
                 name,
                 kind: hir::DefinitionKind::Function {
-                    type_parameters,
                     parameters,
                     return_type,
-                    type_bounds,
                     body,
                 },
                 span: Span::new(node.syntax()),
@@ -413,10 +656,6 @@ impl<'lowering> DefinitionCollector<'lowering> {
         };
         let name = self.identifier(name)?;
 
-        let type_parameters = self.type_parameters(node.type_parameters())?;
-
-        let type_bounds = self.type_bounds(node.type_bounds())?;
-
         let Some(body) = node.body() else {
             self.diagnostic.report(miette!("Struct without a body"));
             return Err(());
@@ -430,11 +669,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 attributes,
                 visibility,
                 name,
-                kind: hir::DefinitionKind::Struct {
-                    type_parameters,
-                    type_bounds,
-                    body,
-                },
+                kind: hir::DefinitionKind::Struct { body },
                 span: Span::new(node.syntax()),
             },
             file: self.file,
@@ -456,16 +691,12 @@ impl<'lowering> DefinitionCollector<'lowering> {
         };
         let name = self.identifier(name)?;
 
-        let type_parameters = self.type_parameters(node.type_parameters())?;
-
-        let type_bounds = self.type_bounds(node.type_bounds())?;
-
         let variants = self.with_scope(
             ScopeKind::Block,
             |this| -> Result<Vec<hir::EnumVariant>, ()> {
                 let mut variants = vec![];
                 for variant in node.variants() {
-                    variants.push(this.enum_variant(variant)?);
+                    variants.extend(this.enum_variant(variant)?);
                 }
 
                 Ok(variants)
@@ -479,11 +710,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 attributes,
                 visibility,
                 name,
-                kind: hir::DefinitionKind::Enum {
-                    type_parameters,
-                    type_bounds,
-                    variants,
-                },
+                kind: hir::DefinitionKind::Enum { variants },
                 span: Span::new(node.syntax()),
             },
             file: self.file,
@@ -545,10 +772,11 @@ impl<'lowering> DefinitionCollector<'lowering> {
         self.env[scope].definition((Namespace::Value, name.symbol), definition);
 
         let parent_module = self.module;
-        let child = self.env.module(self.file, Some(parent_module));
+        let krate = self.env[parent_module].krate;
+        let child = self.env.module(krate, self.file, Some(parent_module));
         self.env[parent_module].children.insert(name.symbol, child);
 
-        self.with_scope(ScopeKind::Module, |this| -> Result<(), ()> {
+        self.with_module(child, name.symbol, |this| -> Result<(), ()> {
             for definition in inline_module.definitions() {
                 this.definition(definition)?;
             }
@@ -559,6 +787,52 @@ impl<'lowering> DefinitionCollector<'lowering> {
         Ok(definition)
     }
 
+    fn external_module_definition(
+        &mut self,
+        node: ast::ModuleDefinition,
+        attributes: Vec<AttributeId>,
+        visibility: hir::Visibility,
+    ) -> Result<DefinitionId, ()> {
+        let Some(name) = node.name() else {
+            self.diagnostic.report(miette!("Module without a name"));
+            return Err(());
+        };
+        let name = self.identifier(name)?;
+
+        let path = self
+            .env
+            .builtins(&attributes)
+            .find_map(|builtin| match builtin {
+                Builtin::Path(path) => Some(path.clone()),
+                _ => None,
+            });
+
+        let scope = self.current_scope();
+        let definition = self.env.definition(crate::env::Definition {
+            scope,
+            definition: hir::Definition {
+                attributes,
+                visibility,
+                name,
+                kind: hir::DefinitionKind::Module {
+                    kind: hir::ModuleDefinitionKind::External,
+                },
+                span: Span::new(node.syntax()),
+            },
+            file: self.file,
+        });
+        self.env[scope].definition((Namespace::Type, name.symbol), definition);
+
+        self.external_modules.push(ExternalModule {
+            module: self.module,
+            inline: self.inline.clone(),
+            name,
+            path,
+        });
+
+        Ok(definition)
+    }
+
     fn trait_definition(
         &mut self,
         node: ast::TraitDefinition,
@@ -571,14 +845,12 @@ impl<'lowering> DefinitionCollector<'lowering> {
         };
         let name = self.identifier(name)?;
 
-        let type_parameters = self.type_parameters(node.type_parameters())?;
-
-        let type_bounds = self.type_bounds(node.type_bounds())?;
-
         let definitions = self.with_scope(ScopeKind::Block, |this| {
             let mut definitions = HashMap::new();
             for definition in node.definitions() {
-                let (symbol, definition) = this.associated_definition(definition)?;
+                let Some((symbol, definition)) = this.associated_definition(definition)? else {
+                    continue;
+                };
                 definitions
                     .entry(symbol)
                     .or_insert_with(Vec::new)
@@ -595,11 +867,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 attributes,
                 visibility,
                 name,
-                kind: hir::DefinitionKind::Trait {
-                    type_parameters,
-                    type_bounds,
-                    definitions,
-                },
+                kind: hir::DefinitionKind::Trait { definitions },
                 span: Span::new(node.syntax()),
             },
             file: self.file,
@@ -609,84 +877,13 @@ impl<'lowering> DefinitionCollector<'lowering> {
         Ok(definition)
     }
 
-    fn type_parameters(
-        &mut self,
-        nodes: impl Iterator<Item = ast::TypeParameter>,
-    ) -> Result<Vec<hir::TypeParameter>, ()> {
-        let mut parameters = vec![];
-        for parameter in nodes {
-            parameters.push(self.type_parameter(parameter)?);
-        }
-        Ok(parameters)
-    }
-
-    fn type_parameter(&mut self, node: ast::TypeParameter) -> Result<hir::TypeParameter, ()> {
-        let Some(r#type) = node.r#type() else {
-            self.diagnostic
-                .report(miette!("Type parameter without a type"));
-            return Err(());
-        };
-        let r#type = self.type_expression(r#type, false)?;
-
-        let mut constraints = vec![];
-        for constraint in node.constraints() {
-            let Some(r#type) = constraint.r#type() else {
-                self.diagnostic
-                    .report(miette!("Type parameter constraint without a type"));
-                return Err(());
-            };
-            constraints.push(self.type_expression(r#type, false)?);
-        }
-
-        Ok(hir::TypeParameter {
-            r#type,
-            constraints,
-            span: Span::new(node.syntax()),
-        })
-    }
-
-    fn type_bounds(
-        &mut self,
-        nodes: impl Iterator<Item = ast::TypeBound>,
-    ) -> Result<Vec<hir::TypeBound>, ()> {
-        let mut bounds = vec![];
-        for bound in nodes {
-            bounds.push(self.type_bound(bound)?);
-        }
-        Ok(bounds)
-    }
-
-    fn type_bound(&mut self, node: ast::TypeBound) -> Result<hir::TypeBound, ()> {
-        let Some(r#type) = node.r#type() else {
-            self.diagnostic.report(miette!("Type bound without a type"));
-            return Err(());
-        };
-        let r#type = self.type_expression(r#type, false)?;
-
-        let mut constraints = vec![];
-        for constraint in node.constraints() {
-            let Some(r#type) = constraint.r#type() else {
-                self.diagnostic
-                    .report(miette!("Type bound constraint without a type"));
-                return Err(());
-            };
-            constraints.push(self.type_expression(r#type, false)?);
-        }
-
-        Ok(hir::TypeBound {
-            r#type,
-            constraints,
-            span: Span::new(node.syntax()),
-        })
-    }
-
     fn function_parameters(
         &mut self,
         nodes: impl Iterator<Item = ast::FunctionParameter>,
     ) -> Result<Vec<hir::FunctionParameter>, ()> {
         let mut parameters = vec![];
         for parameter in nodes {
-            parameters.push(self.function_parameter(parameter)?);
+            parameters.extend(self.function_parameter(parameter)?);
         }
         Ok(parameters)
     }
@@ -694,10 +891,10 @@ impl<'lowering> DefinitionCollector<'lowering> {
     fn function_parameter(
         &mut self,
         node: ast::FunctionParameter,
-    ) -> Result<hir::FunctionParameter, ()> {
-        let mut attributes = vec![];
-        for attribute in node.attributes() {
-            attributes.push(self.attribute(attribute)?);
+    ) -> Result<Option<hir::FunctionParameter>, ()> {
+        let attributes = self.attributes(node.attributes(), Target::Parameter)?;
+        if !self.enabled(self.env.builtins(&attributes)) {
+            return Ok(None);
         }
 
         let Some(pattern) = node.pattern() else {
@@ -714,12 +911,12 @@ impl<'lowering> DefinitionCollector<'lowering> {
         };
         let r#type = self.type_expression(r#type, false)?;
 
-        Ok(hir::FunctionParameter {
+        Ok(Some(hir::FunctionParameter {
             attributes,
             pattern,
             r#type,
             span: Span::new(node.syntax()),
-        })
+        }))
     }
 
     fn constant_definition(
@@ -821,10 +1018,6 @@ impl<'lowering> DefinitionCollector<'lowering> {
         };
         let name = self.identifier(name)?;
 
-        let type_parameters = self.type_parameters(node.type_parameters())?;
-
-        let type_bounds = self.type_bounds(node.type_bounds())?;
-
         let initializer = match node.initializer() {
             Some(initializer) => Some(self.type_expression(initializer, false)?),
             None => None,
@@ -837,11 +1030,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 attributes,
                 visibility,
                 name,
-                kind: hir::DefinitionKind::Type {
-                    type_parameters,
-                    type_bounds,
-                    initializer,
-                },
+                kind: hir::DefinitionKind::Type { initializer },
                 span: Span::new(node.syntax()),
             },
             file: self.file,
@@ -869,14 +1058,12 @@ impl<'lowering> DefinitionCollector<'lowering> {
         };
         let for_type = self.type_expression(for_type, false)?;
 
-        let type_parameters = self.type_parameters(node.type_parameters())?;
-
-        let type_bounds = self.type_bounds(node.type_bounds())?;
-
         let definitions = self.with_scope(ScopeKind::Block, |this| {
             let mut definitions = HashMap::new();
             for definition in node.definitions() {
-                let (symbol, definition) = this.associated_definition(definition)?;
+                let Some((symbol, definition)) = this.associated_definition(definition)? else {
+                    continue;
+                };
                 definitions
                     .entry(symbol)
                     .or_insert_with(Vec::new)
@@ -893,8 +1080,6 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 visibility,
                 trait_type,
                 for_type,
-                type_parameters,
-                type_bounds,
                 definitions,
                 span: Span::new(node.syntax()),
             },
@@ -916,6 +1101,15 @@ impl<'lowering> DefinitionCollector<'lowering> {
             self.diagnostic.report(miette!("Use tree without a kind"));
             return Err(());
         };
+        // A leading `::` starts over from the root, even inside a list.
+        let root = [hir::PathSegment {
+            kind: hir::PathSegmentKind::Root,
+            binding: hir::Binding::Unresolved,
+        }];
+        let segments = match node.root() {
+            Some(_) => &root[..],
+            None => segments,
+        };
 
         match kind {
             ast::UseTreeKind::Glob(_) => {
@@ -949,7 +1143,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
             }
             ast::UseTreeKind::List(list) => {
                 for tree in list.trees() {
-                    self.use_tree(tree, scope, attributes, visibility, &segments)?;
+                    self.use_tree(tree, scope, attributes, visibility, segments)?;
                 }
             }
         }
@@ -990,7 +1184,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
             }
             ast::UseTreeTrailing::Nested(nested) => {
                 for tree in nested.trees() {
-                    self.use_tree(tree, scope, &attributes, &visibility, &segments)?;
+                    self.use_tree(tree, scope, attributes, visibility, segments)?;
                 }
             }
         }
@@ -1004,6 +1198,11 @@ impl<'lowering> DefinitionCollector<'lowering> {
             ast::StructBody::Named(record) => {
                 let mut fields = vec![];
                 for field in record.fields() {
+                    let attributes = self.attributes(field.attributes(), Target::Field)?;
+                    if !self.enabled(self.env.builtins(&attributes)) {
+                        continue;
+                    }
+
                     let visibility = self.visibility(field.visibility());
 
                     let Some(name) = field.name() else {
@@ -1020,7 +1219,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
                     };
                     let ty = self.type_expression(ty, false)?;
 
-                    fields.push((visibility, name, ty));
+                    fields.push((attributes, visibility, name, ty));
                 }
 
                 Ok(hir::StructBody::Named(fields))
@@ -1028,6 +1227,11 @@ impl<'lowering> DefinitionCollector<'lowering> {
             ast::StructBody::Unnamed(unnamed) => {
                 let mut fields = vec![];
                 for field in unnamed.fields() {
+                    let attributes = self.attributes(field.attributes(), Target::Field)?;
+                    if !self.enabled(self.env.builtins(&attributes)) {
+                        continue;
+                    }
+
                     let visibility = self.visibility(field.visibility());
 
                     let Some(ty) = field.r#type() else {
@@ -1037,21 +1241,18 @@ impl<'lowering> DefinitionCollector<'lowering> {
                     };
                     let ty = self.type_expression(ty, false)?;
 
-                    fields.push((visibility, ty));
+                    fields.push((attributes, visibility, ty));
                 }
                 Ok(hir::StructBody::Unnamed(fields))
             }
         }
     }
 
-    fn enum_variant(&mut self, node: ast::EnumVariant) -> Result<hir::EnumVariant, ()> {
+    fn enum_variant(&mut self, node: ast::EnumVariant) -> Result<Option<hir::EnumVariant>, ()> {
         let span = Span::new(node.syntax());
         let (attributes, name, kind) = match node {
             ast::EnumVariant::Unit(node) => {
-                let mut attributes = vec![];
-                for attribute in node.attributes() {
-                    attributes.push(self.attribute(attribute)?);
-                }
+                let attributes = self.attributes(node.attributes(), Target::Variant)?;
 
                 let Some(name) = node.name() else {
                     self.diagnostic
@@ -1063,10 +1264,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 (attributes, name, hir::EnumVariantKind::Unit)
             }
             ast::EnumVariant::Scalar(node) => {
-                let mut attributes = vec![];
-                for attribute in node.attributes() {
-                    attributes.push(self.attribute(attribute)?);
-                }
+                let attributes = self.attributes(node.attributes(), Target::Variant)?;
 
                 let Some(name) = node.name() else {
                     self.diagnostic
@@ -1085,10 +1283,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 (attributes, name, hir::EnumVariantKind::Scalar(initializer))
             }
             ast::EnumVariant::Named(node) => {
-                let mut attributes = vec![];
-                for attribute in node.attributes() {
-                    attributes.push(self.attribute(attribute)?);
-                }
+                let attributes = self.attributes(node.attributes(), Target::Variant)?;
 
                 let Some(name) = node.name() else {
                     self.diagnostic
@@ -1099,9 +1294,9 @@ impl<'lowering> DefinitionCollector<'lowering> {
 
                 let mut fields = vec![];
                 for field in node.fields() {
-                    let mut attributes = vec![];
-                    for attribute in field.attributes() {
-                        attributes.push(self.attribute(attribute)?);
+                    let attributes = self.attributes(field.attributes(), Target::Field)?;
+                    if !self.enabled(self.env.builtins(&attributes)) {
+                        continue;
                     }
 
                     let Some(name) = field.name() else {
@@ -1124,10 +1319,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 (attributes, name, hir::EnumVariantKind::Named(fields))
             }
             ast::EnumVariant::Unnamed(node) => {
-                let mut attributes = vec![];
-                for attribute in node.attributes() {
-                    attributes.push(self.attribute(attribute)?);
-                }
+                let attributes = self.attributes(node.attributes(), Target::Variant)?;
 
                 let Some(name) = node.name() else {
                     self.diagnostic
@@ -1138,49 +1330,54 @@ impl<'lowering> DefinitionCollector<'lowering> {
 
                 let mut fields = vec![];
                 for field in node.fields() {
-                    let mut attributes = vec![];
-                    for attribute in field.attributes() {
-                        attributes.push(self.attribute(attribute)?);
-                    }
-
-                    let Some(ty) = field.r#type() else {
-                        self.diagnostic
-                            .report(miette!("Enum variant field without a type"));
-                        return Err(());
-                    };
-                    let ty = self.type_expression(ty, false)?;
+                    let ty = self.type_expression(field, false)?;
 
-                    fields.push((attributes, ty));
+                    fields.push((vec![], ty));
                 }
 
                 (attributes, name, hir::EnumVariantKind::Unnamed(fields))
             }
         };
+        if !self.enabled(self.env.builtins(&attributes)) {
+            return Ok(None);
+        }
 
-        Ok(hir::EnumVariant {
+        Ok(Some(hir::EnumVariant {
             attributes,
             name,
             kind,
             span,
-        })
+        }))
     }
 
     fn visibility(&mut self, node: Option<ast::Visibility>) -> hir::Visibility {
-        match node {
-            None => hir::Visibility::Private,
-            Some(visibility) => {
-                if visibility.krate().is_some() {
-                    hir::Visibility::Krate
-                } else if visibility.super_().is_some() {
-                    hir::Visibility::Super
-                } else if visibility.self_().is_some() {
-                    hir::Visibility::Self_
-                } else {
-                    self.diagnostic
-                        .report(miette!("Item with invalid visibility"));
-                    hir::Visibility::Private
-                }
-            }
+        let Some(visibility) = node else {
+            return hir::Visibility::Private;
+        };
+        if visibility.left_paren().is_none() {
+            return hir::Visibility::Public;
+        }
+        let Some(path) = visibility.path() else {
+            self.diagnostic
+                .report(miette!("Item with invalid visibility"));
+            return hir::Visibility::Private;
+        };
+        let Ok(segments) = self.path(path) else {
+            return hir::Visibility::Private;
+        };
+
+        let kind = match segments.as_slice() {
+            [segment] => Some(&segment.kind),
+            _ => None,
+        };
+        match kind {
+            Some(hir::PathSegmentKind::Krate) => hir::Visibility::Krate,
+            Some(hir::PathSegmentKind::Super_) => hir::Visibility::Super,
+            Some(hir::PathSegmentKind::Self_) => hir::Visibility::Self_,
+            _ => hir::Visibility::Restricted(hir::Path {
+                segments,
+                binding: hir::Binding::Unresolved,
+            }),
         }
     }
 
@@ -1190,11 +1387,6 @@ impl<'lowering> DefinitionCollector<'lowering> {
         mutable: bool,
     ) -> Result<hir::TypeExpression, ()> {
         match node {
-            ast::TypeExpression::Never(_) => Ok(hir::TypeExpression {
-                mutable,
-                kind: hir::TypeExpressionKind::Never,
-                span: Span::new(node.syntax()),
-            }),
             ast::TypeExpression::Mutable(mutable) => {
                 let Some(inner) = mutable.r#type() else {
                     self.diagnostic
@@ -1243,13 +1435,8 @@ impl<'lowering> DefinitionCollector<'lowering> {
             }
             ast::TypeExpression::Tuple(tuple) => {
                 let mut arguments = vec![];
-                for argument in tuple.arguments() {
-                    let Some(type_expression) = argument.r#type() else {
-                        self.diagnostic
-                            .report(miette!("Tuple type without an element type"));
-                        return Err(());
-                    };
-                    arguments.push(self.type_expression(type_expression, false)?);
+                for element in tuple.elements() {
+                    arguments.push(self.type_expression(element, false)?);
                 }
 
                 Ok(hir::TypeExpression {
@@ -1432,10 +1619,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 hir::ExpressionKind::Tuple { elements }
             }
             ast::Expression::Block(node) => {
-                let mut attributes = vec![];
-                for attribute in node.attributes() {
-                    attributes.push(self.attribute(attribute)?);
-                }
+                let attributes = self.attributes(node.attributes(), Target::Expression)?;
 
                 let statements =
                     self.with_scope(ScopeKind::Block, |this| this.block_expression(node))?;
@@ -1632,121 +1816,6 @@ impl<'lowering> DefinitionCollector<'lowering> {
 
                 hir::ExpressionKind::Await { expression }
             }
-            ast::Expression::Range(node) => {
-                let range = match node {
-                    ast::RangeExpression::Full(_) => hir::RangeExpression::Full,
-                    ast::RangeExpression::To(node) => {
-                        let Some(end) = node.end() else {
-                            self.diagnostic
-                                .report(miette!("Range to expression without an end"));
-                            return Err(());
-                        };
-                        let end = self.expression(end)?;
-                        let end = Box::new(end);
-
-                        hir::RangeExpression::To { end }
-                    }
-                    ast::RangeExpression::FromTo(node) => {
-                        let Some(start) = node.start() else {
-                            self.diagnostic
-                                .report(miette!("Range from-to expression without a start"));
-                            return Err(());
-                        };
-                        let start = self.expression(start)?;
-                        let start = Box::new(start);
-
-                        let Some(end) = node.end() else {
-                            self.diagnostic
-                                .report(miette!("Range from-to expression without an end"));
-                            return Err(());
-                        };
-                        let end = self.expression(end)?;
-                        let end = Box::new(end);
-
-                        hir::RangeExpression::FromTo { start, end }
-                    }
-                    ast::RangeExpression::From(node) => {
-                        let Some(start) = node.start() else {
-                            self.diagnostic
-                                .report(miette!("Range from expression without a start"));
-                            return Err(());
-                        };
-                        let start = self.expression(start)?;
-                        let start = Box::new(start);
-
-                        hir::RangeExpression::From { start }
-                    }
-                    ast::RangeExpression::FromToInclusive(node) => {
-                        let Some(start) = node.start() else {
-                            self.diagnostic.report(miette!(
-                                "Range from-to-inclusive expression without a start"
-                            ));
-                            return Err(());
-                        };
-                        let start = self.expression(start)?;
-                        let start = Box::new(start);
-
-                        let Some(end) = node.end() else {
-                            self.diagnostic.report(miette!(
-                                "Range from-to-inclusive expression without an end"
-                            ));
-                            return Err(());
-                        };
-                        let end = self.expression(end)?;
-                        let end = Box::new(end);
-
-                        hir::RangeExpression::FromToInclusive { start, end }
-                    }
-                    ast::RangeExpression::ToInclusive(node) => {
-                        let Some(end) = node.end() else {
-                            self.diagnostic
-                                .report(miette!("Range to-inclusive expression without an end"));
-                            return Err(());
-                        };
-                        let end = self.expression(end)?;
-                        let end = Box::new(end);
-
-                        hir::RangeExpression::ToInclusive { end }
-                    }
-                };
-
-                hir::ExpressionKind::Range { range }
-            }
-            ast::Expression::Struct(node) => {
-                let Some(path) = node.path() else {
-                    self.diagnostic
-                        .report(miette!("Struct expression without a path"));
-                    return Err(());
-                };
-                let path = self.path_expression(path)?;
-
-                let type_arguments = vec![];
-
-                let mut fields = vec![];
-                for field in node.fields() {
-                    let Some(name) = field.name() else {
-                        self.diagnostic
-                            .report(miette!("Struct field without a name"));
-                        return Err(());
-                    };
-                    let name = self.identifier(name)?;
-
-                    let Some(value) = field.value() else {
-                        self.diagnostic
-                            .report(miette!("Struct field without a value"));
-                        return Err(());
-                    };
-                    let value = self.expression(value)?;
-
-                    fields.push((name, value));
-                }
-
-                hir::ExpressionKind::Struct {
-                    path,
-                    type_arguments,
-                    fields,
-                }
-            }
             ast::Expression::Try(node) => {
                 let Some(value) = node.expression() else {
                     self.diagnostic
@@ -1808,7 +1877,11 @@ impl<'lowering> DefinitionCollector<'lowering> {
                         .report(miette!("Definition statement without a definition"));
                     return Err(());
                 };
-                let definition = match self.definition(definition)? {
+                let definition = match self.definition_with(
+                    node.attributes().collect(),
+                    node.visibility(),
+                    definition,
+                )? {
                     Some(definition) => definition,
                     None => return Ok(None),
                 };
@@ -1816,6 +1889,11 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 hir::StatementKind::Definition { definition }
             }
             ast::Statement::Expression(node) => {
+                let attributes = self.attributes(node.attributes(), Target::Statement)?;
+                if !self.enabled(self.env.builtins(&attributes)) {
+                    return Ok(None);
+                }
+
                 let Some(expression) = node.expression() else {
                     self.diagnostic
                         .report(miette!("Expression statement without an expression"));
@@ -1823,7 +1901,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 };
                 let value = self.expression(expression)?;
 
-                hir::StatementKind::Expression { value }
+                hir::StatementKind::Expression { attributes, value }
             }
             ast::Statement::Let(node) => {
                 let Some(pattern) = node.pattern() else {
@@ -1937,109 +2015,6 @@ impl<'lowering> DefinitionCollector<'lowering> {
                     span: Span::new(node.syntax()),
                 })
             }
-            ast::Pattern::Range(node) => match node {
-                ast::RangePattern::To(node) => {
-                    let Some(end) = node.end() else {
-                        self.diagnostic
-                            .report(miette!("Range to pattern without an end"));
-                        return Err(());
-                    };
-                    let end = self.pattern(end, false)?;
-                    let end = Box::new(end);
-
-                    Ok(hir::Pattern {
-                        mutable,
-                        kind: hir::PatternKind::Range {
-                            range: hir::RangePattern::To { end },
-                        },
-                        span: Span::new(node.syntax()),
-                    })
-                }
-                ast::RangePattern::FromTo(node) => {
-                    let Some(start) = node.start() else {
-                        self.diagnostic
-                            .report(miette!("Range from-to pattern without a start"));
-                        return Err(());
-                    };
-                    let start = self.pattern(start, false)?;
-                    let start = Box::new(start);
-
-                    let Some(end) = node.end() else {
-                        self.diagnostic
-                            .report(miette!("Range from-to pattern without an end"));
-                        return Err(());
-                    };
-                    let end = self.pattern(end, false)?;
-                    let end = Box::new(end);
-
-                    Ok(hir::Pattern {
-                        mutable,
-                        kind: hir::PatternKind::Range {
-                            range: hir::RangePattern::FromTo { start, end },
-                        },
-                        span: Span::new(node.syntax()),
-                    })
-                }
-                ast::RangePattern::From(node) => {
-                    let Some(start) = node.start() else {
-                        self.diagnostic
-                            .report(miette!("Range from pattern without a start"));
-                        return Err(());
-                    };
-                    let start = self.pattern(start, false)?;
-                    let start = Box::new(start);
-
-                    Ok(hir::Pattern {
-                        mutable,
-                        kind: hir::PatternKind::Range {
-                            range: hir::RangePattern::From { start },
-                        },
-                        span: Span::new(node.syntax()),
-                    })
-                }
-                ast::RangePattern::FromToInclusive(node) => {
-                    let Some(start) = node.start() else {
-                        self.diagnostic
-                            .report(miette!("Range from-to-inclusive pattern without a start"));
-                        return Err(());
-                    };
-                    let start = self.pattern(start, false)?;
-                    let start = Box::new(start);
-
-                    let Some(end) = node.end() else {
-                        self.diagnostic
-                            .report(miette!("Range from-to-inclusive pattern without an end"));
-                        return Err(());
-                    };
-                    let end = self.pattern(end, false)?;
-                    let end = Box::new(end);
-
-                    Ok(hir::Pattern {
-                        mutable,
-                        kind: hir::PatternKind::Range {
-                            range: hir::RangePattern::FromToInclusive { start, end },
-                        },
-                        span: Span::new(node.syntax()),
-                    })
-                }
-                ast::RangePattern::ToInclusive(node) => {
-                    let Some(end) = node.end() else {
-                        self.diagnostic
-                            .report(miette!("Range to-inclusive pattern without an end"));
-                        return Err(());
-                    };
-                    let end = self.pattern(end, false)?;
-                    let end = Box::new(end);
-
-                    Ok(hir::Pattern {
-                        mutable,
-                        kind: hir::PatternKind::Range {
-                            range: hir::RangePattern::ToInclusive { end },
-                        },
-                        span: Span::new(node.syntax()),
-                    })
-                }
-            },
             ast::Pattern::At(node) => {
                 let Some(name) = node.name() else {
                     self.diagnostic.report(miette!("At pattern without a name"));
@@ -2079,7 +2054,11 @@ impl<'lowering> DefinitionCollector<'lowering> {
                         .report(miette!("Named pattern without a path"));
                     return Err(());
                 };
-                let path = self.path_pattern(path)?;
+                let segments = self.path(path)?;
+                let path = hir::Path {
+                    segments,
+                    binding: hir::Binding::Unresolved,
+                };
 
                 let mut fields = vec![];
                 for field in node.fields() {
@@ -2112,7 +2091,11 @@ impl<'lowering> DefinitionCollector<'lowering> {
                         .report(miette!("Unnamed pattern without a path"));
                     return Err(());
                 };
-                let path = self.path_pattern(path)?;
+                let segments = self.path(path)?;
+                let path = hir::Path {
+                    segments,
+                    binding: hir::Binding::Unresolved,
+                };
 
                 let mut elements = vec![];
                 for element in node.elements() {
@@ -2167,9 +2150,8 @@ impl<'lowering> DefinitionCollector<'lowering> {
                 hir::LiteralKind::Boolean { value }
             }
             ast::Literal::Character(node) => {
+                // The lexer reports empty and unterminated characters.
                 let Some(kind) = node.kind() else {
-                    self.diagnostic
-                        .report(miette!("Character literal without a value"));
                     return Err(());
                 };
                 let value = match kind {
@@ -2189,76 +2171,54 @@ impl<'lowering> DefinitionCollector<'lowering> {
                         c
                     }
                     ast::CharacterLiteralKind::Escape(node) => {
-                        let Some(text) = node.segment() else {
-                            self.diagnostic
-                                .report(miette!("Character literal without an escape sequence"));
-                            return Err(());
-                        };
-                        let text = text.text();
-                        let Some(c) = text.chars().next() else {
-                            self.diagnostic
-                                .report(miette!("Character literal escape sequence is empty"));
-                            return Err(());
-                        };
-
-                        match c {
-                            '\\' => '\\',
-                            '\'' => '\'',
-                            '\"' => '\"',
-                            'n' => '\n',
-                            't' => '\t',
-                            _ => {
-                                self.diagnostic.report(miette!(
-                                    "Character literal with unknown escape sequence: \\{}",
-                                    c
-                                ));
-                                return Err(());
-                            }
-                        }
+                        self.escape(node.syntax(), node.segment(), "character")?
                     }
                     ast::CharacterLiteralKind::Unicode(node) => {
-                        let code_point: String =
-                            node.segments().map(|s| s.text().to_owned()).collect();
-                        let Ok(value) = u32::from_str_radix(&code_point, 16) else {
-                            self.diagnostic.report(miette!(
-                                "Character literal with invalid unicode code point: {}",
-                                code_point
-                            ));
-                            return Err(());
-                        };
-                        let Some(c) = std::char::from_u32(value) else {
-                            self.diagnostic.report(miette!(
-                                "Character literal with invalid unicode code point: {}",
-                                code_point
-                            ));
-                            return Err(());
-                        };
-
-                        c
+                        self.unicode(node.syntax(), node.segments(), "character")?
                     }
                 };
 
                 hir::LiteralKind::Character { value }
             }
             ast::Literal::Integer(node) => {
-                let text = node.syntax().text().to_owned().to_string();
-                let Some(value) = text.replace('_', "").parse().ok() else {
-                    self.diagnostic
-                        .report(miette!("Integer literal with invalid value: {}", text));
-                    return Err(());
-                };
+                let text = digits(node.syntax());
+                match self.numeric_suffix(node.suffix(), "integer", true, true)? {
+                    // `1f32` is a float.
+                    Some(NumericSuffix::Float(suffix)) => {
+                        let Some(value) = text.parse().ok() else {
+                            self.diagnostic
+                                .report(miette!("Integer literal with invalid value: {}", text));
+                            return Err(());
+                        };
 
-                hir::LiteralKind::Integer { value }
+                        hir::LiteralKind::Float {
+                            value,
+                            suffix: Some(suffix),
+                        }
+                    }
+                    Some(NumericSuffix::Integer(suffix)) => hir::LiteralKind::Integer {
+                        value: self.integer(node.syntax(), &text, 10, Some(suffix))?,
+                        suffix: Some(suffix),
+                    },
+                    None => hir::LiteralKind::Integer {
+                        value: self.integer(node.syntax(), &text, 10, None)?,
+                        suffix: None,
+                    },
+                }
             }
             ast::Literal::Float(node) => {
-                let text = node.syntax().text().to_owned().to_string();
-                let Some(value) = text.replace('_', "").parse().ok() else {
+                let text = digits(node.syntax());
+                let Some(value) = text.parse().ok() else {
                     self.diagnostic
                         .report(miette!("Float literal with invalid value: {}", text));
                     return Err(());
                 };
+                let suffix = match self.numeric_suffix(node.suffix(), "float", false, true)? {
+                    Some(NumericSuffix::Float(suffix)) => Some(suffix),
+                    _ => None,
+                };
 
-                hir::LiteralKind::Float { value }
+                hir::LiteralKind::Float { value, suffix }
             }
             ast::Literal::String(node) => {
                 let mut segments = vec![];
@@ -2270,52 +2230,12 @@ impl<'lowering> DefinitionCollector<'lowering> {
                             hir::StringSegment::Text { value }
                         }
                         ast::StringSegment::Escape(node) => {
-                            let Some(value) = node.segment() else {
-                                self.diagnostic.report(miette!(
-                                    "String escape segment without an escape sequence"
-                                ));
-                                return Err(());
-                            };
-                            let value = match value.text().chars().next() {
-                                Some('\\') => '\\',
-                                Some('\'') => '\'',
-                                Some('\"') => '\"',
-                                Some('n') => '\n',
-                                Some('t') => '\t',
-                                Some(c) => {
-                                    self.diagnostic.report(miette!(
-                                        "String literal with unknown escape sequence: \\{}",
-                                        c
-                                    ));
-                                    return Err(());
-                                }
-                                None => {
-                                    self.diagnostic.report(miette!(
-                                        "String escape segment with empty escape sequence"
-                                    ));
-                                    return Err(());
-                                }
-                            };
+                            let value = self.escape(node.syntax(), node.segment(), "string")?;
 
                             hir::StringSegment::Escape { value }
                         }
                         ast::StringSegment::Unicode(node) => {
-                            let code_point: String =
-                                node.segments().map(|s| s.text().to_owned()).collect();
-                            let Some(value) = u32::from_str_radix(&code_point, 16).ok() else {
-                                self.diagnostic.report(miette!(
-                                    "String literal with invalid unicode code point: {}",
-                                    code_point
-                                ));
-                                return Err(());
-                            };
-                            let Some(value) = std::char::from_u32(value) else {
-                                self.diagnostic.report(miette!(
-                                    "String literal with invalid unicode code point: {}",
-                                    code_point
-                                ));
-                                return Err(());
-                            };
+                            let value = self.unicode(node.syntax(), node.segments(), "string")?;
 
                             hir::StringSegment::Unicode { value }
                         }
@@ -2337,35 +2257,65 @@ impl<'lowering> DefinitionCollector<'lowering> {
 
                 hir::LiteralKind::String { segments }
             }
+            ast::Literal::Byte(node) => {
+                let Some(kind) = node.kind() else {
+                    return Err(());
+                };
+                let value = self.byte(kind)?;
+
+                hir::LiteralKind::Byte { value }
+            }
+            ast::Literal::ByteString(node) => {
+                let value = self.bytes(node.segments(), "byte string", false)?;
+
+                hir::LiteralKind::ByteString { value }
+            }
+            ast::Literal::CString(node) => {
+                let value = self.bytes(node.segments(), "C string", true)?;
+
+                hir::LiteralKind::CString { value }
+            }
             ast::Literal::Binary(node) => {
-                let text = node.syntax().text().to_owned().to_string();
-                let Some(value) = i128::from_str_radix(&text[2..], 2).ok() else {
-                    self.diagnostic
-                        .report(miette!("Binary literal with invalid value: {}", text));
+                let text = digits(node.syntax());
+                if text.is_empty() || !text.chars().all(|c| c.is_digit(2)) {
+                    // The lexer reports missing and invalid digits.
                     return Err(());
+                }
+                let suffix = match self.numeric_suffix(node.suffix(), "binary", true, false)? {
+                    Some(NumericSuffix::Integer(suffix)) => Some(suffix),
+                    _ => None,
                 };
+                let value = self.integer(node.syntax(), &text, 2, suffix)?;
 
-                hir::LiteralKind::Integer { value }
+                hir::LiteralKind::Integer { value, suffix }
             }
             ast::Literal::Octal(node) => {
-                let text = node.syntax().text().to_owned().to_string();
-                let Some(value) = i128::from_str_radix(&text[2..], 8).ok() else {
-                    self.diagnostic
-                        .report(miette!("Octal literal with invalid value: {}", text));
+                let text = digits(node.syntax());
+                if text.is_empty() || !text.chars().all(|c| c.is_digit(8)) {
+                    // The lexer reports missing and invalid digits.
                     return Err(());
+                }
+                let suffix = match self.numeric_suffix(node.suffix(), "octal", true, false)? {
+                    Some(NumericSuffix::Integer(suffix)) => Some(suffix),
+                    _ => None,
                 };
+                let value = self.integer(node.syntax(), &text, 8, suffix)?;
 
-                hir::LiteralKind::Integer { value }
+                hir::LiteralKind::Integer { value, suffix }
             }
             ast::Literal::Hex(node) => {
-                let text = node.syntax().text().to_owned().to_string();
-                let Some(value) = i128::from_str_radix(&text[2..], 16).ok() else {
-                    self.diagnostic
-                        .report(miette!("Hex literal with invalid value: {}", text));
+                let text = digits(node.syntax());
+                if text.is_empty() {
+                    // The lexer reports missing digits.
                     return Err(());
+                }
+                let suffix = match self.numeric_suffix(node.suffix(), "hexadecimal", true, false)? {
+                    Some(NumericSuffix::Integer(suffix)) => Some(suffix),
+                    _ => None,
                 };
+                let value = self.integer(node.syntax(), &text, 16, suffix)?;
 
-                hir::LiteralKind::Integer { value }
+                hir::LiteralKind::Integer { value, suffix }
             }
         };
 
@@ -2375,6 +2325,294 @@ impl<'lowering> DefinitionCollector<'lowering> {
         })
     }
 
+    /// Reads the value of an integer literal from its `digits` in `radix`, which must fit in the
+    /// type of its `suffix`, or in `u128` without one.
+    fn integer(
+        &mut self,
+        node: &SyntaxNode,
+        digits: &str,
+        radix: u32,
+        suffix: Option<hir::IntegerSuffix>,
+    ) -> Result<u128, ()> {
+        match u128::from_str_radix(digits, radix) {
+            Ok(value) => Ok(value),
+            Err(error) if *error.kind() == IntErrorKind::PosOverflow => {
+                let prefix = match radix {
+                    2 => "0b",
+                    8 => "0o",
+                    16 => "0x",
+                    _ => "",
+                };
+                let primitive = suffix.map_or(Primitive::U128, Primitive::from);
+                self.diagnostic.report(primitive::out_of_range(
+                    node.text_range(),
+                    format_args!("{}{}", prefix, digits),
+                    primitive,
+                ));
+                Err(())
+            }
+            Err(_) => {
+                self.diagnostic
+                    .report(miette!("Integer literal with invalid value: {}", digits));
+                Err(())
+            }
+        }
+    }
+
+    /// Reads the type suffix of a `literal` literal, which may name an integer or a float type
+    /// as allowed.
+    fn numeric_suffix(
+        &mut self,
+        token: Option<SyntaxToken>,
+        literal: &str,
+        integer: bool,
+        float: bool,
+    ) -> Result<Option<NumericSuffix>, ()> {
+        let Some(token) = token else {
+            return Ok(None);
+        };
+        let suffix = match token.text() {
+            "u8" => NumericSuffix::Integer(hir::IntegerSuffix::U8),
+            "u16" => NumericSuffix::Integer(hir::IntegerSuffix::U16),
+            "u32" => NumericSuffix::Integer(hir::IntegerSuffix::U32),
+            "u64" => NumericSuffix::Integer(hir::IntegerSuffix::U64),
+            "u128" => NumericSuffix::Integer(hir::IntegerSuffix::U128),
+            "usize" => NumericSuffix::Integer(hir::IntegerSuffix::Usize),
+            "i8" => NumericSuffix::Integer(hir::IntegerSuffix::I8),
+            "i16" => NumericSuffix::Integer(hir::IntegerSuffix::I16),
+            "i32" => NumericSuffix::Integer(hir::IntegerSuffix::I32),
+            "i64" => NumericSuffix::Integer(hir::IntegerSuffix::I64),
+            "i128" => NumericSuffix::Integer(hir::IntegerSuffix::I128),
+            "isize" => NumericSuffix::Integer(hir::IntegerSuffix::Isize),
+            "f32" => NumericSuffix::Float(hir::FloatSuffix::F32),
+            "f64" => NumericSuffix::Float(hir::FloatSuffix::F64),
+            _ => {
+                self.diagnostic
+                    .report(invalid_suffix(&token, literal, "unknown suffix"));
+                return Err(());
+            }
+        };
+
+        match suffix {
+            NumericSuffix::Integer(_) if !integer => {
+                self.diagnostic
+                    .report(invalid_suffix(&token, literal, "integer suffix"));
+                Err(())
+            }
+            NumericSuffix::Float(_) if !float => {
+                self.diagnostic
+                    .report(invalid_suffix(&token, literal, "float suffix"));
+                Err(())
+            }
+            suffix => Ok(Some(suffix)),
+        }
+    }
+
+    /// Reads the value of a byte literal, which is an ASCII character or an escape.
+    fn byte(&mut self, kind: ast::CharacterLiteralKind) -> Result<u8, ()> {
+        match kind {
+            ast::CharacterLiteralKind::One(node) => {
+                let Some(token) = node.character() else {
+                    self.diagnostic
+                        .report(miette!("Byte literal without a byte"));
+                    return Err(());
+                };
+                let Some(c) = token.text().chars().next() else {
+                    self.diagnostic.report(miette!("Byte literal is empty"));
+                    return Err(());
+                };
+                if !c.is_ascii() {
+                    self.diagnostic.report(labelled(
+                        token.text_range(),
+                        "use a `\\xNN` escape",
+                        format!("Non-ASCII character `{}` in byte literal", c),
+                    ));
+                    return Err(());
+                }
+
+                Ok(c as u8)
+            }
+            ast::CharacterLiteralKind::Escape(node) => {
+                self.byte_escape(node.syntax(), node.segment(), "byte")
+            }
+            ast::CharacterLiteralKind::Unicode(node) => {
+                self.diagnostic.report(labelled(
+                    node.syntax().text_range(),
+                    "use a `\\xNN` escape",
+                    "Unicode escape in byte literal".to_owned(),
+                ));
+                Err(())
+            }
+        }
+    }
+
+    /// Reads an escape in a `literal` literal as a character, where `\xNN` is at most `\x7F`.
+    fn escape(
+        &mut self,
+        node: &SyntaxNode,
+        segment: Option<SyntaxToken>,
+        literal: &str,
+    ) -> Result<char, ()> {
+        let Some(segment) = segment else {
+            self.diagnostic.report(miette!(
+                "Escape without an escape sequence in {} literal",
+                literal
+            ));
+            return Err(());
+        };
+        match escape_value(segment.text()) {
+            Some(value) if value.is_ascii() => Ok(value as char),
+            Some(_) => {
+                self.diagnostic.report(labelled(
+                    node.text_range(),
+                    "must be at most `\\x7F`",
+                    format!(
+                        "Escape sequence `\\{}` out of range in {} literal",
+                        segment.text(),
+                        literal
+                    ),
+                ));
+                Err(())
+            }
+            None => {
+                self.diagnostic
+                    .report(invalid_escape(node, &segment, literal));
+                Err(())
+            }
+        }
+    }
+
+    /// Reads a `\u{...}` escape in a `literal` literal, which must be a Unicode scalar value.
+    fn unicode(
+        &mut self,
+        node: &SyntaxNode,
+        segments: impl Iterator<Item = SyntaxToken>,
+        literal: &str,
+    ) -> Result<char, ()> {
+        let code_point: String = segments.map(|s| s.text().to_owned()).collect();
+        // The lexer has reported escapes that are unterminated or have invalid digits.
+        let terminated = node
+            .children_with_tokens()
+            .any(|child| child.kind() == SyntaxKind::UNICODE_END);
+        if !terminated || !code_point.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(());
+        }
+
+        let label = match u32::from_str_radix(&code_point, 16) {
+            Ok(value) if code_point.len() <= 6 => match std::char::from_u32(value) {
+                Some(c) => return Ok(c),
+                None if (0xD800..=0xDFFF).contains(&value) => "surrogates are not characters",
+                None => "must be at most `10FFFF`",
+            },
+            Ok(_) => "must be at most `10FFFF`",
+            Err(_) => "expected hex digits",
+        };
+        self.diagnostic.report(labelled(
+            node.text_range(),
+            label,
+            format!(
+                "Invalid unicode escape `\\u{{{}}}` in {} literal",
+                code_point, literal
+            ),
+        ));
+
+        Err(())
+    }
+
+    /// Reads an escape in a `literal` literal as a byte, where `\xNN` may be any byte.
+    fn byte_escape(
+        &mut self,
+        node: &SyntaxNode,
+        segment: Option<SyntaxToken>,
+        literal: &str,
+    ) -> Result<u8, ()> {
+        let Some(segment) = segment else {
+            self.diagnostic.report(miette!(
+                "Escape without an escape sequence in {} literal",
+                literal
+            ));
+            return Err(());
+        };
+        let Some(value) = escape_value(segment.text()) else {
+            self.diagnostic
+                .report(invalid_escape(node, &segment, literal));
+            return Err(());
+        };
+
+        Ok(value)
+    }
+
+    /// Reads the bytes of a byte string or C string literal.
+    ///
+    /// Byte strings are ASCII apart from `\xNN` escapes. C strings are UTF-8 and may not contain
+    /// a nul, which is added after the last byte.
+    fn bytes(
+        &mut self,
+        segments: impl Iterator<Item = ast::StringSegment>,
+        literal: &str,
+        c_string: bool,
+    ) -> Result<Vec<u8>, ()> {
+        let mut bytes = vec![];
+        for segment in segments {
+            let range = segment.syntax().text_range();
+            let value = match segment {
+                ast::StringSegment::Text(node) => {
+                    let text = node.syntax().text().to_string();
+                    if !c_string
+                        && let Some((offset, c)) = text.char_indices().find(|(_, c)| !c.is_ascii())
+                    {
+                        let start = range.start() + rowan::TextSize::from(offset as u32);
+                        let end = start + rowan::TextSize::of(c);
+                        self.diagnostic.report(labelled(
+                            rowan::TextRange::new(start, end),
+                            "use a `\\xNN` escape",
+                            format!("Non-ASCII character `{}` in {} literal", c, literal),
+                        ));
+                        return Err(());
+                    }
+
+                    text.into_bytes()
+                }
+                ast::StringSegment::Escape(node) => {
+                    vec![self.byte_escape(node.syntax(), node.segment(), literal)?]
+                }
+                ast::StringSegment::Unicode(node) if c_string => {
+                    let c = self.unicode(node.syntax(), node.segments(), literal)?;
+
+                    c.to_string().into_bytes()
+                }
+                ast::StringSegment::Unicode(_) => {
+                    self.diagnostic.report(labelled(
+                        range,
+                        "use `\\xNN` escapes",
+                        format!("Unicode escape in {} literal", literal),
+                    ));
+                    return Err(());
+                }
+                ast::StringSegment::Interpolation(_) => {
+                    self.diagnostic.report(labelled(
+                        range,
+                        "interpolation",
+                        format!("Interpolation in {} literal", literal),
+                    ));
+                    return Err(());
+                }
+            };
+            if c_string && value.contains(&0) {
+                self.diagnostic.report(labelled(
+                    range,
+                    "nul byte",
+                    format!("Nul byte in {} literal", literal),
+                ));
+                return Err(());
+            }
+
+            bytes.extend(value);
+        }
+
+        Ok(bytes)
+    }
+
     fn path(&mut self, node: ast::Path) -> Result<Vec<hir::PathSegment>, ()> {
         let mut segments = vec![];
         for segment in node.segments() {
@@ -2467,11 +2705,11 @@ impl<'lowering> DefinitionCollector<'lowering> {
             return Err(());
         };
 
+        // Operators of several characters are nodes around their tokens: `+=` is `+` and `=`.
         match node
             .syntax()
-            .descendants_with_tokens()
-            .find_map(|node| node.into_token())
-            .map(|t| t.kind())
+            .first_child_or_token()
+            .map(|child| child.kind())
         {
             Some(PLUS) => Ok(hir::BinaryOperator::Add),
             Some(PLUS__PIPE) => Ok(hir::BinaryOperator::SaturatingAdd),
@@ -2523,11 +2761,11 @@ impl<'lowering> DefinitionCollector<'lowering> {
             return Err(());
         };
 
+        // Operators of several characters are nodes around their tokens: `+=` is `+` and `=`.
         match node
             .syntax()
-            .descendants_with_tokens()
-            .find_map(|node| node.into_token())
-            .map(|t| t.kind())
+            .first_child_or_token()
+            .map(|child| child.kind())
         {
             Some(EQUAL) => Ok(hir::AssignmentOperator::Assign),
             Some(PLUS__EQUAL) => Ok(hir::AssignmentOperator::Add),
@@ -2564,7 +2802,7 @@ impl<'lowering> DefinitionCollector<'lowering> {
             _ => {
                 self.diagnostic
                     .report(miette!("Unknown assignment operator"));
-                return Err(());
+                Err(())
             }
         }
     }
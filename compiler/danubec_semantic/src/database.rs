@@ -0,0 +1,348 @@
+use crate::{
+    fs::{Fs, ModuleError},
+    outline::{Outline, outline},
+};
+use danubec_ast as ast;
+use danubec_diagnostic::Diagnostic;
+use danubec_symbol::FileId;
+use danubec_syntax::{AstNode, SyntaxNode};
+use fxhash::FxHashMap;
+use miette::{LabeledSpan, Severity};
+use rowan::GreenNode;
+use std::{
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Memoised, demand-driven queries over the sources of a crate.
+///
+/// Every input change bumps the revision. A memo records the queries it read, and is
+/// reused as long as none of them changed since it was last verified. A recomputed
+/// value equal to the previous one keeps its old `changed_at`, so the queries that read
+/// it are not recomputed.
+///
+/// The queries stop at sources, parses and module files. [`semantic`](crate::semantic)
+/// reads parsed sources from here but still collects and resolves from scratch.
+#[derive(Debug)]
+pub struct Database {
+    fs: Fs,
+    revision: Revision,
+    memos: FxHashMap<Query, Memo>,
+    stack: Vec<Vec<Query>>,
+    executed: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Revision(usize);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Query {
+    Source(FileId),
+    Parse(FileId),
+    Module(FileId, String),
+    ModuleAt(FileId, PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Source(Option<Arc<str>>),
+    Parse(Option<Parse>),
+    Module(Result<FileId, ModuleError>),
+}
+
+#[derive(Debug)]
+struct Memo {
+    value: Value,
+    changed_at: Revision,
+    verified_at: Revision,
+    dependencies: Vec<Query>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parse {
+    green: GreenNode,
+    errors: Arc<[SyntaxError]>,
+}
+
+/// An error from parsing or validating a file, kept as data so that parses can be
+/// memoised and compared. Reports from later passes convert to it too, see
+/// [`Database::diagnostics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub message: String,
+    pub severity: Option<Severity>,
+    /// Byte ranges in the file, each with an optional label.
+    pub labels: Vec<(Range<usize>, Option<String>)>,
+}
+
+impl Database {
+    pub fn new() -> Self {
+        Self::with_fs(Fs::new())
+    }
+
+    pub fn with_fs(fs: Fs) -> Self {
+        Self {
+            fs,
+            revision: Revision(0),
+            memos: FxHashMap::default(),
+            stack: vec![],
+            executed: 0,
+        }
+    }
+
+    #[inline]
+    pub fn krate(&mut self, path: PathBuf) -> FileId {
+        self.fs.krate(path)
+    }
+
+    /// See [`Fs::file`].
+    #[inline]
+    pub fn file(&mut self, path: PathBuf) -> FileId {
+        self.fs.file(path)
+    }
+
+    /// See [`Fs::std`].
+    #[inline]
+    pub fn std(&mut self) -> PathBuf {
+        self.fs.std()
+    }
+
+    #[inline]
+    pub const fn fs(&self) -> &Fs {
+        &self.fs
+    }
+
+    #[inline]
+    pub const fn revision(&self) -> Revision {
+        self.revision
+    }
+
+    /// Number of query executions so far, cache hits excluded.
+    #[inline]
+    pub const fn executed(&self) -> usize {
+        self.executed
+    }
+
+    pub fn set_source(&mut self, file: FileId, source: String) {
+        let query = Query::Source(file);
+        let value = Value::Source(Some(Arc::from(source)));
+        if self
+            .memos
+            .get(&query)
+            .is_some_and(|memo| memo.value == value)
+        {
+            return;
+        }
+
+        if let Some(path) = self.fs.path(file).cloned()
+            && let Value::Source(Some(source)) = &value
+        {
+            self.fs.overlay(path, source.to_string());
+        }
+
+        self.revision.0 += 1;
+        self.memos.insert(
+            query,
+            Memo {
+                value,
+                changed_at: self.revision,
+                verified_at: self.revision,
+                dependencies: vec![],
+            },
+        );
+    }
+
+    pub fn source(&mut self, file: FileId) -> Option<Arc<str>> {
+        match self.fetch(Query::Source(file)) {
+            Value::Source(source) => source,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn parse(&mut self, file: FileId) -> Option<Parse> {
+        match self.fetch(Query::Parse(file)) {
+            Value::Parse(parse) => parse,
+            _ => unreachable!(),
+        }
+    }
+
+    /// The definitions in `file`, for an editor to list.
+    pub fn outline(&mut self, file: FileId) -> Vec<Outline> {
+        let Some(parse) = self.parse(file) else {
+            return vec![];
+        };
+
+        ast::Root::cast(parse.syntax())
+            .map(|root| outline(&root))
+            .unwrap_or_default()
+    }
+
+    /// The reports of `diagnostic`, such as those of [`semantic`](crate::semantic), by the
+    /// file their labels point into. Reports without a file are keyed by `None`.
+    pub fn diagnostics(&self, diagnostic: &Diagnostic) -> Vec<(Option<FileId>, SyntaxError)> {
+        diagnostic
+            .iter()
+            .map(|report| {
+                let file = report
+                    .source_code()
+                    .and_then(|source| source.read_span(&(0..0).into(), 0, 0).ok())
+                    .and_then(|contents| contents.name().map(|name| self.fs.lookup(name.as_ref())))
+                    .flatten();
+
+                (file, SyntaxError::new(report))
+            })
+            .collect()
+    }
+
+    pub fn module(&mut self, parent: FileId, path: &str) -> Result<FileId, ModuleError> {
+        match self.fetch(Query::Module(parent, path.to_owned())) {
+            Value::Module(file) => file,
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn module_at(&mut self, parent: FileId, path: &Path) -> Result<FileId, ModuleError> {
+        match self.fetch(Query::ModuleAt(parent, path.to_path_buf())) {
+            Value::Module(file) => file,
+            _ => unreachable!(),
+        }
+    }
+
+    fn fetch(&mut self, query: Query) -> Value {
+        if let Some(dependencies) = self.stack.last_mut() {
+            dependencies.push(query.clone());
+        }
+
+        if self.validate(&query) {
+            return self.memos[&query].value.clone();
+        }
+
+        self.execute(query)
+    }
+
+    fn validate(&mut self, query: &Query) -> bool {
+        let Some(memo) = self.memos.get(query) else {
+            return false;
+        };
+        if memo.verified_at == self.revision || matches!(query, Query::Source(_)) {
+            return true;
+        }
+        // Module lookups read the file system directly, so they are redone once per revision.
+        if matches!(query, Query::Module(..) | Query::ModuleAt(..)) {
+            return false;
+        }
+
+        let verified_at = memo.verified_at;
+        for dependency in memo.dependencies.clone() {
+            if !self.validate(&dependency) {
+                self.execute(dependency.clone());
+            }
+            if self.memos[&dependency].changed_at > verified_at {
+                return false;
+            }
+        }
+
+        if let Some(memo) = self.memos.get_mut(query) {
+            memo.verified_at = self.revision;
+        }
+
+        true
+    }
+
+    fn execute(&mut self, query: Query) -> Value {
+        self.stack.push(vec![]);
+        let value = match &query {
+            Query::Source(file) => Value::Source(self.fs.source(*file).map(Arc::from)),
+            Query::Parse(file) => Value::Parse(self.execute_parse(*file)),
+            Query::Module(parent, path) => Value::Module(self.fs.module(*parent, path)),
+            Query::ModuleAt(parent, path) => Value::Module(self.fs.module_at(*parent, path)),
+        };
+        let dependencies = self.stack.pop().unwrap_or_default();
+        self.executed += 1;
+
+        let changed_at = match self.memos.get(&query) {
+            Some(memo) if memo.value == value => memo.changed_at,
+            _ => self.revision,
+        };
+        self.memos.insert(
+            query,
+            Memo {
+                value: value.clone(),
+                changed_at,
+                verified_at: self.revision,
+                dependencies,
+            },
+        );
+
+        value
+    }
+
+    fn execute_parse(&mut self, file: FileId) -> Option<Parse> {
+        let source = self.source(file)?;
+        let mut diagnostic = Diagnostic::new();
+        let node = danubec_parse::parse(&source, &mut diagnostic);
+        if let Some(root) = ast::Root::cast(node.clone()) {
+            ast::validate(&root, &mut diagnostic);
+        }
+
+        Some(Parse {
+            green: node.green().into_owned(),
+            errors: diagnostic.iter().map(SyntaxError::new).collect(),
+        })
+    }
+}
+
+impl Default for Database {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parse {
+    #[inline]
+    pub fn syntax(&self) -> SyntaxNode {
+        SyntaxNode::new_root(self.green.clone())
+    }
+
+    #[inline]
+    pub fn errors(&self) -> &[SyntaxError] {
+        &self.errors
+    }
+}
+
+impl SyntaxError {
+    fn new(report: &miette::Report) -> Self {
+        let labels = report
+            .labels()
+            .into_iter()
+            .flatten()
+            .map(|label| {
+                let range = label.offset()..label.offset() + label.len();
+                (range, label.label().map(str::to_owned))
+            })
+            .collect();
+
+        Self {
+            message: report.to_string(),
+            severity: report.severity(),
+            labels,
+        }
+    }
+
+    /// The error as a report, which the caller gives the source of its file.
+    pub fn report(&self) -> miette::Report {
+        let labels: Vec<_> = self
+            .labels
+            .iter()
+            .map(|(range, label)| LabeledSpan::new_with_span(label.clone(), range.clone()))
+            .collect();
+
+        miette!(
+            severity = self.severity.unwrap_or(Severity::Error),
+            labels = labels,
+            "{}",
+            self.message
+        )
+    }
+}
@@ -1,19 +1,58 @@
-use danubec_hir::{Attribute, Binding, Import, ImportKind, Path, PathSegment, Visibility};
-use danubec_symbol::{AttributeId, DefinitionId, FileId, ImplementId, ModuleId, ScopeId, Symbol};
+use crate::{
+    attribute::{Builtin, Deprecation},
+    primitive::Primitive,
+};
+use danubec_hir::{
+    Attribute, Binding, Import, ImportKind, Path, PathSegment, PathSegmentKind, TopLevelAttribute,
+    Visibility,
+};
+use danubec_symbol::{
+    AttributeId, DefinitionId, FileId, ImplementId, KrateId, ModuleId, ScopeId, Symbol,
+};
+use danubec_syntax::Span;
 use fxhash::FxHashMap;
-use slotmap::SlotMap;
+use miette::NamedSource;
+use slotmap::{SecondaryMap, SlotMap};
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct Env {
+    krates: SlotMap<KrateId, Krate>,
     modules: SlotMap<ModuleId, Module>,
     scopes: SlotMap<ScopeId, Scope>,
     attributes: SlotMap<AttributeId, Attribute>,
+    builtins: SecondaryMap<AttributeId, Builtin>,
     definitions: SlotMap<DefinitionId, Definition>,
     implements: SlotMap<ImplementId, Implement>,
+    primitives: FxHashMap<Symbol, Primitive>,
+    /// The types inferred for numeric literals.
+    literals: FxHashMap<(FileId, Span), Primitive>,
+    /// The definitions that bound paths name, by the name that names them.
+    references: FxHashMap<(FileId, Span), DefinitionId>,
+    /// The text of every collected file, named by its path, for reports to point into.
+    sources: Sources,
+}
+
+/// Only the names are printed, as the text of every file would drown out the rest.
+#[derive(Default)]
+struct Sources(FxHashMap<FileId, NamedSource<Arc<str>>>);
+
+#[derive(Debug)]
+pub struct Krate {
+    pub name: Symbol,
+    pub root: ModuleId,
+    /// Crates reachable through `::name` paths, keyed by the name the manifest gives them.
+    pub dependencies: FxHashMap<Symbol, KrateId>,
+    /// The module whose items are visible in every module of the crate unless shadowed.
+    pub prelude: Option<ModuleId>,
 }
 
 #[derive(Debug)]
 pub struct Module {
+    pub krate: KrateId,
+    pub attributes: Vec<TopLevelAttribute>,
+    /// The built-in attributes among `attributes`.
+    pub builtins: Vec<Builtin>,
     pub parent: Option<ModuleId>,
     pub children: FxHashMap<Symbol, ModuleId>,
     pub scope: ScopeId,
@@ -57,20 +96,52 @@ pub enum Namespace {
     Type,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolved {
+    Module(ModuleId),
+    Definition(DefinitionId),
+    Primitive(Primitive),
+}
+
 impl Env {
     pub fn new() -> Self {
         Self {
+            krates: SlotMap::with_key(),
             modules: SlotMap::with_key(),
             scopes: SlotMap::with_key(),
             attributes: SlotMap::with_key(),
+            builtins: SecondaryMap::new(),
             definitions: SlotMap::with_key(),
             implements: SlotMap::with_key(),
+            primitives: FxHashMap::default(),
+            literals: FxHashMap::default(),
+            references: FxHashMap::default(),
+            sources: Sources::default(),
         }
     }
 
-    pub fn module(&mut self, file: FileId, parent: Option<ModuleId>) -> ModuleId {
+    pub fn krate(&mut self, name: Symbol, file: FileId) -> KrateId {
+        let krate = self.krates.insert(Krate {
+            name,
+            root: ModuleId::default(),
+            dependencies: FxHashMap::default(),
+            prelude: None,
+        });
+        self.krates[krate].root = self.module(krate, file, None);
+
+        krate
+    }
+
+    pub fn krates(&self) -> impl Iterator<Item = (KrateId, &Krate)> {
+        self.krates.iter()
+    }
+
+    pub fn module(&mut self, krate: KrateId, file: FileId, parent: Option<ModuleId>) -> ModuleId {
         let scope = self.scope(Scope::new(ScopeKind::Module).parent_module(parent));
         self.modules.insert(Module {
+            krate,
+            attributes: vec![],
+            builtins: vec![],
             parent,
             scope,
             children: FxHashMap::default(),
@@ -86,6 +157,25 @@ impl Env {
         self.attributes.insert(attribute)
     }
 
+    /// Records what `attribute` means as a built-in attribute.
+    pub fn builtin(&mut self, attribute: AttributeId, builtin: Builtin) {
+        self.builtins.insert(attribute, builtin);
+    }
+
+    pub fn builtins(&self, attributes: &[AttributeId]) -> impl Iterator<Item = &Builtin> {
+        attributes
+            .iter()
+            .filter_map(|attribute| self.builtins.get(*attribute))
+    }
+
+    pub fn deprecation(&self, definition: DefinitionId) -> Option<&Deprecation> {
+        self.builtins(&self[definition].definition.attributes)
+            .find_map(|builtin| match builtin {
+                Builtin::Deprecated(deprecation) => Some(deprecation),
+                _ => None,
+            })
+    }
+
     pub fn definition(&mut self, definition: Definition) -> DefinitionId {
         self.definitions.insert(definition)
     }
@@ -93,6 +183,190 @@ impl Env {
     pub fn implement(&mut self, implement: Implement) -> ImplementId {
         self.implements.insert(implement)
     }
+
+    pub fn primitive(&mut self, symbol: Symbol, primitive: Primitive) {
+        self.primitives.insert(symbol, primitive);
+    }
+
+    /// Records the type of the numeric literal at `span`, returning whether it was new.
+    pub fn literal(&mut self, file: FileId, span: Span, primitive: Primitive) -> bool {
+        self.literals.insert((file, span), primitive).is_none()
+    }
+
+    pub fn literal_type(&self, file: FileId, span: Span) -> Option<Primitive> {
+        self.literals.get(&(file, span)).copied()
+    }
+
+    /// Records that the name at `span` refers to `definition`.
+    pub fn reference(&mut self, file: FileId, span: Span, definition: DefinitionId) {
+        self.references.insert((file, span), definition);
+    }
+
+    /// The definition that the name around `offset` in `file` refers to.
+    pub fn reference_at(&self, file: FileId, offset: usize) -> Option<(Span, DefinitionId)> {
+        self.references
+            .iter()
+            .find(|((reference, span), _)| {
+                let range = span.text_range();
+                *reference == file
+                    && usize::from(range.start()) <= offset
+                    && offset <= usize::from(range.end())
+            })
+            .map(|((_, span), definition)| (*span, *definition))
+    }
+
+    pub fn source(&mut self, file: FileId, source: NamedSource<Arc<str>>) {
+        self.sources.0.insert(file, source);
+    }
+
+    /// The named source of `file`, to attach to the reports about it.
+    pub fn source_code(&self, file: FileId) -> Option<NamedSource<Arc<str>>> {
+        self.sources.0.get(&file).cloned()
+    }
+
+    pub fn modules(&self) -> impl Iterator<Item = (ModuleId, &Module)> {
+        self.modules.iter()
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = (DefinitionId, &Definition)> {
+        self.definitions.iter()
+    }
+
+    /// Resolves `segments` as written in `module`.
+    ///
+    /// `::name` starts at the root of a dependency of the module's crate, and `krate`,
+    /// `self` and `super` are relative to the module.
+    pub fn resolve_path(&self, module: ModuleId, segments: &[PathSegment]) -> Option<Resolved> {
+        let (first, rest) = segments.split_first()?;
+        let (module, rest) = match &first.kind {
+            PathSegmentKind::Root => {
+                let (name, rest) = rest.split_first()?;
+                let PathSegmentKind::Identifier(name) = &name.kind else {
+                    return None;
+                };
+                let krate = self[self[module].krate].dependencies.get(&name.symbol)?;
+
+                (self[*krate].root, rest)
+            }
+            PathSegmentKind::Krate => (self[self[module].krate].root, rest),
+            PathSegmentKind::Self_ => (module, rest),
+            PathSegmentKind::Super_ => (self[module].parent?, rest),
+            PathSegmentKind::Identifier(_) => (module, segments),
+        };
+
+        let relative = rest.len() == segments.len();
+        let mut resolved = Resolved::Module(module);
+        for (index, segment) in rest.iter().enumerate() {
+            let Resolved::Module(module) = resolved else {
+                return None;
+            };
+            resolved = match &segment.kind {
+                PathSegmentKind::Identifier(name) if relative && index == 0 => {
+                    self.resolve_name(module, name.symbol)?
+                }
+                PathSegmentKind::Identifier(name) => self.lookup(module, name.symbol)?,
+                PathSegmentKind::Super_ => Resolved::Module(self[module].parent?),
+                _ => return None,
+            };
+        }
+
+        Some(resolved)
+    }
+
+    /// Resolves `segments` as written in a body in `scope`: a single name is looked up in
+    /// the blocks around it first, then everything as in [`Env::resolve_path`].
+    pub fn resolve_path_in(&self, scope: ScopeId, segments: &[PathSegment]) -> Option<Resolved> {
+        if let [segment] = segments
+            && let PathSegmentKind::Identifier(name) = &segment.kind
+        {
+            let mut current = Some(scope);
+            while let Some(scope) = current
+                && self[scope].kind != ScopeKind::Module
+            {
+                let definition =
+                    [Namespace::Type, Namespace::Value]
+                        .into_iter()
+                        .find_map(|namespace| {
+                            self[scope]
+                                .definitions
+                                .get(&(namespace, name.symbol))?
+                                .first()
+                                .copied()
+                        });
+                if let Some(definition) = definition {
+                    return Some(Resolved::Definition(definition));
+                }
+                current = self[scope].parent;
+            }
+        }
+
+        self.resolve_path(self.module_of(scope)?, segments)
+    }
+
+    /// Resolves a single name as written in `module`: its own items and imports first,
+    /// then the prelude of its crate, then the primitive types.
+    pub fn resolve_name(&self, module: ModuleId, symbol: Symbol) -> Option<Resolved> {
+        self.lookup(module, symbol)
+            .or_else(|| {
+                let prelude = self[self[module].krate].prelude?;
+                self.lookup(prelude, symbol)
+            })
+            .or_else(|| {
+                let primitive = self.primitives.get(&symbol)?;
+                Some(Resolved::Primitive(*primitive))
+            })
+    }
+
+    /// The module whose items are visible from `scope`.
+    pub fn module_of(&self, scope: ScopeId) -> Option<ModuleId> {
+        let mut scope = Some(scope);
+        while let Some(current) = scope {
+            if let Some((module, _)) = self.modules().find(|(_, module)| module.scope == current) {
+                return Some(module);
+            }
+            scope = self[current].parent;
+        }
+
+        None
+    }
+
+    /// Finds `symbol` among the child modules, definitions and resolved imports of `module`.
+    fn lookup(&self, module: ModuleId, symbol: Symbol) -> Option<Resolved> {
+        if let Some(child) = self[module].children.get(&symbol) {
+            return Some(Resolved::Module(*child));
+        }
+
+        let scope = &self[self[module].scope];
+        let definition = [Namespace::Type, Namespace::Value]
+            .into_iter()
+            .find_map(|namespace| {
+                scope
+                    .definitions
+                    .get(&(namespace, symbol))?
+                    .first()
+                    .copied()
+            });
+        if let Some(definition) = definition {
+            return Some(Resolved::Definition(definition));
+        }
+
+        scope.imports.iter().find_map(|import| {
+            let name = match &import.kind {
+                ImportKind::Symbol(Some(name)) => name.symbol,
+                ImportKind::Symbol(None) => match &import.path.segments.last()?.kind {
+                    PathSegmentKind::Identifier(name) => name.symbol,
+                    _ => return None,
+                },
+                _ => return None,
+            };
+            match import.path.binding {
+                Binding::Definition(definition) if name == symbol => {
+                    Some(Resolved::Definition(definition))
+                }
+                _ => None,
+            }
+        })
+    }
 }
 
 impl Scope {
@@ -119,6 +393,16 @@ impl Scope {
         self
     }
 
+    #[inline]
+    pub fn imports(&self) -> &[Import] {
+        &self.imports
+    }
+
+    #[inline]
+    pub fn imports_mut(&mut self) -> &mut [Import] {
+        &mut self.imports
+    }
+
     pub fn definition(
         &mut self,
         (namespace, symbol): (Namespace, Symbol),
@@ -153,6 +437,37 @@ impl Scope {
     }
 }
 
+impl std::fmt::Debug for Sources {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<_> = self.0.values().map(NamedSource::name).collect();
+        names.sort();
+
+        f.debug_list().entries(names).finish()
+    }
+}
+
+impl Default for Env {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Index<KrateId> for Env {
+    type Output = Krate;
+
+    #[inline]
+    fn index(&self, index: KrateId) -> &Self::Output {
+        &self.krates[index]
+    }
+}
+
+impl std::ops::IndexMut<KrateId> for Env {
+    #[inline]
+    fn index_mut(&mut self, index: KrateId) -> &mut Self::Output {
+        &mut self.krates[index]
+    }
+}
+
 impl std::ops::Index<ModuleId> for Env {
     type Output = Module;
 
@@ -185,6 +500,15 @@ impl std::ops::IndexMut<ScopeId> for Env {
     }
 }
 
+impl std::ops::Index<AttributeId> for Env {
+    type Output = Attribute;
+
+    #[inline]
+    fn index(&self, index: AttributeId) -> &Self::Output {
+        &self.attributes[index]
+    }
+}
+
 impl std::ops::Index<DefinitionId> for Env {
     type Output = Definition;
 
@@ -194,6 +518,13 @@ impl std::ops::Index<DefinitionId> for Env {
     }
 }
 
+impl std::ops::IndexMut<DefinitionId> for Env {
+    #[inline]
+    fn index_mut(&mut self, index: DefinitionId) -> &mut Self::Output {
+        &mut self.definitions[index]
+    }
+}
+
 impl std::ops::Index<Symbol> for Module {
     type Output = ModuleId;
 
@@ -1,10 +1,41 @@
 use danubec_symbol::FileId;
 use slotmap::{SecondaryMap, SlotMap};
-use std::path::PathBuf;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Component, Path, PathBuf},
+};
+
+pub trait FileSystem: std::fmt::Debug {
+    fn read(&self, path: &Path) -> Option<String>;
+
+    fn exists(&self, path: &Path) -> bool;
+
+    fn canonicalize(&self, path: &Path) -> Option<PathBuf>;
+}
+
+#[derive(Debug)]
+pub struct DiskFileSystem;
+
+#[derive(Debug, Default)]
+pub struct MemoryFileSystem {
+    files: BTreeMap<PathBuf, String>,
+}
 
 #[derive(Debug)]
 pub struct Fs {
     inner: Inner,
+    /// Files whose child modules live next to them rather than in a directory named after them:
+    /// crate roots, `mod.dnb` and `#[path]` targets.
+    owners: BTreeSet<FileId>,
+    /// Keyed by [`key`], like the files.
+    overlays: BTreeMap<PathBuf, String>,
+    backend: Box<dyn FileSystem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModuleError {
+    NotFound { tried: Vec<PathBuf> },
+    Ambiguous { candidates: Vec<PathBuf> },
 }
 
 #[derive(Debug)]
@@ -12,49 +43,150 @@ struct Inner {
     paths: SlotMap<FileId, PathBuf>,
     canonicals: SecondaryMap<FileId, PathBuf>,
     files: SecondaryMap<FileId, String>,
+    /// Files by the [`key`] of their path and by their canonical path.
+    ids: BTreeMap<PathBuf, FileId>,
+    canonical_ids: BTreeMap<PathBuf, FileId>,
 }
 
 impl Fs {
     pub fn new() -> Self {
+        Self::with(DiskFileSystem)
+    }
+
+    pub fn with(backend: impl FileSystem + 'static) -> Self {
         Self {
             inner: Inner::new(),
+            owners: BTreeSet::new(),
+            overlays: BTreeMap::new(),
+            backend: Box::new(backend),
         }
     }
 
-    #[inline]
     pub fn krate(&mut self, path: PathBuf) -> FileId {
-        self.inner.file(path)
+        let file_id = self.inner.file(path, &*self.backend);
+        self.owners.insert(file_id);
+
+        file_id
     }
 
-    pub fn module(&mut self, parent_id: FileId, path: &str) -> Option<FileId> {
-        let parent = self.inner.path(parent_id)?.parent()?.join(path);
+    /// Finds `mod path;` declared in `parent_id`, either as `path.dnb` or `path/mod.dnb`.
+    ///
+    /// Children of `a.dnb` live in `a/`, unless it owns its directory.
+    pub fn module(&mut self, parent_id: FileId, path: &str) -> Result<FileId, ModuleError> {
+        let directory = self.directory(parent_id);
+        let base = directory.join(path);
+        let candidates = [
+            base.with_extension("dnb"),
+            base.join("mod").with_extension("dnb"),
+        ];
 
-        {
-            let path = parent.with_extension("dnb");
-            if path.exists() {
-                return Some(self.inner.file(path));
+        let mut found: Vec<_> = candidates
+            .iter()
+            .filter(|path| self.exists(path))
+            .cloned()
+            .collect();
+        match found.len() {
+            0 => Err(ModuleError::NotFound {
+                tried: candidates.to_vec(),
+            }),
+            1 => {
+                let path = found.pop().unwrap();
+                let owner = path.file_stem().is_some_and(|stem| stem == "mod");
+                let file_id = self.inner.file(path, &*self.backend);
+                if owner {
+                    self.owners.insert(file_id);
+                }
+
+                Ok(file_id)
             }
+            _ => Err(ModuleError::Ambiguous { candidates: found }),
         }
+    }
 
+    /// Finds `#[path = "path"] mod name;`, relative to the directory of `parent_id`.
+    pub fn module_at(&mut self, parent_id: FileId, path: &Path) -> Result<FileId, ModuleError> {
+        let path = match self
+            .inner
+            .path(parent_id)
+            .and_then(|parent| parent.parent())
         {
-            let path = parent.join("mod").with_extension("dnb");
-            if path.exists() {
-                return Some(self.inner.file(path));
-            }
+            Some(directory) => directory.join(path),
+            None => path.to_path_buf(),
+        };
+        if !self.exists(&path) {
+            return Err(ModuleError::NotFound { tried: vec![path] });
         }
 
-        None
+        let file_id = self.inner.file(path, &*self.backend);
+        self.owners.insert(file_id);
+
+        Ok(file_id)
     }
 
+    /// The id of the file at `path`, such as one opened in an editor before any module
+    /// declares it.
     #[inline]
+    pub fn file(&mut self, path: PathBuf) -> FileId {
+        self.inner.file(path, &*self.backend)
+    }
+
+    /// The id already given to the file at `path`, if any.
+    #[inline]
+    pub fn lookup(&self, path: &Path) -> Option<FileId> {
+        self.inner.ids.get(&key(path)).copied()
+    }
+
     pub fn source(&mut self, file_id: FileId) -> Option<&str> {
-        self.inner.source(file_id)
+        let path = self.inner.path(file_id)?;
+        if let Some(source) = self.overlays.get(&key(path)) {
+            return Some(source);
+        }
+
+        self.inner.source(file_id, &*self.backend)
     }
 
     #[inline]
     pub fn path(&self, file_id: FileId) -> Option<&PathBuf> {
         self.inner.path(file_id)
     }
+
+    /// Replaces the contents of `path` with unsaved text, whether or not it exists in the backend.
+    pub fn overlay(&mut self, path: PathBuf, source: String) {
+        self.overlays.insert(key(&path), source);
+    }
+
+    pub fn remove_overlay(&mut self, path: &Path) -> Option<String> {
+        self.overlays.remove(&key(path))
+    }
+
+    /// Whether both ids name the same file on the backend, e.g. through `..` or a symlink.
+    pub fn same_file(&self, lhs: FileId, rhs: FileId) -> bool {
+        match (self.inner.canonicalized(lhs), self.inner.canonicalized(rhs)) {
+            (Some(lhs), Some(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.overlays.contains_key(&key(path)) || self.backend.exists(path)
+    }
+
+    fn directory(&self, file_id: FileId) -> PathBuf {
+        let Some(path) = self.inner.path(file_id) else {
+            return PathBuf::new();
+        };
+        let directory = path.parent().unwrap_or(Path::new(""));
+        match path.file_stem() {
+            Some(stem) if !self.owners.contains(&file_id) => directory.join(stem),
+            _ => directory.to_path_buf(),
+        }
+    }
+}
+
+impl Default for Fs {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Inner {
@@ -63,21 +195,30 @@ impl Inner {
             paths: SlotMap::with_key(),
             canonicals: SecondaryMap::new(),
             files: SecondaryMap::new(),
+            ids: BTreeMap::new(),
+            canonical_ids: BTreeMap::new(),
         }
     }
 
-    fn file(&mut self, path: PathBuf) -> FileId {
-        if let Some(file_id) = self
-            .paths
-            .iter()
-            .find_map(|(id, p)| (p == &path).then_some(id))
+    fn file(&mut self, path: PathBuf, backend: &dyn FileSystem) -> FileId {
+        let key = key(&path);
+        if let Some(&file_id) = self.ids.get(&key) {
+            return file_id;
+        }
+
+        let canonical = backend.canonicalize(&path);
+        if let Some(canonical) = &canonical
+            && let Some(&file_id) = self.canonical_ids.get(canonical)
         {
+            self.ids.insert(key, file_id);
             return file_id;
         }
 
-        let canonical = path.canonicalize().ok();
         let canonical = canonical.and_then(|c| if c != path { Some(c) } else { None });
-        let file_id = self.paths.insert(path);
+        let file_id = self.paths.insert(path.clone());
+        self.ids.insert(key, file_id);
+        self.canonical_ids
+            .insert(canonical.clone().unwrap_or(path), file_id);
 
         if let Some(canonical) = canonical {
             self.canonicals.insert(file_id, canonical);
@@ -96,13 +237,84 @@ impl Inner {
         self.paths.get(file_id)
     }
 
-    fn source(&mut self, file_id: FileId) -> Option<&str> {
+    fn source(&mut self, file_id: FileId, backend: &dyn FileSystem) -> Option<&str> {
         if !self.files.contains_key(file_id) {
             let path = self.canonicalized(file_id)?;
-            let content = std::fs::read_to_string(path).ok()?;
+            let content = backend.read(path)?;
             self.files.insert(file_id, content);
         }
 
         self.files.get(file_id).map(|s| s.as_str())
     }
 }
+
+impl FileSystem for DiskFileSystem {
+    fn read(&self, path: &Path) -> Option<String> {
+        std::fs::read_to_string(path).ok()
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn canonicalize(&self, path: &Path) -> Option<PathBuf> {
+        path.canonicalize().ok()
+    }
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn file(mut self, path: impl Into<PathBuf>, source: impl Into<String>) -> Self {
+        self.insert(path, source);
+        self
+    }
+
+    pub fn insert(&mut self, path: impl Into<PathBuf>, source: impl Into<String>) {
+        self.files.insert(normalize(&path.into()), source.into());
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn read(&self, path: &Path) -> Option<String> {
+        self.files.get(&normalize(path)).cloned()
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.contains_key(&normalize(path))
+    }
+
+    fn canonicalize(&self, path: &Path) -> Option<PathBuf> {
+        let path = normalize(path);
+        self.files.contains_key(&path).then_some(path)
+    }
+}
+
+/// `path` without `.` components, so that `./src/lib.dnb` and `src/lib.dnb` are the same
+/// file. `..` is kept, since it may follow a symlink.
+fn key(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+/// Resolves `.` and `..` lexically; there are no symlinks in memory.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {
+                //
+            }
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
@@ -0,0 +1,127 @@
+use crate::{attribute::Builtin, env::Env};
+use danubec_diagnostic::Diagnostic;
+use danubec_hir as hir;
+use danubec_symbol::{DefinitionId, ModuleId, ScopeId, SymbolInterner};
+use fxhash::FxHashMap;
+use miette::Severity;
+
+/// A function annotated with `#[test]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Test {
+    /// `krate::module::function`, which filters are matched against.
+    pub path: String,
+    pub definition: DefinitionId,
+    pub expectation: Expectation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    Pass,
+    /// `#[should_fail]`, optionally with a substring the failure message must contain.
+    Fail(Option<String>),
+}
+
+/// Finds the `#[test]` functions of every module whose path contains `filter`, sorted by path.
+pub fn tests(
+    env: &Env,
+    symbols: &SymbolInterner,
+    filter: &str,
+    diagnostic: &mut Diagnostic,
+) -> Vec<Test> {
+    let modules: FxHashMap<ScopeId, ModuleId> = env
+        .modules()
+        .map(|(module, data)| (data.scope, module))
+        .collect();
+
+    let mut tests = vec![];
+    for (definition, data) in env.definitions() {
+        let mut test = false;
+        let mut expectation = Expectation::Pass;
+        for builtin in env.builtins(&data.definition.attributes) {
+            match builtin {
+                Builtin::Test => test = true,
+                Builtin::ShouldFail(message) => expectation = Expectation::Fail(message.clone()),
+                _ => {
+                    //
+                }
+            }
+        }
+
+        let name = &symbols[data.definition.name.symbol];
+        if !test {
+            if expectation != Expectation::Pass {
+                diagnostic.report(miette!(
+                    severity = Severity::Warning,
+                    "`#[should_fail]` on `{}` has no effect without `#[test]`",
+                    name
+                ));
+            }
+            continue;
+        }
+
+        let hir::DefinitionKind::Function { parameters, .. } = &data.definition.kind else {
+            continue;
+        };
+        let Some(module) = modules.get(&data.scope) else {
+            diagnostic.report(miette!(
+                "Test function `{}` must be declared at module level",
+                name
+            ));
+            continue;
+        };
+        if !parameters.is_empty() {
+            diagnostic.report(miette!("Test function `{}` cannot take parameters", name));
+            continue;
+        }
+
+        let path = path(env, symbols, *module, name);
+        if path.contains(filter) {
+            tests.push(Test {
+                path,
+                definition,
+                expectation,
+            });
+        }
+    }
+    tests.sort_by(|lhs, rhs| lhs.path.cmp(&rhs.path));
+
+    tests
+}
+
+/// `krate::module::name` of a definition declared at module level.
+pub(crate) fn definition_path(
+    env: &Env,
+    symbols: &SymbolInterner,
+    definition: DefinitionId,
+) -> Option<String> {
+    let data = &env[definition];
+    let (module, _) = env
+        .modules()
+        .find(|(_, module)| module.scope == data.scope)?;
+
+    Some(path(
+        env,
+        symbols,
+        module,
+        &symbols[data.definition.name.symbol],
+    ))
+}
+
+fn path(env: &Env, symbols: &SymbolInterner, module: ModuleId, name: &str) -> String {
+    let mut segments = vec![name];
+    let mut current = module;
+    while let Some(parent) = env[current].parent {
+        if let Some((symbol, _)) = env[parent]
+            .children
+            .iter()
+            .find(|(_, child)| **child == current)
+        {
+            segments.push(&symbols[*symbol]);
+        }
+        current = parent;
+    }
+    segments.push(&symbols[env[env[module].krate].name]);
+    segments.reverse();
+
+    segments.join("::")
+}
@@ -1,6 +1,12 @@
-use crate::{env::Env, ticker::Ticker};
+use crate::{
+    env::{Env, Resolved},
+    primitive::{self, Primitive},
+    ticker::Ticker,
+};
 use danubec_diagnostic::Diagnostic;
-use danubec_symbol::SymbolInterner;
+use danubec_hir as hir;
+use danubec_symbol::{FileId, ModuleId, SymbolInterner};
+use danubec_syntax::Span;
 
 pub fn inference(
     env: &mut Env,
@@ -8,5 +14,348 @@ pub fn inference(
     diagnostic: &mut Diagnostic,
     ticker: &mut Ticker,
 ) {
-    //
+    let definitions: Vec<_> = env
+        .definitions()
+        .map(|(definition, data)| (definition, data.scope, data.file))
+        .collect();
+
+    for (definition, scope, file) in definitions {
+        let Some(module) = env.module_of(scope) else {
+            continue;
+        };
+        let start = diagnostic.len();
+        let literals = {
+            let mut inferrer = LiteralInferrer::new(env, module);
+            inferrer.definition(&env[definition].definition.kind);
+            inferrer.literals
+        };
+
+        for Literal {
+            span,
+            primitive,
+            value,
+        } in literals
+        {
+            if !env.literal(file, span, primitive) {
+                continue;
+            }
+            ticker.mark();
+
+            if let Some(value) = value
+                && let Some(report) = out_of_range(span, primitive, value)
+            {
+                diagnostic.report(report);
+            }
+        }
+        if let Some(source) = env.source_code(file) {
+            diagnostic.with_source_code(start, source);
+        }
+    }
+}
+
+fn out_of_range(span: Span, primitive: Primitive, value: Value) -> Option<miette::Report> {
+    let range = span.text_range();
+    match value {
+        Value::Integer { value, negated } if !primitive.contains(value, negated) => {
+            let sign = if negated { "-" } else { "" };
+            Some(primitive::out_of_range(
+                range,
+                format_args!("{}{}", sign, value),
+                primitive,
+            ))
+        }
+        Value::Float(value)
+            if primitive == Primitive::F32
+                && value.is_finite()
+                && value.abs() > f32::MAX as f64 =>
+        {
+            Some(primitive::out_of_range(range, value, primitive))
+        }
+        _ => None,
+    }
+}
+
+/// A numeric literal with the type its suffix or context gives it.
+struct Literal {
+    span: Span,
+    primitive: Primitive,
+    /// `None` when the type is a default rather than written or taken from context, and so not
+    /// checked.
+    value: Option<Value>,
+}
+
+#[derive(Clone, Copy)]
+enum Value {
+    Integer { value: u128, negated: bool },
+    Float(f64),
+}
+
+/// Walks a definition, passing the type each expression is expected to have down to its literals.
+///
+/// A suffix wins over the context. Literals with neither default to `i32` and `f64`.
+struct LiteralInferrer<'env> {
+    env: &'env Env,
+    module: ModuleId,
+    return_type: Option<Primitive>,
+    literals: Vec<Literal>,
+}
+
+impl<'env> LiteralInferrer<'env> {
+    fn new(env: &'env Env, module: ModuleId) -> Self {
+        Self {
+            env,
+            module,
+            return_type: None,
+            literals: vec![],
+        }
+    }
+
+    fn definition(&mut self, kind: &hir::DefinitionKind) {
+        match kind {
+            hir::DefinitionKind::Function {
+                return_type, body, ..
+            } => {
+                self.return_type = return_type
+                    .as_ref()
+                    .and_then(|r#type| self.primitive(r#type));
+                if let Some(body) = body {
+                    self.statements(body, self.return_type);
+                }
+            }
+            hir::DefinitionKind::Constant {
+                r#type,
+                initializer: Some(initializer),
+            } => {
+                let expected = r#type.as_ref().and_then(|r#type| self.primitive(r#type));
+                self.expression(initializer, expected);
+            }
+            hir::DefinitionKind::Static {
+                r#type,
+                initializer,
+            } => {
+                let expected = self.primitive(r#type);
+                self.expression(initializer, expected);
+            }
+            hir::DefinitionKind::Enum { variants, .. } => {
+                for variant in variants {
+                    if let hir::EnumVariantKind::Scalar(value) = &variant.kind {
+                        self.expression(value, None);
+                    }
+                }
+            }
+            _ => {
+                //
+            }
+        }
+    }
+
+    /// The last statement is the value of `statements` when it is an expression.
+    fn statements(&mut self, statements: &[hir::Statement], expected: Option<Primitive>) {
+        for (index, statement) in statements.iter().enumerate() {
+            let tail = index + 1 == statements.len();
+            match &statement.kind {
+                hir::StatementKind::Let {
+                    r#type,
+                    initializer: Some(initializer),
+                    ..
+                } => {
+                    let expected = r#type.as_ref().and_then(|r#type| self.primitive(r#type));
+                    self.expression(initializer, expected);
+                }
+                hir::StatementKind::Expression { value, .. } => {
+                    self.expression(value, if tail { expected } else { None });
+                }
+                _ => {
+                    //
+                }
+            }
+        }
+    }
+
+    fn expression(&mut self, expression: &hir::Expression, expected: Option<Primitive>) {
+        match &expression.kind {
+            hir::ExpressionKind::Literal { value } => self.literal(value, expected, false),
+            hir::ExpressionKind::Unary { operator, operand } => match (operator, &operand.kind) {
+                (hir::UnaryOperator::Negate, hir::ExpressionKind::Literal { value }) => {
+                    self.literal(value, expected, true)
+                }
+                (hir::UnaryOperator::Negate | hir::UnaryOperator::Positive, _)
+                | (hir::UnaryOperator::BitwiseNot, _) => self.expression(operand, expected),
+                (hir::UnaryOperator::Not, _) => self.expression(operand, None),
+            },
+            hir::ExpressionKind::Binary {
+                left,
+                operator,
+                right,
+            } => {
+                let (left_expected, right_expected) = match operator {
+                    hir::BinaryOperator::LeftShift
+                    | hir::BinaryOperator::SaturatingLeftShift
+                    | hir::BinaryOperator::RightShift
+                    | hir::BinaryOperator::RightShiftUnsigned => (expected, None),
+                    hir::BinaryOperator::LogicalAnd
+                    | hir::BinaryOperator::LogicalOr
+                    | hir::BinaryOperator::Equal
+                    | hir::BinaryOperator::NotEqual
+                    | hir::BinaryOperator::Less
+                    | hir::BinaryOperator::LessOrEqual
+                    | hir::BinaryOperator::Greater
+                    | hir::BinaryOperator::GreaterOrEqual => (None, None),
+                    _ => (expected, expected),
+                };
+                self.expression(left, left_expected);
+                self.expression(right, right_expected);
+            }
+            hir::ExpressionKind::Return { value } => {
+                if let Some(value) = value {
+                    self.expression(value, self.return_type);
+                }
+            }
+            hir::ExpressionKind::Yield { value } => {
+                if let Some(value) = value {
+                    self.expression(value, None);
+                }
+            }
+            hir::ExpressionKind::Let {
+                r#type,
+                initializer,
+                ..
+            } => {
+                if let Some(initializer) = initializer {
+                    let expected = r#type.as_ref().and_then(|r#type| self.primitive(r#type));
+                    self.expression(initializer, expected);
+                }
+            }
+            hir::ExpressionKind::Block { statements, .. } => {
+                self.statements(statements, expected);
+            }
+            hir::ExpressionKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.expression(condition, None);
+                self.statements(then_branch, expected);
+                if let Some(else_branch) = else_branch {
+                    self.expression(else_branch, expected);
+                }
+            }
+            hir::ExpressionKind::Match { expression, arms } => {
+                self.expression(expression, None);
+                for (_, arm) in arms {
+                    self.expression(arm, expected);
+                }
+            }
+            hir::ExpressionKind::For { iterable, body, .. } => {
+                self.expression(iterable, None);
+                self.statements(body, None);
+            }
+            hir::ExpressionKind::While { condition, body } => {
+                self.expression(condition, None);
+                self.statements(body, None);
+            }
+            hir::ExpressionKind::Loop { body } => {
+                self.statements(body, None);
+            }
+            hir::ExpressionKind::Array { elements } | hir::ExpressionKind::Tuple { elements } => {
+                for element in elements {
+                    self.expression(element, None);
+                }
+            }
+            hir::ExpressionKind::Assignment { left, right, .. } => {
+                self.expression(left, None);
+                self.expression(right, None);
+            }
+            hir::ExpressionKind::FunctionCall {
+                callee, arguments, ..
+            } => {
+                self.expression(callee, None);
+                for argument in arguments {
+                    self.expression(argument, None);
+                }
+            }
+            hir::ExpressionKind::MethodCall {
+                receiver,
+                arguments,
+                ..
+            } => {
+                self.expression(receiver, None);
+                for argument in arguments {
+                    self.expression(argument, None);
+                }
+            }
+            hir::ExpressionKind::Index { receiver, index } => {
+                self.expression(receiver, None);
+                self.expression(index, None);
+            }
+            hir::ExpressionKind::Struct { fields, .. } => {
+                for (_, field) in fields {
+                    self.expression(field, None);
+                }
+            }
+            hir::ExpressionKind::Field { receiver, .. }
+            | hir::ExpressionKind::Await {
+                expression: receiver,
+            }
+            | hir::ExpressionKind::Try { value: receiver } => {
+                self.expression(receiver, None);
+            }
+            hir::ExpressionKind::Range { range } => match range {
+                hir::RangeExpression::Full => {
+                    //
+                }
+                hir::RangeExpression::To { end }
+                | hir::RangeExpression::ToInclusive { end }
+                | hir::RangeExpression::From { start: end } => self.expression(end, None),
+                hir::RangeExpression::FromTo { start, end }
+                | hir::RangeExpression::FromToInclusive { start, end } => {
+                    self.expression(start, None);
+                    self.expression(end, None);
+                }
+            },
+            hir::ExpressionKind::Break
+            | hir::ExpressionKind::Continue
+            | hir::ExpressionKind::Path { .. } => {
+                //
+            }
+        }
+    }
+
+    fn literal(&mut self, literal: &hir::Literal, expected: Option<Primitive>, negated: bool) {
+        let (expected, default, value) = match literal.kind {
+            hir::LiteralKind::Integer { value, suffix } => {
+                let expected = match suffix {
+                    Some(suffix) => Some(suffix.into()),
+                    None => expected.filter(|primitive| primitive.is_integer()),
+                };
+                (expected, Primitive::I32, Value::Integer { value, negated })
+            }
+            hir::LiteralKind::Float { value, suffix } => {
+                let expected = match suffix {
+                    Some(suffix) => Some(suffix.into()),
+                    None => expected.filter(|primitive| primitive.is_float()),
+                };
+                let value = if negated { -value } else { value };
+
+                (expected, Primitive::F64, Value::Float(value))
+            }
+            _ => return,
+        };
+
+        self.literals.push(Literal {
+            span: literal.span,
+            primitive: expected.unwrap_or(default),
+            value: expected.map(|_| value),
+        });
+    }
+
+    fn primitive(&self, r#type: &hir::TypeExpression) -> Option<Primitive> {
+        let hir::TypeExpressionKind::Path { path } = &r#type.kind else {
+            return None;
+        };
+        match self.env.resolve_path(self.module, &path.segments)? {
+            Resolved::Primitive(primitive) => Some(primitive),
+            _ => None,
+        }
+    }
 }
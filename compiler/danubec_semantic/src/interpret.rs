@@ -0,0 +1,1682 @@
+use crate::{
+    env::{Env, Resolved},
+    harness::{Expectation, Test, definition_path},
+    primitive::Primitive,
+};
+use danubec_hir as hir;
+use danubec_symbol::{DefinitionId, FileId, ScopeId, Symbol, SymbolInterner};
+use danubec_syntax::Span;
+use fxhash::FxHashMap;
+use std::{cmp::Ordering, fmt};
+
+/// How deep calls may nest before a test is stopped.
+const MAX_DEPTH: usize = 128;
+
+/// The stack each test runs on, as every call nests a few evaluations deep on it.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// How many expressions and loop iterations a test may evaluate before it is stopped, so that
+/// one that never ends does not hang the run.
+const MAX_STEPS: u64 = 10_000_000;
+
+/// The functions of `std` that the interpreter runs itself, as their bodies are empty.
+const INTRINSICS: [(&str, Intrinsic); 4] = [
+    ("std::io::print", Intrinsic::Print),
+    ("std::io::println", Intrinsic::Println),
+    ("std::panic::panic", Intrinsic::Panic),
+    ("std::panic::assert", Intrinsic::Assert),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Passed,
+    /// Why the test failed.
+    Failed(String),
+}
+
+/// Runs `test` by walking the HIR of its body, returning how it went and what it printed.
+///
+/// A test passes when it returns, or when it panics if it is `#[should_fail]`. Running into
+/// something the interpreter does not support fails it either way.
+pub fn run(env: &Env, symbols: &SymbolInterner, test: &Test) -> (Outcome, String) {
+    let mut interpreter = Interpreter::new(env, symbols);
+    let span = env[test.definition].definition.name.span;
+    let result = std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, || interpreter.call(test.definition, vec![], span))
+            .expect("the test thread starts")
+            .join()
+            .expect("the interpreter does not panic")
+    });
+
+    let outcome = match (&test.expectation, result) {
+        (Expectation::Pass, Ok(_)) => Outcome::Passed,
+        (Expectation::Fail(_), Ok(_)) => Outcome::Failed("the test did not panic".to_owned()),
+        (Expectation::Fail(None), Err(Error::Panic { .. })) => Outcome::Passed,
+        (Expectation::Fail(Some(expected)), Err(error @ Error::Panic { .. })) => match &error {
+            Error::Panic { message, .. } if message.contains(expected.as_str()) => Outcome::Passed,
+            _ => Outcome::Failed(format!(
+                "{}\nthe panic message does not contain `{}`",
+                error, expected
+            )),
+        },
+        (_, Err(error)) => Outcome::Failed(error.to_string()),
+    };
+
+    (outcome, interpreter.output)
+}
+
+#[derive(Debug)]
+enum Error {
+    /// `panic`, a failed `assert`, an overflow, a division by zero or an index out of bounds.
+    Panic {
+        message: String,
+        /// `path:line:column`
+        location: Option<String>,
+    },
+    /// Something the interpreter cannot run, either because it does not support it yet or
+    /// because the checks let through a program that makes no sense.
+    Unsupported(String),
+    /// The test nested calls too deeply or ran for too long.
+    Limit(String),
+}
+
+/// How evaluation leaves an expression other than with a value.
+#[derive(Debug)]
+enum Unwind {
+    Break,
+    Continue,
+    Return(Value),
+    Error(Error),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Intrinsic {
+    Print,
+    Println,
+    Panic,
+    Assert,
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Unit,
+    Bool(bool),
+    Char(char),
+    Integer(Integer),
+    Float(f64, Primitive),
+    String(String),
+    Tuple(Vec<Value>),
+    Array(Vec<Value>),
+    Function(DefinitionId),
+    /// `start..end`, `start..=end` or `start..`, which `for` loops iterate over.
+    Range {
+        start: Integer,
+        end: Option<Integer>,
+        inclusive: bool,
+    },
+}
+
+/// An integer of a primitive type, kept as a sign and a magnitude so that both `i128` and
+/// `u128` fit. Zero is never negative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Integer {
+    negative: bool,
+    magnitude: u128,
+    primitive: Primitive,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Arithmetic {
+    Add,
+    Subtract,
+    Multiply,
+    Exponent,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Overflow {
+    Panic,
+    Saturate,
+    Wrap,
+}
+
+/// A failure found while holding on to a local, reported once it is let go.
+enum Fault {
+    Panic(String, Span),
+    Unsupported(String),
+}
+
+struct Interpreter<'env> {
+    env: &'env Env,
+    symbols: &'env SymbolInterner,
+    intrinsics: FxHashMap<DefinitionId, Intrinsic>,
+    /// The file of the function being run, by which the types of its literals are keyed.
+    file: FileId,
+    /// The locals of the function being run, innermost block last.
+    frames: Vec<Vec<(Symbol, Value)>>,
+    depth: usize,
+    steps: u64,
+    output: String,
+}
+
+impl<'env> Interpreter<'env> {
+    fn new(env: &'env Env, symbols: &'env SymbolInterner) -> Self {
+        let intrinsics = env
+            .definitions()
+            .filter_map(|(definition, _)| {
+                let path = definition_path(env, symbols, definition)?;
+                let (_, intrinsic) = INTRINSICS.iter().find(|(name, _)| *name == path)?;
+
+                Some((definition, *intrinsic))
+            })
+            .collect();
+
+        Self {
+            env,
+            symbols,
+            intrinsics,
+            file: FileId::default(),
+            frames: vec![],
+            depth: 0,
+            steps: 0,
+            output: String::new(),
+        }
+    }
+
+    /// Calls `definition` with `arguments`, already coerced to its parameter types.
+    fn call(
+        &mut self,
+        definition: DefinitionId,
+        arguments: Vec<Value>,
+        span: Span,
+    ) -> Result<Value, Error> {
+        if let Some(&intrinsic) = self.intrinsics.get(&definition) {
+            return self.intrinsic(intrinsic, arguments, span);
+        }
+
+        let env = self.env;
+        let data = &env[definition];
+        let name = &self.symbols[data.definition.name.symbol];
+        let hir::DefinitionKind::Function {
+            parameters, body, ..
+        } = &data.definition.kind
+        else {
+            return Err(unsupported(format!("`{}` is not a function", name)));
+        };
+        let Some(body) = body else {
+            return Err(unsupported(format!("`{}` has no body", name)));
+        };
+        if parameters.len() != arguments.len() {
+            return Err(unsupported(format!(
+                "`{}` takes {} arguments, but {} were given",
+                name,
+                parameters.len(),
+                arguments.len()
+            )));
+        }
+        if self.depth == MAX_DEPTH {
+            return Err(Error::Limit(format!(
+                "calls nested more than {} deep",
+                MAX_DEPTH
+            )));
+        }
+
+        let file = std::mem::replace(&mut self.file, data.file);
+        let frames = std::mem::replace(&mut self.frames, vec![vec![]]);
+        self.depth += 1;
+        let result = parameters
+            .iter()
+            .zip(arguments)
+            .try_for_each(|(parameter, argument)| self.bind(&parameter.pattern, argument))
+            .and_then(|_| self.statements(body));
+        self.depth -= 1;
+        self.frames = frames;
+        self.file = file;
+
+        match result {
+            Ok(value) | Err(Unwind::Return(value)) => Ok(value),
+            Err(Unwind::Break) => Err(unsupported("`break` outside of a loop")),
+            Err(Unwind::Continue) => Err(unsupported("`continue` outside of a loop")),
+            Err(Unwind::Error(error)) => Err(error),
+        }
+    }
+
+    fn intrinsic(
+        &mut self,
+        intrinsic: Intrinsic,
+        arguments: Vec<Value>,
+        span: Span,
+    ) -> Result<Value, Error> {
+        match (intrinsic, &arguments[..]) {
+            (Intrinsic::Print, [Value::String(message)]) => self.output.push_str(message),
+            (Intrinsic::Println, [Value::String(message)]) => {
+                self.output.push_str(message);
+                self.output.push('\n');
+            }
+            (Intrinsic::Panic, [Value::String(message)]) => {
+                return Err(self.panic(message.clone(), span));
+            }
+            (Intrinsic::Assert, [Value::Bool(true)]) => {}
+            (Intrinsic::Assert, [Value::Bool(false)]) => {
+                let message = match self.text(span) {
+                    Some(text) => format!("assertion failed: {}", text),
+                    None => "assertion failed".to_owned(),
+                };
+                return Err(self.panic(message, span));
+            }
+            _ => {
+                return Err(unsupported(format!(
+                    "wrong arguments to `{:?}`: {:?}",
+                    intrinsic, arguments
+                )));
+            }
+        }
+
+        Ok(Value::Unit)
+    }
+
+    /// The value of the last expression statement. The HIR does not keep whether it ends
+    /// with `;`, so the value is kept either way, as literal inference does.
+    fn statements(&mut self, statements: &[hir::Statement]) -> Result<Value, Unwind> {
+        let mut value = Value::Unit;
+        for statement in statements {
+            value = match &statement.kind {
+                hir::StatementKind::Let {
+                    pattern,
+                    initializer,
+                    ..
+                } => {
+                    // Only assignments read a local that has not been initialized.
+                    let value = match initializer {
+                        Some(initializer) => self.expression(initializer)?,
+                        None => Value::Unit,
+                    };
+                    self.bind(pattern, value)?;
+
+                    Value::Unit
+                }
+                hir::StatementKind::Expression { value, .. } => self.expression(value)?,
+                hir::StatementKind::Definition { .. } | hir::StatementKind::Semicolon => {
+                    Value::Unit
+                }
+            };
+        }
+
+        Ok(value)
+    }
+
+    /// Runs `statements` in a block of their own.
+    fn block(&mut self, statements: &[hir::Statement]) -> Result<Value, Unwind> {
+        self.scoped(vec![], |this| this.statements(statements))
+    }
+
+    /// Runs the body of a loop, returning whether it breaks out.
+    fn iteration(&mut self, statements: &[hir::Statement]) -> Result<bool, Unwind> {
+        match self.statements(statements) {
+            Ok(_) | Err(Unwind::Continue) => Ok(false),
+            Err(Unwind::Break) => Ok(true),
+            Err(unwind) => Err(unwind),
+        }
+    }
+
+    fn scoped<T>(&mut self, bindings: Vec<(Symbol, Value)>, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.frames.push(bindings);
+        let result = f(self);
+        self.frames.pop();
+
+        result
+    }
+
+    /// Binds `value` to the irrefutable `pattern` in the innermost block.
+    fn bind(&mut self, pattern: &hir::Pattern, value: Value) -> Result<(), Unwind> {
+        let mut bindings = vec![];
+        if !self.pattern(pattern, &value, &mut bindings)? {
+            return Err(self
+                .panic(
+                    format!("`{}` does not match the pattern", value),
+                    pattern.span,
+                )
+                .into());
+        }
+        self.frames
+            .last_mut()
+            .expect("a function has a frame")
+            .extend(bindings);
+
+        Ok(())
+    }
+
+    fn expression(&mut self, expression: &hir::Expression) -> Result<Value, Unwind> {
+        self.step()?;
+
+        let span = expression.span;
+        match &expression.kind {
+            hir::ExpressionKind::Literal { value } => self.literal(value, false, None),
+            hir::ExpressionKind::Path { path } => self.path(path),
+            hir::ExpressionKind::Block { statements, .. } => self.block(statements),
+            hir::ExpressionKind::Tuple { elements } if elements.is_empty() => Ok(Value::Unit),
+            hir::ExpressionKind::Tuple { elements } => {
+                let elements = self.expressions(elements)?;
+                Ok(Value::Tuple(elements))
+            }
+            hir::ExpressionKind::Array { elements } => {
+                let elements = self.expressions(elements)?;
+                Ok(Value::Array(elements))
+            }
+            hir::ExpressionKind::Unary { operator, operand } => self.unary(operator, operand, span),
+            hir::ExpressionKind::Binary {
+                left,
+                operator,
+                right,
+            } => match operator {
+                hir::BinaryOperator::LogicalAnd => {
+                    let value = self.condition(left)? && self.condition(right)?;
+                    Ok(Value::Bool(value))
+                }
+                hir::BinaryOperator::LogicalOr => {
+                    let value = self.condition(left)? || self.condition(right)?;
+                    Ok(Value::Bool(value))
+                }
+                _ => {
+                    let lhs = self.expression(left)?;
+                    let rhs = self.expression(right)?;
+                    self.binary(operator, lhs, left, rhs, right, span)
+                }
+            },
+            hir::ExpressionKind::Assignment {
+                left,
+                operator,
+                right,
+            } => {
+                let value = self.expression(right)?;
+                self.assign(left, operator, value, right, span)?;
+
+                Ok(Value::Unit)
+            }
+            hir::ExpressionKind::Let {
+                pattern,
+                initializer,
+                ..
+            } => {
+                let Some(initializer) = initializer else {
+                    return Err(unsupported("`let` without a value").into());
+                };
+                let value = self.expression(initializer)?;
+                let mut bindings = vec![];
+                let matched = self.pattern(pattern, &value, &mut bindings)?;
+                if matched {
+                    self.frames
+                        .last_mut()
+                        .expect("a function has a frame")
+                        .extend(bindings);
+                }
+
+                Ok(Value::Bool(matched))
+            }
+            hir::ExpressionKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                // What `if let` binds is only visible in the first branch.
+                let value = self.scoped(vec![], |this| match this.condition(condition)? {
+                    true => this.statements(then_branch).map(Some),
+                    false => Ok(None),
+                })?;
+                match (value, else_branch) {
+                    (Some(value), _) => Ok(value),
+                    (None, Some(else_branch)) => self.expression(else_branch),
+                    (None, None) => Ok(Value::Unit),
+                }
+            }
+            hir::ExpressionKind::Match { expression, arms } => {
+                let value = self.expression(expression)?;
+                for (pattern, arm) in arms {
+                    let mut bindings = vec![];
+                    if self.pattern(pattern, &value, &mut bindings)? {
+                        return self.scoped(bindings, |this| this.expression(arm));
+                    }
+                }
+
+                Err(self
+                    .panic(format!("no arm matches `{}`", value), span)
+                    .into())
+            }
+            hir::ExpressionKind::Loop { body } => loop {
+                self.step()?;
+                if self.scoped(vec![], |this| this.iteration(body))? {
+                    break Ok(Value::Unit);
+                }
+            },
+            hir::ExpressionKind::While { condition, body } => loop {
+                self.step()?;
+                let done = self.scoped(vec![], |this| match this.condition(condition)? {
+                    true => this.iteration(body),
+                    false => Ok(true),
+                })?;
+                if done {
+                    break Ok(Value::Unit);
+                }
+            },
+            hir::ExpressionKind::For {
+                pattern,
+                iterable,
+                body,
+            } => {
+                let iterable = self.expression(iterable)?;
+                self.for_each(pattern, iterable, body, span)?;
+
+                Ok(Value::Unit)
+            }
+            hir::ExpressionKind::Break => Err(Unwind::Break),
+            hir::ExpressionKind::Continue => Err(Unwind::Continue),
+            hir::ExpressionKind::Return { value } => {
+                let value = match value {
+                    Some(value) => self.expression(value)?,
+                    None => Value::Unit,
+                };
+
+                Err(Unwind::Return(value))
+            }
+            hir::ExpressionKind::FunctionCall {
+                callee, arguments, ..
+            } => {
+                let definition = match self.expression(callee)? {
+                    Value::Function(definition) => definition,
+                    value => {
+                        return Err(unsupported(format!("`{}` is not a function", value)).into());
+                    }
+                };
+                let mut values = self.expressions(arguments)?;
+                if let hir::DefinitionKind::Function { parameters, .. } =
+                    &self.env[definition].definition.kind
+                {
+                    let scope = self.env[definition].scope;
+                    for ((parameter, value), argument) in
+                        parameters.iter().zip(&mut values).zip(arguments)
+                    {
+                        let expected = self.primitive(&parameter.r#type, scope);
+                        let coerced = self.coerce(value.clone(), expected, argument)?;
+                        *value = coerced;
+                    }
+                }
+
+                Ok(self.call(definition, values, span)?)
+            }
+            hir::ExpressionKind::Index { receiver, index } => {
+                let receiver = self.expression(receiver)?;
+                let index = self.expression(index)?;
+                match receiver {
+                    Value::Array(elements) => {
+                        let index = self.index(&index, elements.len(), span)?;
+                        Ok(elements[index].clone())
+                    }
+                    value => Err(unsupported(format!("cannot index into `{}`", value)).into()),
+                }
+            }
+            hir::ExpressionKind::Field { receiver, field } => {
+                let receiver = self.expression(receiver)?;
+                let name = &self.symbols[field.symbol];
+                match (&receiver, name.parse::<usize>()) {
+                    (Value::Tuple(elements), Ok(index)) if index < elements.len() => {
+                        Ok(elements[index].clone())
+                    }
+                    _ => Err(unsupported(format!("`{}` has no field `{}`", receiver, name)).into()),
+                }
+            }
+            hir::ExpressionKind::Range { range } => self.range(range),
+            hir::ExpressionKind::MethodCall { .. } => {
+                Err(unsupported("method calls are not supported").into())
+            }
+            hir::ExpressionKind::Struct { .. } => {
+                Err(unsupported("struct expressions are not supported").into())
+            }
+            hir::ExpressionKind::Await { .. } => {
+                Err(unsupported("`.await` is not supported").into())
+            }
+            hir::ExpressionKind::Try { .. } => Err(unsupported("`?` is not supported").into()),
+            hir::ExpressionKind::Yield { .. } => {
+                Err(unsupported("`yield` is not supported").into())
+            }
+        }
+    }
+
+    fn expressions(&mut self, expressions: &[hir::Expression]) -> Result<Vec<Value>, Unwind> {
+        expressions
+            .iter()
+            .map(|expression| self.expression(expression))
+            .collect()
+    }
+
+    fn condition(&mut self, expression: &hir::Expression) -> Result<bool, Unwind> {
+        match self.expression(expression)? {
+            Value::Bool(value) => Ok(value),
+            value => Err(unsupported(format!("expected `bool`, found `{}`", value)).into()),
+        }
+    }
+
+    /// A literal, typed as inference typed it, else as its suffix or `default` says, else as
+    /// `i32` or `f64`.
+    fn literal(
+        &mut self,
+        literal: &hir::Literal,
+        negated: bool,
+        default: Option<Primitive>,
+    ) -> Result<Value, Unwind> {
+        let inferred = self.env.literal_type(self.file, literal.span);
+        match &literal.kind {
+            hir::LiteralKind::Boolean { value } => Ok(Value::Bool(*value)),
+            hir::LiteralKind::Character { value } => Ok(Value::Char(*value)),
+            hir::LiteralKind::Byte { value } => Ok(Value::Integer(Integer::from_u128(
+                Primitive::U8,
+                *value as u128,
+            ))),
+            hir::LiteralKind::ByteString { value } => Ok(Value::Array(
+                value
+                    .iter()
+                    .map(|byte| Value::Integer(Integer::from_u128(Primitive::U8, *byte as u128)))
+                    .collect(),
+            )),
+            hir::LiteralKind::Integer { value, suffix } => {
+                let primitive = inferred
+                    .or(suffix.map(Primitive::from))
+                    .or(default)
+                    .unwrap_or(Primitive::I32);
+                if primitive.is_float() {
+                    let value = *value as f64;
+                    return Ok(float(if negated { -value } else { value }, primitive));
+                }
+
+                match Integer::new(primitive, negated, *value) {
+                    Some(integer) => Ok(Value::Integer(integer)),
+                    None => Err(unsupported(format!(
+                        "literal `{}{}` out of range for `{}`",
+                        if negated { "-" } else { "" },
+                        value,
+                        primitive.name()
+                    ))
+                    .into()),
+                }
+            }
+            hir::LiteralKind::Float { value, suffix } => {
+                let primitive = inferred
+                    .or(suffix.map(Primitive::from))
+                    .filter(|primitive| primitive.is_float())
+                    .unwrap_or(Primitive::F64);
+
+                Ok(float(if negated { -value } else { *value }, primitive))
+            }
+            hir::LiteralKind::String { segments } => {
+                let mut string = String::new();
+                for segment in segments {
+                    match segment {
+                        hir::StringSegment::Text { value } => string.push_str(value),
+                        hir::StringSegment::Unicode { value }
+                        | hir::StringSegment::Escape { value } => string.push(*value),
+                        hir::StringSegment::Interpolation { expression } => {
+                            let value = self.expression(expression)?;
+                            string.push_str(&value.to_string());
+                        }
+                    }
+                }
+
+                Ok(Value::String(string))
+            }
+            hir::LiteralKind::CString { .. } => {
+                Err(unsupported("C string literals are not supported").into())
+            }
+        }
+    }
+
+    fn path(&mut self, path: &hir::Path) -> Result<Value, Unwind> {
+        match &path.binding {
+            hir::Binding::Definition(definition) => self.definition(*definition),
+            hir::Binding::Unresolved | hir::Binding::Local(_) => {
+                let local = local(path).and_then(|symbol| {
+                    self.frames
+                        .iter()
+                        .rev()
+                        .flat_map(|frame| frame.iter().rev())
+                        .find(|(name, _)| *name == symbol)
+                });
+                match local {
+                    Some((_, value)) => Ok(value.clone()),
+                    None => Err(unsupported("a path that names nothing").into()),
+                }
+            }
+        }
+    }
+
+    /// The value of a path to `definition`: a function, or what a constant or static holds.
+    fn definition(&mut self, definition: DefinitionId) -> Result<Value, Unwind> {
+        let env = self.env;
+        let data = &env[definition];
+        let (r#type, initializer) = match &data.definition.kind {
+            hir::DefinitionKind::Function { .. } => return Ok(Value::Function(definition)),
+            hir::DefinitionKind::Constant {
+                r#type,
+                initializer: Some(initializer),
+            } => (r#type.as_ref(), initializer),
+            hir::DefinitionKind::Static {
+                r#type,
+                initializer,
+            } => (Some(r#type), initializer),
+            _ => {
+                let name = &self.symbols[data.definition.name.symbol];
+                return Err(unsupported(format!("`{}` is not a value", name)).into());
+            }
+        };
+
+        // Constants and statics see no locals, and type their literals by their own file.
+        let file = std::mem::replace(&mut self.file, data.file);
+        let frames = std::mem::replace(&mut self.frames, vec![vec![]]);
+        let value = self.expression(initializer).and_then(|value| {
+            let expected = r#type.and_then(|r#type| self.primitive(r#type, data.scope));
+            self.coerce(value, expected, initializer)
+        });
+        self.frames = frames;
+        self.file = file;
+
+        value
+    }
+
+    fn unary(
+        &mut self,
+        operator: &hir::UnaryOperator,
+        operand: &hir::Expression,
+        span: Span,
+    ) -> Result<Value, Unwind> {
+        // `-128i8` is one literal, as `128i8` alone does not fit.
+        if let (hir::UnaryOperator::Negate, hir::ExpressionKind::Literal { value }) =
+            (operator, &operand.kind)
+        {
+            return self.literal(value, true, None);
+        }
+
+        let value = self.expression(operand)?;
+        match (operator, value) {
+            (hir::UnaryOperator::Positive, value @ (Value::Integer(_) | Value::Float(..))) => {
+                Ok(value)
+            }
+            (hir::UnaryOperator::Negate, Value::Integer(integer)) => {
+                match Integer::new(integer.primitive, !integer.negative, integer.magnitude) {
+                    Some(integer) => Ok(Value::Integer(integer)),
+                    None => Err(self.panic("attempt to negate with overflow", span).into()),
+                }
+            }
+            (hir::UnaryOperator::Negate, Value::Float(value, primitive)) => {
+                Ok(Value::Float(-value, primitive))
+            }
+            (hir::UnaryOperator::Not, Value::Bool(value)) => Ok(Value::Bool(!value)),
+            (hir::UnaryOperator::BitwiseNot, Value::Integer(integer)) => Ok(Value::Integer(
+                Integer::from_bits(integer.primitive, !integer.bits()),
+            )),
+            (operator, value) => {
+                Err(unsupported(format!("cannot apply `{:?}` to `{}`", operator, value)).into())
+            }
+        }
+    }
+
+    fn binary(
+        &mut self,
+        operator: &hir::BinaryOperator,
+        lhs: Value,
+        left: &hir::Expression,
+        rhs: Value,
+        right: &hir::Expression,
+        span: Span,
+    ) -> Result<Value, Unwind> {
+        use hir::BinaryOperator as Op;
+
+        // The amount of a shift has a type of its own.
+        let shift = matches!(
+            operator,
+            Op::LeftShift | Op::SaturatingLeftShift | Op::RightShift | Op::RightShiftUnsigned
+        );
+        let (lhs, rhs) = match shift {
+            true => (lhs, rhs),
+            false => self.unify(lhs, left, rhs, right)?,
+        };
+
+        let value = match (operator, lhs, rhs) {
+            (Op::Equal, lhs, rhs) => Value::Bool(compare(&lhs, &rhs)? == Some(Ordering::Equal)),
+            (Op::NotEqual, lhs, rhs) => Value::Bool(compare(&lhs, &rhs)? != Some(Ordering::Equal)),
+            (Op::Less, lhs, rhs) => Value::Bool(compare(&lhs, &rhs)? == Some(Ordering::Less)),
+            (Op::LessOrEqual, lhs, rhs) => Value::Bool(matches!(
+                compare(&lhs, &rhs)?,
+                Some(Ordering::Less | Ordering::Equal)
+            )),
+            (Op::Greater, lhs, rhs) => Value::Bool(compare(&lhs, &rhs)? == Some(Ordering::Greater)),
+            (Op::GreaterOrEqual, lhs, rhs) => Value::Bool(matches!(
+                compare(&lhs, &rhs)?,
+                Some(Ordering::Greater | Ordering::Equal)
+            )),
+            (Op::BitwiseAnd, Value::Bool(lhs), Value::Bool(rhs)) => Value::Bool(lhs & rhs),
+            (Op::BitwiseOr, Value::Bool(lhs), Value::Bool(rhs)) => Value::Bool(lhs | rhs),
+            (Op::BitwiseXor, Value::Bool(lhs), Value::Bool(rhs)) => Value::Bool(lhs ^ rhs),
+            (operator, Value::Integer(lhs), Value::Integer(rhs)) => {
+                Value::Integer(self.integer(operator, lhs, rhs, span)?)
+            }
+            (operator, Value::Float(lhs, primitive), Value::Float(rhs, _)) => {
+                let value = match operator {
+                    Op::Add => lhs + rhs,
+                    Op::Subtract => lhs - rhs,
+                    Op::Multiply => lhs * rhs,
+                    Op::Divide => lhs / rhs,
+                    Op::Remainder => lhs % rhs,
+                    Op::Exponent => lhs.powf(rhs),
+                    operator => {
+                        return Err(unsupported(format!(
+                            "cannot apply `{:?}` to floats",
+                            operator
+                        ))
+                        .into());
+                    }
+                };
+
+                float(value, primitive)
+            }
+            (operator, lhs, rhs) => {
+                return Err(unsupported(format!(
+                    "cannot apply `{:?}` to `{}` and `{}`",
+                    operator, lhs, rhs
+                ))
+                .into());
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn integer(
+        &self,
+        operator: &hir::BinaryOperator,
+        lhs: Integer,
+        rhs: Integer,
+        span: Span,
+    ) -> Result<Integer, Unwind> {
+        use hir::BinaryOperator as Op;
+
+        let primitive = lhs.primitive;
+        let arithmetic =
+            |arithmetic, overflow| self.arithmetic(arithmetic, overflow, lhs, rhs, span);
+        match operator {
+            Op::Add => arithmetic(Arithmetic::Add, Overflow::Panic),
+            Op::SaturatingAdd => arithmetic(Arithmetic::Add, Overflow::Saturate),
+            Op::WrappingAdd => arithmetic(Arithmetic::Add, Overflow::Wrap),
+            Op::Subtract => arithmetic(Arithmetic::Subtract, Overflow::Panic),
+            Op::SaturatingSubtract => arithmetic(Arithmetic::Subtract, Overflow::Saturate),
+            Op::WrappingSubtract => arithmetic(Arithmetic::Subtract, Overflow::Wrap),
+            Op::Multiply => arithmetic(Arithmetic::Multiply, Overflow::Panic),
+            Op::SaturatingMultiply => arithmetic(Arithmetic::Multiply, Overflow::Saturate),
+            Op::WrappingMultiply => arithmetic(Arithmetic::Multiply, Overflow::Wrap),
+            Op::Exponent => arithmetic(Arithmetic::Exponent, Overflow::Panic),
+            Op::SaturatingExponent => arithmetic(Arithmetic::Exponent, Overflow::Saturate),
+            Op::WrappingExponent => arithmetic(Arithmetic::Exponent, Overflow::Wrap),
+            Op::Divide | Op::Remainder if rhs.magnitude == 0 => {
+                let message = match operator {
+                    Op::Divide => "attempt to divide by zero",
+                    _ => "attempt to calculate the remainder with a divisor of zero",
+                };
+                Err(self.panic(message, span).into())
+            }
+            Op::Divide => Integer::new(
+                primitive,
+                lhs.negative != rhs.negative,
+                lhs.magnitude / rhs.magnitude,
+            )
+            .ok_or_else(|| self.panic("attempt to divide with overflow", span).into()),
+            Op::Remainder => {
+                Ok(
+                    Integer::new(primitive, lhs.negative, lhs.magnitude % rhs.magnitude)
+                        .expect("a remainder is smaller than the dividend"),
+                )
+            }
+            Op::BitwiseAnd => Ok(Integer::from_bits(primitive, lhs.bits() & rhs.bits())),
+            Op::BitwiseOr => Ok(Integer::from_bits(primitive, lhs.bits() | rhs.bits())),
+            Op::BitwiseXor => Ok(Integer::from_bits(primitive, lhs.bits() ^ rhs.bits())),
+            Op::LeftShift | Op::SaturatingLeftShift | Op::RightShift | Op::RightShiftUnsigned => {
+                let width = lhs.width();
+                if rhs.negative || rhs.magnitude >= width as u128 {
+                    let direction = match operator {
+                        Op::LeftShift | Op::SaturatingLeftShift => "left",
+                        _ => "right",
+                    };
+                    let message = format!("attempt to shift {} with overflow", direction);
+                    return Err(self.panic(message, span).into());
+                }
+
+                let amount = rhs.magnitude as u32;
+                let value = match operator {
+                    Op::LeftShift => Integer::from_bits(primitive, lhs.bits() << amount),
+                    Op::SaturatingLeftShift => {
+                        let factor = Integer::from_u128(Primitive::U128, 1 << amount);
+                        return self.arithmetic(
+                            Arithmetic::Multiply,
+                            Overflow::Saturate,
+                            lhs,
+                            factor,
+                            span,
+                        );
+                    }
+                    Op::RightShift if primitive.r#type().signed => {
+                        Integer::from_bits(primitive, ((lhs.bits() as i128) >> amount) as u128)
+                    }
+                    Op::RightShift => Integer::from_bits(primitive, lhs.magnitude >> amount),
+                    _ => Integer::from_bits(primitive, (lhs.bits() & mask(width)) >> amount),
+                };
+
+                Ok(value)
+            }
+            Op::LogicalAnd
+            | Op::LogicalOr
+            | Op::Equal
+            | Op::NotEqual
+            | Op::Less
+            | Op::LessOrEqual
+            | Op::Greater
+            | Op::GreaterOrEqual => {
+                Err(unsupported(format!("cannot apply `{:?}` to integers", operator)).into())
+            }
+        }
+    }
+
+    /// `lhs` and `rhs` combined exactly, then panicking, saturating or wrapping when the result
+    /// does not fit in the type of `lhs`.
+    fn arithmetic(
+        &self,
+        arithmetic: Arithmetic,
+        overflow: Overflow,
+        lhs: Integer,
+        rhs: Integer,
+        span: Span,
+    ) -> Result<Integer, Unwind> {
+        let primitive = lhs.primitive;
+        if let Arithmetic::Exponent = arithmetic
+            && rhs.negative
+        {
+            return Err(self
+                .panic("attempt to raise to a negative power", span)
+                .into());
+        }
+
+        if let Overflow::Wrap = overflow {
+            let bits = match arithmetic {
+                Arithmetic::Add => lhs.bits().wrapping_add(rhs.bits()),
+                Arithmetic::Subtract => lhs.bits().wrapping_sub(rhs.bits()),
+                Arithmetic::Multiply => lhs.bits().wrapping_mul(rhs.bits()),
+                Arithmetic::Exponent => wrapping_pow(lhs.bits(), rhs.magnitude),
+            };
+            return Ok(Integer::from_bits(primitive, bits));
+        }
+
+        // The sign of the exact result, and its magnitude unless it does not fit in `u128`.
+        let (negative, magnitude) = match arithmetic {
+            Arithmetic::Add => add(lhs.negative, lhs.magnitude, rhs.negative, rhs.magnitude),
+            Arithmetic::Subtract => add(
+                lhs.negative,
+                lhs.magnitude,
+                !rhs.negative && rhs.magnitude != 0,
+                rhs.magnitude,
+            ),
+            Arithmetic::Multiply => (
+                lhs.negative != rhs.negative,
+                lhs.magnitude.checked_mul(rhs.magnitude),
+            ),
+            Arithmetic::Exponent => {
+                let odd = rhs.magnitude % 2 == 1;
+                let magnitude = match (lhs.magnitude, u32::try_from(rhs.magnitude)) {
+                    (0 | 1, _) if rhs.magnitude != 0 => Some(lhs.magnitude),
+                    (magnitude, Ok(exponent)) => magnitude.checked_pow(exponent),
+                    (_, Err(_)) => None,
+                };
+                (lhs.negative && odd, magnitude)
+            }
+        };
+
+        if let Some(integer) =
+            magnitude.and_then(|magnitude| Integer::new(primitive, negative, magnitude))
+        {
+            return Ok(integer);
+        }
+        match overflow {
+            Overflow::Saturate => {
+                let (min, max) = primitive.range().expect("an integer type has a range");
+                Ok(match negative {
+                    true => Integer::new(primitive, true, min.unsigned_abs())
+                        .expect("the minimum is in range"),
+                    false => Integer::from_u128(primitive, max),
+                })
+            }
+            _ => {
+                let verb = match arithmetic {
+                    Arithmetic::Add => "add",
+                    Arithmetic::Subtract => "subtract",
+                    Arithmetic::Multiply => "multiply",
+                    Arithmetic::Exponent => "exponentiate",
+                };
+                Err(self
+                    .panic(format!("attempt to {} with overflow", verb), span)
+                    .into())
+            }
+        }
+    }
+
+    /// Gives an integer or float literal without a suffix the type of the other operand,
+    /// as it was typed `i32` or `f64` for want of context.
+    fn unify(
+        &self,
+        lhs: Value,
+        left: &hir::Expression,
+        rhs: Value,
+        right: &hir::Expression,
+    ) -> Result<(Value, Value), Unwind> {
+        let (Some(lhs_primitive), Some(rhs_primitive)) = (lhs.primitive(), rhs.primitive()) else {
+            return Ok((lhs, rhs));
+        };
+        if lhs_primitive == rhs_primitive || lhs_primitive.is_float() != rhs_primitive.is_float() {
+            return Ok((lhs, rhs));
+        }
+
+        match unsuffixed(left) {
+            true => Ok((self.coerce(lhs, Some(rhs_primitive), left)?, rhs)),
+            false => Ok((lhs, self.coerce(rhs, Some(lhs_primitive), right)?)),
+        }
+    }
+
+    /// `value`, converted to `expected` if it is a literal without a suffix of another type.
+    fn coerce(
+        &self,
+        value: Value,
+        expected: Option<Primitive>,
+        expression: &hir::Expression,
+    ) -> Result<Value, Unwind> {
+        let Some(expected) = expected else {
+            return Ok(value);
+        };
+        match value {
+            Value::Integer(integer) if integer.primitive != expected && expected.is_integer() => {
+                if !unsuffixed(expression) {
+                    return Err(mismatch(expected, integer.primitive).into());
+                }
+                match Integer::new(expected, integer.negative, integer.magnitude) {
+                    Some(integer) => Ok(Value::Integer(integer)),
+                    None => Err(unsupported(format!(
+                        "literal `{}` out of range for `{}`",
+                        integer,
+                        expected.name()
+                    ))
+                    .into()),
+                }
+            }
+            Value::Float(value, primitive) if primitive != expected && expected.is_float() => {
+                if !unsuffixed(expression) {
+                    return Err(mismatch(expected, primitive).into());
+                }
+                Ok(float(value, expected))
+            }
+            value => Ok(value),
+        }
+    }
+
+    fn assign(
+        &mut self,
+        left: &hir::Expression,
+        operator: &hir::AssignmentOperator,
+        value: Value,
+        right: &hir::Expression,
+        span: Span,
+    ) -> Result<(), Unwind> {
+        let (symbol, indices) = self.place(left)?;
+        let current = match slot(&mut self.frames, symbol, &indices) {
+            Ok(slot) => slot.clone(),
+            Err(fault) => return Err(self.fault(fault)),
+        };
+        let value = match binary_operator(operator) {
+            None => match &current {
+                // A local declared without a value takes any.
+                Value::Unit => value,
+                current => self.coerce(value, current.primitive(), right)?,
+            },
+            Some(operator) => {
+                let value = self.coerce(value, current.primitive(), right)?;
+                match operator {
+                    hir::BinaryOperator::LogicalAnd | hir::BinaryOperator::LogicalOr => {
+                        match (current, value) {
+                            (Value::Bool(lhs), Value::Bool(rhs)) => match operator {
+                                hir::BinaryOperator::LogicalAnd => Value::Bool(lhs && rhs),
+                                _ => Value::Bool(lhs || rhs),
+                            },
+                            (lhs, rhs) => {
+                                return Err(unsupported(format!(
+                                    "cannot apply `{:?}` to `{}` and `{}`",
+                                    operator, lhs, rhs
+                                ))
+                                .into());
+                            }
+                        }
+                    }
+                    operator => self.binary(&operator, current, left, value, right, span)?,
+                }
+            }
+        };
+
+        match slot(&mut self.frames, symbol, &indices) {
+            Ok(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            Err(fault) => Err(self.fault(fault)),
+        }
+    }
+
+    /// The local that `expression` assigns to, and the indices into it.
+    fn place(
+        &mut self,
+        expression: &hir::Expression,
+    ) -> Result<(Symbol, Vec<(Value, Span)>), Unwind> {
+        match &expression.kind {
+            hir::ExpressionKind::Path { path }
+                if !matches!(path.binding, hir::Binding::Definition(_)) =>
+            {
+                match local(path) {
+                    Some(symbol) => Ok((symbol, vec![])),
+                    None => Err(unsupported("a path that names nothing").into()),
+                }
+            }
+            hir::ExpressionKind::Index { receiver, index } => {
+                let (symbol, mut indices) = self.place(receiver)?;
+                let index = self.expression(index)?;
+                indices.push((index, expression.span));
+
+                Ok((symbol, indices))
+            }
+            _ => Err(unsupported("only locals and their elements can be assigned to").into()),
+        }
+    }
+
+    fn index(&self, index: &Value, len: usize, span: Span) -> Result<usize, Unwind> {
+        checked_index(index, len, span).map_err(|fault| self.fault(fault))
+    }
+
+    fn range(&mut self, range: &hir::RangeExpression) -> Result<Value, Unwind> {
+        let (start, end, inclusive) = match range {
+            hir::RangeExpression::FromTo { start, end } => (start, Some(end), false),
+            hir::RangeExpression::FromToInclusive { start, end } => (start, Some(end), true),
+            hir::RangeExpression::From { start } => (start, None, false),
+            _ => return Err(unsupported("ranges without a start are not supported").into()),
+        };
+
+        let lhs = self.expression(start)?;
+        let (lhs, rhs) = match end {
+            Some(end) => {
+                let rhs = self.expression(end)?;
+                let (lhs, rhs) = self.unify(lhs, start, rhs, end)?;
+                (lhs, Some(rhs))
+            }
+            None => (lhs, None),
+        };
+        match (lhs, rhs) {
+            (Value::Integer(start), None) => Ok(Value::Range {
+                start,
+                end: None,
+                inclusive,
+            }),
+            (Value::Integer(start), Some(Value::Integer(end))) => Ok(Value::Range {
+                start,
+                end: Some(end),
+                inclusive,
+            }),
+            _ => Err(unsupported("only ranges of integers are supported").into()),
+        }
+    }
+
+    fn for_each(
+        &mut self,
+        pattern: &hir::Pattern,
+        iterable: Value,
+        body: &[hir::Statement],
+        span: Span,
+    ) -> Result<(), Unwind> {
+        let mut iteration = |this: &mut Self, value: Value| -> Result<bool, Unwind> {
+            this.step()?;
+            let mut bindings = vec![];
+            if !this.pattern(pattern, &value, &mut bindings)? {
+                let message = format!("`{}` does not match the pattern", value);
+                return Err(this.panic(message, pattern.span).into());
+            }
+
+            this.scoped(bindings, |this| this.iteration(body))
+        };
+
+        match iterable {
+            Value::Array(elements) => {
+                for element in elements {
+                    if iteration(self, element)? {
+                        break;
+                    }
+                }
+            }
+            Value::String(string) => {
+                for char in string.chars() {
+                    if iteration(self, Value::Char(char))? {
+                        break;
+                    }
+                }
+            }
+            Value::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let mut current = start;
+                loop {
+                    if let Some(end) = end {
+                        match current.cmp(&end) {
+                            Ordering::Less => {
+                                //
+                            }
+                            Ordering::Equal if inclusive => {
+                                //
+                            }
+                            _ => break,
+                        }
+                    }
+                    if iteration(self, Value::Integer(current))? {
+                        break;
+                    }
+                    if end.is_some_and(|end| current == end) {
+                        break;
+                    }
+
+                    current = match current.successor() {
+                        Some(next) => next,
+                        None => {
+                            return Err(self.panic("attempt to add with overflow", span).into());
+                        }
+                    };
+                }
+            }
+            value => return Err(unsupported(format!("cannot iterate over `{}`", value)).into()),
+        }
+
+        Ok(())
+    }
+
+    /// Whether `value` matches `pattern`, collecting what it binds.
+    fn pattern(
+        &mut self,
+        pattern: &hir::Pattern,
+        value: &Value,
+        bindings: &mut Vec<(Symbol, Value)>,
+    ) -> Result<bool, Unwind> {
+        match &pattern.kind {
+            hir::PatternKind::Never => Ok(false),
+            hir::PatternKind::Placeholder => Ok(true),
+            hir::PatternKind::Path { path } => match path.binding {
+                hir::Binding::Definition(definition) => {
+                    let expected = self.definition(definition)?;
+                    Ok(compare(&expected, value)? == Some(Ordering::Equal))
+                }
+                _ => match local(path) {
+                    Some(symbol) => {
+                        bindings.push((symbol, value.clone()));
+                        Ok(true)
+                    }
+                    None => Err(unsupported("a path that names nothing").into()),
+                },
+            },
+            hir::PatternKind::Literal { value: literal } => {
+                let expected = self.literal(literal, false, value.primitive())?;
+                Ok(compare(&expected, value)? == Some(Ordering::Equal))
+            }
+            hir::PatternKind::Tuple { elements } | hir::PatternKind::Array { elements } => {
+                let values = match (&pattern.kind, value) {
+                    (hir::PatternKind::Tuple { .. }, Value::Tuple(values))
+                    | (hir::PatternKind::Array { .. }, Value::Array(values)) => values,
+                    _ => {
+                        return Err(
+                            unsupported(format!("`{}` cannot match the pattern", value)).into()
+                        );
+                    }
+                };
+                if elements.len() != values.len() {
+                    return Ok(false);
+                }
+                for (element, value) in elements.iter().zip(values) {
+                    if !self.pattern(element, value, bindings)? {
+                        return Ok(false);
+                    }
+                }
+
+                Ok(true)
+            }
+            hir::PatternKind::At { name, pattern } => {
+                if !self.pattern(pattern, value, bindings)? {
+                    return Ok(false);
+                }
+                bindings.push((name.symbol, value.clone()));
+
+                Ok(true)
+            }
+            hir::PatternKind::Or { patterns } => {
+                for pattern in patterns {
+                    let mut alternative = vec![];
+                    if self.pattern(pattern, value, &mut alternative)? {
+                        bindings.extend(alternative);
+                        return Ok(true);
+                    }
+                }
+
+                Ok(false)
+            }
+            hir::PatternKind::Range { .. } | hir::PatternKind::Rest { .. } => {
+                Err(unsupported("range and rest patterns are not supported").into())
+            }
+            hir::PatternKind::Named { .. } | hir::PatternKind::Unnamed { .. } => {
+                Err(unsupported("struct and enum patterns are not supported").into())
+            }
+        }
+    }
+
+    fn primitive(&self, r#type: &hir::TypeExpression, scope: ScopeId) -> Option<Primitive> {
+        let hir::TypeExpressionKind::Path { path } = &r#type.kind else {
+            return None;
+        };
+        match self.env.resolve_path_in(scope, &path.segments)? {
+            Resolved::Primitive(primitive) => Some(primitive),
+            _ => None,
+        }
+    }
+
+    fn step(&mut self) -> Result<(), Unwind> {
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return Err(Error::Limit(format!(
+                "the test did not finish within {} steps",
+                MAX_STEPS
+            ))
+            .into());
+        }
+
+        Ok(())
+    }
+
+    fn panic(&self, message: impl Into<String>, span: Span) -> Error {
+        Error::Panic {
+            message: message.into(),
+            location: self.location(span),
+        }
+    }
+
+    fn fault(&self, fault: Fault) -> Unwind {
+        match fault {
+            Fault::Panic(message, span) => self.panic(message, span).into(),
+            Fault::Unsupported(message) => unsupported(message).into(),
+        }
+    }
+
+    /// `path:line:column` of `span` in the file being run.
+    fn location(&self, span: Span) -> Option<String> {
+        let source = self.env.source_code(self.file)?;
+        let offset = usize::from(span.text_range().start());
+        let before = source.inner().get(..offset)?;
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+        Some(format!("{}:{}:{}", source.name(), line, column))
+    }
+
+    /// The text of the arguments of the call at `span`, for `assert` to report.
+    fn text(&self, span: Span) -> Option<String> {
+        let source = self.env.source_code(self.file)?;
+        let range = span.text_range();
+        let text = source
+            .inner()
+            .get(usize::from(range.start())..usize::from(range.end()))?;
+        let arguments = text.trim_end().strip_suffix(')')?;
+        let (_, arguments) = arguments.split_once('(')?;
+
+        Some(arguments.trim().to_owned())
+    }
+}
+
+impl Integer {
+    /// `-magnitude` if `negative`, if it fits in `primitive`.
+    fn new(primitive: Primitive, negative: bool, magnitude: u128) -> Option<Self> {
+        if !primitive.contains(magnitude, negative) {
+            return None;
+        }
+
+        Some(Self {
+            negative: negative && magnitude != 0,
+            magnitude,
+            primitive,
+        })
+    }
+
+    /// A non-negative `value` that is known to fit.
+    fn from_u128(primitive: Primitive, value: u128) -> Self {
+        Self::new(primitive, false, value).expect("the value is in range")
+    }
+
+    /// Reads the low bits of `bits` as two's complement when `primitive` is signed.
+    fn from_bits(primitive: Primitive, bits: u128) -> Self {
+        let width = primitive.r#type().size.expect("an integer type has a size") * 8;
+        let bits = bits & mask(width);
+        let sign = 1 << (width - 1);
+        if primitive.r#type().signed && bits & sign != 0 {
+            let magnitude = (!bits & mask(width)).wrapping_add(1) & mask(width);
+            // The minimum is its own two's complement.
+            let magnitude = if magnitude == 0 { sign } else { magnitude };
+            return Self::new(primitive, true, magnitude).expect("the value is in range");
+        }
+
+        Self::from_u128(primitive, bits)
+    }
+
+    /// `self + 1`, if it fits.
+    fn successor(self) -> Option<Self> {
+        let (negative, magnitude) = add(self.negative, self.magnitude, false, 1);
+        Self::new(self.primitive, negative, magnitude?)
+    }
+
+    /// The value in two's complement, over all 128 bits.
+    fn bits(self) -> u128 {
+        match self.negative {
+            true => self.magnitude.wrapping_neg(),
+            false => self.magnitude,
+        }
+    }
+
+    fn width(self) -> u32 {
+        self.primitive
+            .r#type()
+            .size
+            .expect("an integer type has a size")
+            * 8
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+        }
+    }
+}
+
+impl Value {
+    fn primitive(&self) -> Option<Primitive> {
+        match self {
+            Value::Integer(integer) => Some(integer.primitive),
+            Value::Float(_, primitive) => Some(*primitive),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Panic {
+                message,
+                location: Some(location),
+            } => write!(f, "panicked at {}: {}", location, message),
+            Error::Panic {
+                message,
+                location: None,
+            } => write!(f, "panicked: {}", message),
+            Error::Unsupported(message) => write!(f, "cannot interpret the test: {}", message),
+            Error::Limit(message) => write!(f, "stopped: {}", message),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Unit => write!(f, "()"),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Char(value) => write!(f, "{}", value),
+            Value::Integer(integer) => write!(f, "{}", integer),
+            Value::Float(value, Primitive::F32) => write!(f, "{}", *value as f32),
+            Value::Float(value, _) => write!(f, "{}", value),
+            Value::String(value) => write!(f, "{}", value),
+            Value::Tuple(elements) => {
+                write!(f, "(")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                if elements.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            }
+            Value::Array(elements) => {
+                write!(f, "[")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Value::Function(_) => write!(f, "fn"),
+            Value::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                write!(f, "{}{}", start, if *inclusive { "..=" } else { ".." })?;
+                match end {
+                    Some(end) => write!(f, "{}", end),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.magnitude)
+    }
+}
+
+impl From<Error> for Unwind {
+    fn from(error: Error) -> Self {
+        Unwind::Error(error)
+    }
+}
+
+fn unsupported(message: impl Into<String>) -> Error {
+    Error::Unsupported(message.into())
+}
+
+fn mismatch(expected: Primitive, found: Primitive) -> Error {
+    unsupported(format!(
+        "expected `{}`, found `{}`",
+        expected.name(),
+        found.name()
+    ))
+}
+
+/// `value` rounded to `f32` if that is its type.
+fn float(value: f64, primitive: Primitive) -> Value {
+    match primitive {
+        Primitive::F32 => Value::Float(value as f32 as f64, primitive),
+        _ => Value::Float(value, primitive),
+    }
+}
+
+/// The name a path of a single identifier gives a local.
+fn local(path: &hir::Path) -> Option<Symbol> {
+    match &path.segments[..] {
+        [segment] => match &segment.kind {
+            hir::PathSegmentKind::Identifier(name) => Some(name.symbol),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether `expression` is a numeric literal without a suffix, possibly negated.
+fn unsuffixed(expression: &hir::Expression) -> bool {
+    match &expression.kind {
+        hir::ExpressionKind::Literal { value } => matches!(
+            value.kind,
+            hir::LiteralKind::Integer { suffix: None, .. }
+                | hir::LiteralKind::Float { suffix: None, .. }
+        ),
+        hir::ExpressionKind::Unary {
+            operator: hir::UnaryOperator::Negate,
+            operand,
+        } => unsuffixed(operand),
+        _ => false,
+    }
+}
+
+/// The order of two values of the same type, `None` if one is NaN.
+fn compare(lhs: &Value, rhs: &Value) -> Result<Option<Ordering>, Unwind> {
+    let ordering = match (lhs, rhs) {
+        (Value::Unit, Value::Unit) => Some(Ordering::Equal),
+        (Value::Bool(lhs), Value::Bool(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Char(lhs), Value::Char(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Integer(lhs), Value::Integer(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Float(lhs, _), Value::Float(rhs, _)) => lhs.partial_cmp(rhs),
+        (Value::String(lhs), Value::String(rhs)) => Some(lhs.cmp(rhs)),
+        (Value::Tuple(lhs), Value::Tuple(rhs)) | (Value::Array(lhs), Value::Array(rhs)) => {
+            for (lhs, rhs) in lhs.iter().zip(rhs) {
+                match compare(lhs, rhs)? {
+                    Some(Ordering::Equal) => {
+                        //
+                    }
+                    ordering => return Ok(ordering),
+                }
+            }
+            Some(lhs.len().cmp(&rhs.len()))
+        }
+        (Value::Function(lhs), Value::Function(rhs)) if lhs == rhs => Some(Ordering::Equal),
+        _ => {
+            return Err(unsupported(format!("cannot compare `{}` with `{}`", lhs, rhs)).into());
+        }
+    };
+
+    Ok(ordering)
+}
+
+/// `lhs + rhs` on signs and magnitudes, with the magnitude `None` if it does not fit in `u128`.
+fn add(lhs_negative: bool, lhs: u128, rhs_negative: bool, rhs: u128) -> (bool, Option<u128>) {
+    if lhs_negative == rhs_negative {
+        (lhs_negative, lhs.checked_add(rhs))
+    } else if lhs >= rhs {
+        (lhs_negative, Some(lhs - rhs))
+    } else {
+        (rhs_negative, Some(rhs - lhs))
+    }
+}
+
+fn wrapping_pow(mut base: u128, mut exponent: u128) -> u128 {
+    let mut result: u128 = 1;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.wrapping_mul(base);
+        }
+        base = base.wrapping_mul(base);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+fn mask(width: u32) -> u128 {
+    u128::MAX >> (128 - width)
+}
+
+/// The operator that a compound assignment applies, `None` for `=`.
+fn binary_operator(operator: &hir::AssignmentOperator) -> Option<hir::BinaryOperator> {
+    use hir::{AssignmentOperator as Assign, BinaryOperator as Op};
+
+    let operator = match operator {
+        Assign::Assign => return None,
+        Assign::Add => Op::Add,
+        Assign::SaturatingAdd => Op::SaturatingAdd,
+        Assign::WrappingAdd => Op::WrappingAdd,
+        Assign::Subtract => Op::Subtract,
+        Assign::SaturatingSubtract => Op::SaturatingSubtract,
+        Assign::WrappingSubtract => Op::WrappingSubtract,
+        Assign::Multiply => Op::Multiply,
+        Assign::SaturatingMultiply => Op::SaturatingMultiply,
+        Assign::WrappingMultiply => Op::WrappingMultiply,
+        Assign::Divide => Op::Divide,
+        Assign::Remainder => Op::Remainder,
+        Assign::Exponent => Op::Exponent,
+        Assign::SaturatingExponent => Op::SaturatingExponent,
+        Assign::WrappingExponent => Op::WrappingExponent,
+        Assign::BitwiseAnd => Op::BitwiseAnd,
+        Assign::BitwiseOr => Op::BitwiseOr,
+        Assign::BitwiseXor => Op::BitwiseXor,
+        Assign::LeftShift => Op::LeftShift,
+        Assign::SaturatingLeftShift => Op::SaturatingLeftShift,
+        Assign::RightShift => Op::RightShift,
+        Assign::RightShiftUnsigned => Op::RightShiftUnsigned,
+        Assign::LogicalAnd => Op::LogicalAnd,
+        Assign::LogicalOr => Op::LogicalOr,
+    };
+
+    Some(operator)
+}
+
+/// The local named `symbol`, indexed by `indices`.
+fn slot<'a>(
+    frames: &'a mut [Vec<(Symbol, Value)>],
+    symbol: Symbol,
+    indices: &[(Value, Span)],
+) -> Result<&'a mut Value, Fault> {
+    let mut slot = frames
+        .iter_mut()
+        .rev()
+        .flat_map(|frame| frame.iter_mut().rev())
+        .find(|(name, _)| *name == symbol)
+        .map(|(_, value)| value)
+        .ok_or_else(|| Fault::Unsupported("a path that names nothing".to_owned()))?;
+    for (index, span) in indices {
+        slot = match slot {
+            Value::Array(elements) => {
+                let index = checked_index(index, elements.len(), *span)?;
+                &mut elements[index]
+            }
+            value => return Err(Fault::Unsupported(format!("cannot index into `{}`", value))),
+        };
+    }
+
+    Ok(slot)
+}
+
+/// `index` as a position in something `len` long, indexed at `span`.
+fn checked_index(index: &Value, len: usize, span: Span) -> Result<usize, Fault> {
+    let Value::Integer(integer) = index else {
+        return Err(Fault::Unsupported(format!("cannot index with `{}`", index)));
+    };
+    match usize::try_from(integer.magnitude) {
+        Ok(position) if !integer.negative && position < len => Ok(position),
+        _ => Err(Fault::Panic(
+            format!(
+                "index out of bounds: the length is {} but the index is {}",
+                len, integer
+            ),
+            span,
+        )),
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Database, env::Env};
+use danubec_diagnostic::Diagnostic;
+use danubec_symbol::{FileId, KrateId, SymbolInterner};
+use fxhash::FxHashMap;
+use std::path::{Path, PathBuf};
+
+/// Creates a crate for `root` and, when `root` is a `Danube.toml`, for each of its
+/// dependencies. Dependencies come before their dependents.
+pub fn krates(
+    db: &mut Database,
+    env: &mut Env,
+    symbols: &mut SymbolInterner,
+    diagnostic: &mut Diagnostic,
+    root: PathBuf,
+) -> Vec<KrateId> {
+    if root.file_name() != Some(danubec_manifest::FILE_NAME.as_ref()) {
+        let name = root.file_stem().unwrap_or_default().to_string_lossy();
+        let name = symbols.intern(&name);
+        let file = db.krate(root);
+
+        return vec![env.krate(name, file)];
+    }
+
+    let mut loader = Loader {
+        db,
+        env,
+        symbols,
+        diagnostic,
+        manifests: FxHashMap::default(),
+        krates: vec![],
+    };
+    loader.load(root);
+
+    loader.krates
+}
+
+struct Loader<'lowering> {
+    db: &'lowering mut Database,
+    env: &'lowering mut Env,
+    symbols: &'lowering mut SymbolInterner,
+    diagnostic: &'lowering mut Diagnostic,
+    /// `None` while the manifest's dependencies are being loaded.
+    manifests: FxHashMap<FileId, Option<KrateId>>,
+    krates: Vec<KrateId>,
+}
+
+impl Loader<'_> {
+    fn load(&mut self, path: PathBuf) -> Option<KrateId> {
+        let file = self.db.krate(path.clone());
+        match self.manifests.get(&file) {
+            Some(Some(krate)) => return Some(*krate),
+            Some(None) => {
+                self.diagnostic
+                    .report(miette!("Cyclic dependency on {:?}", path));
+                return None;
+            }
+            None => {
+                //
+            }
+        }
+
+        let Some(source) = self.db.source(file) else {
+            self.diagnostic
+                .report(miette!("Manifest not found: {:?}", path));
+            return None;
+        };
+        let mut diagnostic = Diagnostic::new();
+        let manifest = danubec_manifest::parse(&source, &mut diagnostic);
+        for report in diagnostic.iter() {
+            self.diagnostic.report(miette!("{:?}: {}", path, report));
+        }
+        let manifest = manifest?;
+
+        self.manifests.insert(file, None);
+
+        let directory = path.parent().unwrap_or(Path::new(""));
+        let mut dependencies = FxHashMap::default();
+        for dependency in &manifest.dependencies {
+            let path = directory
+                .join(&dependency.path)
+                .join(danubec_manifest::FILE_NAME);
+            if let Some(krate) = self.load(path) {
+                dependencies.insert(self.symbols.intern(&dependency.name), krate);
+            }
+        }
+
+        let name = self.symbols.intern(&manifest.package.name);
+        let entry = self.db.krate(directory.join(&manifest.package.entry));
+        let krate = self.env.krate(name, entry);
+        self.env[krate].dependencies = dependencies;
+
+        self.manifests.insert(file, Some(krate));
+        self.krates.push(krate);
+
+        Some(krate)
+    }
+}
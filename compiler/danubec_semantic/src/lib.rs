@@ -6,13 +6,32 @@ extern crate miette;
 #[cfg(test)]
 mod tests;
 
+mod attribute;
+mod cfg;
 mod check;
 mod collect;
+mod database;
 mod env;
 mod fs;
+mod harness;
 mod inference;
+mod interpret;
+mod krate;
+mod library;
+mod outline;
+mod prelude;
+mod primitive;
 mod resolve;
 mod semantic;
 mod ticker;
 
+pub use cfg::*;
+pub use database::*;
+pub use env::Env;
+pub use fs::*;
+pub use harness::*;
+pub use interpret::*;
+pub use library::*;
+pub use outline::*;
+pub use primitive::*;
 pub use semantic::*;
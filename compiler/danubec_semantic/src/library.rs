@@ -0,0 +1,46 @@
+use crate::Fs;
+use std::path::{Path, PathBuf};
+
+/// Where the bundled standard library appears in the file system.
+pub const STD_ROOT: &str = "<std>";
+
+/// The sources of `library/std`, relative to its manifest.
+const STD: &[(&str, &str)] = &[
+    (
+        "Danube.toml",
+        include_str!("../../../library/std/Danube.toml"),
+    ),
+    (
+        "src/lib.dnb",
+        include_str!("../../../library/std/src/lib.dnb"),
+    ),
+    (
+        "src/io.dnb",
+        include_str!("../../../library/std/src/io.dnb"),
+    ),
+    (
+        "src/panic.dnb",
+        include_str!("../../../library/std/src/panic.dnb"),
+    ),
+    (
+        "src/prelude.dnb",
+        include_str!("../../../library/std/src/prelude.dnb"),
+    ),
+    (
+        "src/string.dnb",
+        include_str!("../../../library/std/src/string.dnb"),
+    ),
+];
+
+impl Fs {
+    /// Overlays the standard library shipped with the compiler under [`STD_ROOT`], and
+    /// returns the path of its manifest.
+    pub fn std(&mut self) -> PathBuf {
+        let root = Path::new(STD_ROOT);
+        for (path, source) in STD {
+            self.overlay(root.join(path), (*source).to_owned());
+        }
+
+        root.join(danubec_manifest::FILE_NAME)
+    }
+}
@@ -0,0 +1,153 @@
+use crate::collect::written;
+use danubec_ast as ast;
+use danubec_syntax::{AstNode, Danube};
+use std::ops::Range;
+
+/// A definition in a file, as an editor lists it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outline {
+    pub name: String,
+    pub kind: OutlineKind,
+    /// The whole definition, without the trivia after it.
+    pub range: Range<usize>,
+    /// The part of `range` that names the definition.
+    pub selection: Range<usize>,
+    pub children: Vec<Outline>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineKind {
+    Function,
+    Struct,
+    Field,
+    Enum,
+    Variant,
+    Module,
+    Trait,
+    Constant,
+    Static,
+    Type,
+    Implement,
+}
+
+/// The definitions in `root`, with the ones nested in modules, traits, impl blocks, structs
+/// and enums as children. Definitions in function bodies are left out.
+pub fn outline(root: &ast::Root) -> Vec<Outline> {
+    root.definitions().filter_map(definition).collect()
+}
+
+/// Spans the attributes and visibility of `node` too.
+fn definition(node: ast::Definition) -> Option<Outline> {
+    let mut outline = definition_kind(node.kind()?)?;
+    outline.range = written(node.syntax());
+
+    Some(outline)
+}
+
+fn definition_kind(kind: ast::DefinitionKind) -> Option<Outline> {
+    match kind {
+        ast::DefinitionKind::Function(node) => item(&node, node.name(), OutlineKind::Function),
+        ast::DefinitionKind::Struct(node) => {
+            let mut outline = item(&node, node.name(), OutlineKind::Struct)?;
+            if let Some(ast::StructBody::Named(body)) = node.body() {
+                outline.children = body
+                    .fields()
+                    .filter_map(|field| item(&field, field.name(), OutlineKind::Field))
+                    .collect();
+            }
+
+            Some(outline)
+        }
+        ast::DefinitionKind::Enum(node) => {
+            let mut outline = item(&node, node.name(), OutlineKind::Enum)?;
+            outline.children = node.variants().filter_map(variant).collect();
+
+            Some(outline)
+        }
+        ast::DefinitionKind::Module(node) => {
+            let mut outline = item(&node, node.name(), OutlineKind::Module)?;
+            if let Some(ast::ModuleDefinitionKind::Inline(inline)) = node.kind() {
+                outline.children = inline.definitions().filter_map(definition).collect();
+            }
+
+            Some(outline)
+        }
+        ast::DefinitionKind::Trait(node) => {
+            let mut outline = item(&node, node.name(), OutlineKind::Trait)?;
+            outline.children = associated(node.definitions());
+
+            Some(outline)
+        }
+        ast::DefinitionKind::Constant(node) => item(&node, node.name(), OutlineKind::Constant),
+        ast::DefinitionKind::Static(node) => item(&node, node.name(), OutlineKind::Static),
+        ast::DefinitionKind::Type(node) => item(&node, node.name(), OutlineKind::Type),
+        ast::DefinitionKind::Implement(node) => {
+            let target = node.target_type()?;
+            let name = match node.trait_type() {
+                Some(r#trait) => format!("impl {} for {}", text(&r#trait), text(&target)),
+                None => format!("impl {}", text(&target)),
+            };
+
+            Some(Outline {
+                name,
+                kind: OutlineKind::Implement,
+                range: written(node.syntax()),
+                selection: written(target.syntax()),
+                children: associated(node.definitions()),
+            })
+        }
+        ast::DefinitionKind::Use(_) => None,
+    }
+}
+
+fn associated(definitions: impl Iterator<Item = ast::AssociatedDefinition>) -> Vec<Outline> {
+    definitions
+        .filter_map(|definition| {
+            let mut outline = match definition.kind()? {
+                ast::AssociatedDefinitionKind::Function(node) => {
+                    item(&node, node.name(), OutlineKind::Function)
+                }
+                ast::AssociatedDefinitionKind::Constant(node) => {
+                    item(&node, node.name(), OutlineKind::Constant)
+                }
+                ast::AssociatedDefinitionKind::Type(node) => {
+                    item(&node, node.name(), OutlineKind::Type)
+                }
+            }?;
+            outline.range = written(definition.syntax());
+
+            Some(outline)
+        })
+        .collect()
+}
+
+fn variant(node: ast::EnumVariant) -> Option<Outline> {
+    match node {
+        ast::EnumVariant::Unit(node) => item(&node, node.name(), OutlineKind::Variant),
+        ast::EnumVariant::Scalar(node) => item(&node, node.name(), OutlineKind::Variant),
+        ast::EnumVariant::Named(node) => item(&node, node.name(), OutlineKind::Variant),
+        ast::EnumVariant::Unnamed(node) => item(&node, node.name(), OutlineKind::Variant),
+    }
+}
+
+fn item(
+    node: &impl AstNode<Language = Danube>,
+    name: Option<ast::Identifier>,
+    kind: OutlineKind,
+) -> Option<Outline> {
+    let name = name?;
+
+    Some(Outline {
+        name: text(&name),
+        kind,
+        range: written(node.syntax()),
+        selection: written(name.syntax()),
+        children: vec![],
+    })
+}
+
+fn text(node: &impl AstNode<Language = Danube>) -> String {
+    let text = node.syntax().text().to_string();
+
+    text[..written(node.syntax()).len()].to_owned()
+}
@@ -0,0 +1,40 @@
+use crate::{attribute::Builtin, env::Env};
+use danubec_diagnostic::Diagnostic;
+use danubec_symbol::{KrateId, SymbolInterner};
+
+/// Makes `std` a dependency of every other crate, and its `prelude` module the prelude of
+/// each crate whose root doesn't opt out with `#![no_prelude]`.
+pub fn prelude(
+    env: &mut Env,
+    symbols: &mut SymbolInterner,
+    diagnostic: &mut Diagnostic,
+    std: KrateId,
+) {
+    let name = symbols.intern("std");
+    let prelude = env[env[std].root]
+        .children
+        .get(&symbols.intern("prelude"))
+        .copied();
+    if prelude.is_none() {
+        diagnostic.report(miette!("The standard library has no `prelude` module"));
+    }
+
+    let krates: Vec<_> = env
+        .krates()
+        .map(|(krate, _)| krate)
+        .filter(|krate| *krate != std)
+        .collect();
+    for krate in krates {
+        env[krate].dependencies.entry(name).or_insert(std);
+
+        let root = env[krate].root;
+        if env[root]
+            .builtins
+            .iter()
+            .any(|builtin| matches!(builtin, Builtin::NoPrelude))
+        {
+            continue;
+        }
+        env[krate].prelude = prelude;
+    }
+}
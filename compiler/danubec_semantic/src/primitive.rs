@@ -0,0 +1,270 @@
+use danubec_hir as hir;
+use miette::LabeledSpan;
+use rowan::TextRange;
+use std::fmt::Display;
+
+/// A type built into the language, resolved when no item of the same name is in scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Primitive {
+    Bool,
+    Char,
+    Str,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    Isize,
+    F32,
+    F64,
+}
+
+#[derive(Debug)]
+pub struct PrimitiveType {
+    pub primitive: Primitive,
+    pub name: &'static str,
+    /// In bytes, with pointer-sized integers on a 64-bit target; `None` for `str`, which is unsized.
+    pub size: Option<u32>,
+    pub signed: bool,
+    pub float: bool,
+}
+
+pub const PRIMITIVE_TYPES: [PrimitiveType; 17] = [
+    PrimitiveType {
+        primitive: Primitive::Bool,
+        name: "bool",
+        size: Some(1),
+        signed: false,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::Char,
+        name: "char",
+        size: Some(4),
+        signed: false,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::Str,
+        name: "str",
+        size: None,
+        signed: false,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::U8,
+        name: "u8",
+        size: Some(1),
+        signed: false,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::U16,
+        name: "u16",
+        size: Some(2),
+        signed: false,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::U32,
+        name: "u32",
+        size: Some(4),
+        signed: false,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::U64,
+        name: "u64",
+        size: Some(8),
+        signed: false,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::U128,
+        name: "u128",
+        size: Some(16),
+        signed: false,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::Usize,
+        name: "usize",
+        size: Some(8),
+        signed: false,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::I8,
+        name: "i8",
+        size: Some(1),
+        signed: true,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::I16,
+        name: "i16",
+        size: Some(2),
+        signed: true,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::I32,
+        name: "i32",
+        size: Some(4),
+        signed: true,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::I64,
+        name: "i64",
+        size: Some(8),
+        signed: true,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::I128,
+        name: "i128",
+        size: Some(16),
+        signed: true,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::Isize,
+        name: "isize",
+        size: Some(8),
+        signed: true,
+        float: false,
+    },
+    PrimitiveType {
+        primitive: Primitive::F32,
+        name: "f32",
+        size: Some(4),
+        signed: true,
+        float: true,
+    },
+    PrimitiveType {
+        primitive: Primitive::F64,
+        name: "f64",
+        size: Some(8),
+        signed: true,
+        float: true,
+    },
+];
+
+impl Primitive {
+    pub fn r#type(self) -> &'static PrimitiveType {
+        PRIMITIVE_TYPES
+            .iter()
+            .find(|r#type| r#type.primitive == self)
+            .unwrap()
+    }
+
+    #[inline]
+    pub fn name(self) -> &'static str {
+        self.r#type().name
+    }
+
+    pub fn is_integer(self) -> bool {
+        let r#type = self.r#type();
+        !r#type.float && !matches!(self, Primitive::Bool | Primitive::Char | Primitive::Str)
+    }
+
+    #[inline]
+    pub fn is_float(self) -> bool {
+        self.r#type().float
+    }
+
+    /// The smallest and largest value of an integer type.
+    pub fn range(self) -> Option<(i128, u128)> {
+        if !self.is_integer() {
+            return None;
+        }
+
+        let r#type = self.r#type();
+        let bits = r#type.size? * 8;
+        let range = if r#type.signed {
+            (
+                i128::MIN >> (128 - bits),
+                (i128::MAX >> (128 - bits)) as u128,
+            )
+        } else {
+            (0, u128::MAX >> (128 - bits))
+        };
+
+        Some(range)
+    }
+
+    /// Whether the literal `value`, negated if `negated`, can be represented by this
+    /// integer type.
+    pub fn contains(self, value: u128, negated: bool) -> bool {
+        match self.range() {
+            Some((min, _)) if negated => value <= min.unsigned_abs(),
+            Some((_, max)) => value <= max,
+            None => false,
+        }
+    }
+}
+
+impl From<hir::IntegerSuffix> for Primitive {
+    fn from(suffix: hir::IntegerSuffix) -> Self {
+        match suffix {
+            hir::IntegerSuffix::U8 => Primitive::U8,
+            hir::IntegerSuffix::U16 => Primitive::U16,
+            hir::IntegerSuffix::U32 => Primitive::U32,
+            hir::IntegerSuffix::U64 => Primitive::U64,
+            hir::IntegerSuffix::U128 => Primitive::U128,
+            hir::IntegerSuffix::Usize => Primitive::Usize,
+            hir::IntegerSuffix::I8 => Primitive::I8,
+            hir::IntegerSuffix::I16 => Primitive::I16,
+            hir::IntegerSuffix::I32 => Primitive::I32,
+            hir::IntegerSuffix::I64 => Primitive::I64,
+            hir::IntegerSuffix::I128 => Primitive::I128,
+            hir::IntegerSuffix::Isize => Primitive::Isize,
+        }
+    }
+}
+
+impl From<hir::FloatSuffix> for Primitive {
+    fn from(suffix: hir::FloatSuffix) -> Self {
+        match suffix {
+            hir::FloatSuffix::F32 => Primitive::F32,
+            hir::FloatSuffix::F64 => Primitive::F64,
+        }
+    }
+}
+
+/// Reports the literal at `range`, written as `literal`, as out of the range of `primitive`.
+pub(crate) fn out_of_range(
+    range: TextRange,
+    literal: impl Display,
+    primitive: Primitive,
+) -> miette::Report {
+    let span = usize::from(range.start())..usize::from(range.end());
+    let labels = vec![LabeledSpan::at(
+        span,
+        format!("does not fit in `{}`", primitive.name()),
+    )];
+
+    match primitive.range() {
+        Some((min, max)) => miette!(
+            labels = labels,
+            "Literal `{}` out of range for `{}`, whose range is `{}..={}`",
+            literal,
+            primitive.name(),
+            min,
+            max
+        ),
+        None => miette!(
+            labels = labels,
+            "Literal `{}` out of range for `{}`",
+            literal,
+            primitive.name()
+        ),
+    }
+}
@@ -1,6 +1,14 @@
-use crate::{env::Env, ticker::Ticker};
+use crate::{
+    attribute::Deprecation,
+    env::{Env, Resolved},
+    ticker::Ticker,
+};
 use danubec_diagnostic::Diagnostic;
-use danubec_symbol::SymbolInterner;
+use danubec_hir as hir;
+use danubec_symbol::{DefinitionId, ScopeId, Symbol, SymbolInterner};
+use danubec_syntax::Span;
+use miette::LabeledSpan;
+use std::sync::Arc;
 
 pub fn resolve(
     env: &mut Env,
@@ -8,5 +16,559 @@ pub fn resolve(
     diagnostic: &mut Diagnostic,
     ticker: &mut Ticker,
 ) {
-    //
+    let modules: Vec<_> = env
+        .modules()
+        .map(|(module, data)| (module, data.scope))
+        .collect();
+
+    for (module, scope) in modules {
+        let file = env[module].file;
+        let bindings: Vec<_> = env[scope]
+            .imports()
+            .iter()
+            .enumerate()
+            .filter(|(_, import)| {
+                matches!(import.kind, hir::ImportKind::Symbol(_))
+                    && matches!(import.path.binding, hir::Binding::Unresolved)
+            })
+            .filter_map(|(index, import)| {
+                match env.resolve_path(module, &import.path.segments)? {
+                    Resolved::Definition(definition) => Some((index, definition)),
+                    Resolved::Module(_) | Resolved::Primitive(_) => None,
+                }
+            })
+            .collect();
+
+        for (index, definition) in bindings {
+            let path = &mut env[scope].imports_mut()[index].path;
+            path.binding = hir::Binding::Definition(definition);
+            if let Some(hir::PathSegmentKind::Identifier(name)) =
+                path.segments.last().map(|segment| &segment.kind)
+            {
+                let span = name.span;
+                env.reference(file, span, definition);
+            }
+            ticker.mark();
+
+            if let Some(deprecation) = env.deprecation(definition) {
+                let name = &symbols[env[definition].definition.name.symbol];
+                diagnostic.report(deprecated(name, deprecation, vec![]));
+            }
+        }
+    }
+
+    bodies(env, symbols, diagnostic, ticker);
+}
+
+/// Binds the paths in the signature and body of every definition that name another
+/// definition, and warns about the deprecated ones.
+///
+/// Only unbound paths are looked at, so each warning is given once however many times the
+/// passes are run.
+fn bodies(
+    env: &mut Env,
+    symbols: &mut SymbolInterner,
+    diagnostic: &mut Diagnostic,
+    ticker: &mut Ticker,
+) {
+    let definitions: Vec<_> = env
+        .definitions()
+        .map(|(definition, data)| (definition, data.scope, data.file))
+        .collect();
+
+    for (definition, scope, file) in definitions {
+        // Taken out for the walk, which reads the rest of `env`.
+        let mut kind = std::mem::replace(
+            &mut env[definition].definition.kind,
+            hir::DefinitionKind::Module {
+                kind: hir::ModuleDefinitionKind::External,
+            },
+        );
+        let start = diagnostic.len();
+        let mut binder = Binder {
+            env,
+            symbols,
+            diagnostic,
+            ticker,
+            scope,
+            source: env.source_code(file).map(|source| source.inner().clone()),
+            frames: vec![],
+            references: vec![],
+            // Deprecated items may use each other without warnings.
+            quiet: env.deprecation(definition).is_some(),
+        };
+        binder.definition(&mut kind);
+        let references = binder.references;
+        env[definition].definition.kind = kind;
+        for (span, target) in references {
+            env.reference(file, span, target);
+        }
+
+        if let Some(source) = env.source_code(file) {
+            diagnostic.with_source_code(start, source);
+        }
+    }
+}
+
+/// The names a block or pattern introduces.
+struct Frame {
+    /// The scope that the definitions in a block are declared in, if it has any.
+    scope: Option<ScopeId>,
+    locals: Vec<Symbol>,
+}
+
+struct Binder<'env> {
+    env: &'env Env,
+    symbols: &'env SymbolInterner,
+    diagnostic: &'env mut Diagnostic,
+    ticker: &'env mut Ticker,
+    /// The scope the walked definition is declared in.
+    scope: ScopeId,
+    /// The text of the file the definition is in.
+    source: Option<Arc<str>>,
+    frames: Vec<Frame>,
+    /// The paths bound so far, by the span of the name that names the definition.
+    references: Vec<(Span, DefinitionId)>,
+    quiet: bool,
+}
+
+impl Binder<'_> {
+    fn definition(&mut self, kind: &mut hir::DefinitionKind) {
+        match kind {
+            hir::DefinitionKind::Function {
+                parameters,
+                return_type,
+                body,
+                ..
+            } => {
+                self.with_frame(None, |this| {
+                    for parameter in parameters {
+                        this.type_expression(&mut parameter.r#type);
+                        this.pattern(&mut parameter.pattern);
+                    }
+                    if let Some(return_type) = return_type {
+                        this.type_expression(return_type);
+                    }
+                    if let Some(body) = body {
+                        this.statements(body);
+                    }
+                });
+            }
+            hir::DefinitionKind::Struct { body, .. } => match body {
+                hir::StructBody::Unit => {
+                    //
+                }
+                hir::StructBody::Named(fields) => {
+                    for (_, _, _, r#type) in fields {
+                        self.type_expression(r#type);
+                    }
+                }
+                hir::StructBody::Unnamed(fields) => {
+                    for (_, _, r#type) in fields {
+                        self.type_expression(r#type);
+                    }
+                }
+            },
+            hir::DefinitionKind::Enum { variants, .. } => {
+                for variant in variants {
+                    match &mut variant.kind {
+                        hir::EnumVariantKind::Unit => {
+                            //
+                        }
+                        hir::EnumVariantKind::Scalar(value) => self.expression(value),
+                        hir::EnumVariantKind::Named(fields) => {
+                            for (_, _, r#type) in fields {
+                                self.type_expression(r#type);
+                            }
+                        }
+                        hir::EnumVariantKind::Unnamed(fields) => {
+                            for (_, r#type) in fields {
+                                self.type_expression(r#type);
+                            }
+                        }
+                    }
+                }
+            }
+            hir::DefinitionKind::Constant {
+                r#type,
+                initializer,
+            } => {
+                if let Some(r#type) = r#type {
+                    self.type_expression(r#type);
+                }
+                if let Some(initializer) = initializer {
+                    self.expression(initializer);
+                }
+            }
+            hir::DefinitionKind::Static {
+                r#type,
+                initializer,
+            } => {
+                self.type_expression(r#type);
+                self.expression(initializer);
+            }
+            hir::DefinitionKind::Type {
+                initializer: Some(initializer),
+                ..
+            } => self.type_expression(initializer),
+            _ => {
+                //
+            }
+        }
+    }
+
+    /// Definitions in `statements` are visible in all of them, a `let` only after it.
+    fn statements(&mut self, statements: &mut [hir::Statement]) {
+        let scope = statements
+            .iter()
+            .find_map(|statement| match statement.kind {
+                hir::StatementKind::Definition { definition } => Some(self.env[definition].scope),
+                _ => None,
+            });
+        self.with_frame(scope, |this| {
+            for statement in statements {
+                match &mut statement.kind {
+                    hir::StatementKind::Let {
+                        pattern,
+                        r#type,
+                        initializer,
+                    } => {
+                        if let Some(r#type) = r#type {
+                            this.type_expression(r#type);
+                        }
+                        if let Some(initializer) = initializer {
+                            this.expression(initializer);
+                        }
+                        this.pattern(pattern);
+                    }
+                    hir::StatementKind::Expression { value, .. } => this.expression(value),
+                    hir::StatementKind::Definition { .. } | hir::StatementKind::Semicolon => {
+                        //
+                    }
+                }
+            }
+        });
+    }
+
+    fn expression(&mut self, expression: &mut hir::Expression) {
+        let span = expression.span;
+        match &mut expression.kind {
+            hir::ExpressionKind::Path { path } => self.path(path, span),
+            hir::ExpressionKind::Struct {
+                path,
+                type_arguments,
+                fields,
+            } => {
+                self.path(path, span);
+                for r#type in type_arguments {
+                    self.type_expression(r#type);
+                }
+                for (_, field) in fields {
+                    self.expression(field);
+                }
+            }
+            hir::ExpressionKind::Let {
+                pattern,
+                r#type,
+                initializer,
+            } => {
+                if let Some(r#type) = r#type {
+                    self.type_expression(r#type);
+                }
+                if let Some(initializer) = initializer {
+                    self.expression(initializer);
+                }
+                self.pattern(pattern);
+            }
+            hir::ExpressionKind::Block { statements, .. }
+            | hir::ExpressionKind::Loop { body: statements } => self.statements(statements),
+            hir::ExpressionKind::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                // What `if let` binds is only visible in the first branch.
+                self.with_frame(None, |this| {
+                    this.expression(condition);
+                    this.statements(then_branch);
+                });
+                if let Some(else_branch) = else_branch {
+                    self.expression(else_branch);
+                }
+            }
+            hir::ExpressionKind::While { condition, body } => {
+                self.with_frame(None, |this| {
+                    this.expression(condition);
+                    this.statements(body);
+                });
+            }
+            hir::ExpressionKind::For {
+                pattern,
+                iterable,
+                body,
+            } => {
+                self.expression(iterable);
+                self.with_frame(None, |this| {
+                    this.pattern(pattern);
+                    this.statements(body);
+                });
+            }
+            hir::ExpressionKind::Match { expression, arms } => {
+                self.expression(expression);
+                for (pattern, arm) in arms {
+                    self.with_frame(None, |this| {
+                        this.pattern(pattern);
+                        this.expression(arm);
+                    });
+                }
+            }
+            hir::ExpressionKind::Array { elements } | hir::ExpressionKind::Tuple { elements } => {
+                for element in elements {
+                    self.expression(element);
+                }
+            }
+            hir::ExpressionKind::Binary { left, right, .. }
+            | hir::ExpressionKind::Assignment { left, right, .. }
+            | hir::ExpressionKind::Index {
+                receiver: left,
+                index: right,
+            } => {
+                self.expression(left);
+                self.expression(right);
+            }
+            hir::ExpressionKind::FunctionCall {
+                callee: receiver,
+                type_arguments,
+                arguments,
+            }
+            | hir::ExpressionKind::MethodCall {
+                receiver,
+                type_arguments,
+                arguments,
+                ..
+            } => {
+                self.expression(receiver);
+                for r#type in type_arguments {
+                    self.type_expression(r#type);
+                }
+                for argument in arguments {
+                    self.expression(argument);
+                }
+            }
+            hir::ExpressionKind::Unary { operand: value, .. }
+            | hir::ExpressionKind::Field {
+                receiver: value, ..
+            }
+            | hir::ExpressionKind::Await { expression: value }
+            | hir::ExpressionKind::Try { value } => self.expression(value),
+            hir::ExpressionKind::Return { value } | hir::ExpressionKind::Yield { value } => {
+                if let Some(value) = value {
+                    self.expression(value);
+                }
+            }
+            hir::ExpressionKind::Range { range } => match range {
+                hir::RangeExpression::Full => {
+                    //
+                }
+                hir::RangeExpression::To { end }
+                | hir::RangeExpression::ToInclusive { end }
+                | hir::RangeExpression::From { start: end } => self.expression(end),
+                hir::RangeExpression::FromTo { start, end }
+                | hir::RangeExpression::FromToInclusive { start, end } => {
+                    self.expression(start);
+                    self.expression(end);
+                }
+            },
+            hir::ExpressionKind::Literal { .. }
+            | hir::ExpressionKind::Break
+            | hir::ExpressionKind::Continue => {
+                //
+            }
+        }
+    }
+
+    /// Declares the names `pattern` binds in the innermost frame.
+    fn pattern(&mut self, pattern: &mut hir::Pattern) {
+        let span = pattern.span;
+        match &mut pattern.kind {
+            hir::PatternKind::Path { path } => {
+                // A single name binds a local, unless it names a unit struct or a constant.
+                if let [segment] = &path.segments[..]
+                    && let hir::PathSegmentKind::Identifier(name) = &segment.kind
+                    && matches!(path.binding, hir::Binding::Unresolved)
+                    && !self
+                        .resolve(&path.segments)
+                        .is_some_and(|definition| self.constant(definition))
+                {
+                    self.declare(name.symbol);
+                } else {
+                    self.path(path, span);
+                }
+            }
+            hir::PatternKind::Named { path, fields } => {
+                self.path(path, span);
+                for (_, field) in fields {
+                    self.pattern(field);
+                }
+            }
+            hir::PatternKind::Unnamed { path, elements } => {
+                self.path(path, span);
+                for element in elements {
+                    self.pattern(element);
+                }
+            }
+            hir::PatternKind::Tuple { elements }
+            | hir::PatternKind::Array { elements }
+            | hir::PatternKind::Or { patterns: elements } => {
+                for element in elements {
+                    self.pattern(element);
+                }
+            }
+            hir::PatternKind::At { name, pattern } => {
+                self.declare(name.symbol);
+                self.pattern(pattern);
+            }
+            hir::PatternKind::Rest { pattern } => self.pattern(pattern),
+            hir::PatternKind::Range { range } => match range {
+                hir::RangePattern::FromTo { start, end }
+                | hir::RangePattern::FromToInclusive { start, end } => {
+                    self.pattern(start);
+                    self.pattern(end);
+                }
+                hir::RangePattern::From { start: end }
+                | hir::RangePattern::To { end }
+                | hir::RangePattern::ToInclusive { end } => self.pattern(end),
+            },
+            hir::PatternKind::Never
+            | hir::PatternKind::Placeholder
+            | hir::PatternKind::Literal { .. } => {
+                //
+            }
+        }
+    }
+
+    fn type_expression(&mut self, r#type: &mut hir::TypeExpression) {
+        let span = r#type.span;
+        match &mut r#type.kind {
+            hir::TypeExpressionKind::Path { path } => self.path(path, span),
+            hir::TypeExpressionKind::Slice { element } => self.type_expression(element),
+            hir::TypeExpressionKind::Tuple { elements } => {
+                for element in elements {
+                    self.type_expression(element);
+                }
+            }
+            hir::TypeExpressionKind::Never => {
+                //
+            }
+        }
+    }
+
+    /// Binds `path` to the definition it names, unless it is a local.
+    fn path(&mut self, path: &mut hir::Path, span: Span) {
+        if !matches!(path.binding, hir::Binding::Unresolved) {
+            return;
+        }
+        if let [segment] = &path.segments[..]
+            && let hir::PathSegmentKind::Identifier(name) = &segment.kind
+            && self
+                .frames
+                .iter()
+                .any(|frame| frame.locals.contains(&name.symbol))
+        {
+            return;
+        }
+        let Some(definition) = self.resolve(&path.segments) else {
+            return;
+        };
+        path.binding = hir::Binding::Definition(definition);
+        self.ticker.mark();
+
+        // The last segment names the definition.
+        let span = match path.segments.last().map(|segment| &segment.kind) {
+            Some(hir::PathSegmentKind::Identifier(name)) => name.span,
+            _ => span,
+        };
+        self.references.push((span, definition));
+
+        if !self.quiet
+            && let Some(deprecation) = self.env.deprecation(definition)
+        {
+            let range = span.text_range();
+            let mut range = usize::from(range.start())..usize::from(range.end());
+            // Spans of nodes take the trivia after them.
+            if let Some(text) = self
+                .source
+                .as_deref()
+                .and_then(|source| source.get(range.clone()))
+            {
+                range.end = range.start + text.trim_end().len();
+            }
+            let name = &self.symbols[self.env[definition].definition.name.symbol];
+            self.diagnostic.report(deprecated(
+                name,
+                deprecation,
+                vec![LabeledSpan::underline(range)],
+            ));
+        }
+    }
+
+    fn resolve(&self, segments: &[hir::PathSegment]) -> Option<DefinitionId> {
+        let scope = self
+            .frames
+            .iter()
+            .rev()
+            .find_map(|frame| frame.scope)
+            .unwrap_or(self.scope);
+        match self.env.resolve_path_in(scope, segments)? {
+            Resolved::Definition(definition) => Some(definition),
+            Resolved::Module(_) | Resolved::Primitive(_) => None,
+        }
+    }
+
+    /// Whether `definition` can be named by a pattern of a single name.
+    fn constant(&self, definition: DefinitionId) -> bool {
+        matches!(
+            self.env[definition].definition.kind,
+            hir::DefinitionKind::Struct {
+                body: hir::StructBody::Unit,
+                ..
+            } | hir::DefinitionKind::Constant { .. }
+                | hir::DefinitionKind::Static { .. }
+        )
+    }
+
+    fn declare(&mut self, symbol: Symbol) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.locals.push(symbol);
+        }
+    }
+
+    fn with_frame<F>(&mut self, scope: Option<ScopeId>, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.frames.push(Frame {
+            scope,
+            locals: vec![],
+        });
+        f(self);
+        self.frames.pop();
+    }
+}
+
+fn deprecated(name: &str, deprecation: &Deprecation, labels: Vec<LabeledSpan>) -> miette::Report {
+    let mut message = format!("Use of deprecated item `{}`", name);
+    if let Some(since) = &deprecation.since {
+        message.push_str(&format!(" (since {})", since));
+    }
+    if let Some(note) = &deprecation.note {
+        message.push_str(&format!(": {}", note));
+    }
+
+    miette!(
+        severity = miette::Severity::Warning,
+        labels = labels,
+        "{}",
+        message
+    )
 }
@@ -1,24 +1,48 @@
 use crate::{
-    check::check, collect::collect, env::Env, fs::Fs, inference::inference, resolve::resolve,
-    ticker::Ticker,
+    CfgSet, Database, PRIMITIVE_TYPES, check::check, collect::collect, env::Env,
+    inference::inference, krate::krates, prelude::prelude, resolve::resolve, ticker::Ticker,
 };
 use danubec_diagnostic::Diagnostic;
 use danubec_symbol::SymbolInterner;
 use std::path::PathBuf;
 
 pub struct Context {
+    /// The entry file of a single crate, or a `Danube.toml` to also load its dependencies.
     pub root: PathBuf,
+    /// Options that `#[cfg(...)]` is evaluated against, shared by every crate.
+    pub cfg: CfgSet,
+    /// The `Danube.toml` of the standard library, such as the one from [`Database::std`],
+    /// which every other crate may refer to as `::std` and whose prelude it sees.
+    pub std: Option<PathBuf>,
 }
 
-pub fn semantic(Context { root }: Context) -> (Fs, Env, SymbolInterner, Diagnostic) {
-    let mut fs = Fs::new();
+/// Analyses the crate at `root`, and the crates it depends on.
+///
+/// Only reading and parsing sources goes through `db`, and is reused across calls.
+/// Collection and everything after it start from an empty `Env` on every call.
+pub fn semantic(
+    db: &mut Database,
+    Context { root, cfg, std }: Context,
+) -> (Env, SymbolInterner, Diagnostic) {
     let mut env = Env::new();
     let mut symbols = SymbolInterner::new();
     let mut diagnostic = Diagnostic::new();
 
-    let root = fs.krate(root);
-
-    collect(&mut fs, &mut env, &mut symbols, &mut diagnostic, root);
+    let std = std.and_then(|std| {
+        krates(db, &mut env, &mut symbols, &mut diagnostic, std)
+            .last()
+            .copied()
+    });
+    let krates = krates(db, &mut env, &mut symbols, &mut diagnostic, root);
+    for krate in std.into_iter().chain(krates) {
+        collect(db, &mut env, &mut symbols, &mut diagnostic, &cfg, krate);
+    }
+    if let Some(std) = std {
+        prelude(&mut env, &mut symbols, &mut diagnostic, std);
+    }
+    for r#type in &PRIMITIVE_TYPES {
+        env.primitive(symbols.intern(r#type.name), r#type.primitive);
+    }
 
     loop {
         let mut ticker = Ticker::new();
@@ -30,5 +54,5 @@ pub fn semantic(Context { root }: Context) -> (Fs, Env, SymbolInterner, Diagnost
         }
     }
 
-    (fs, env, symbols, diagnostic)
+    (env, symbols, diagnostic)
 }
@@ -1,14 +1,1502 @@
-use crate::{Context, semantic};
+use crate::{
+    CfgSet, Context, Database, Fs, MemoryFileSystem, Primitive, SyntaxError,
+    env::{Definition, Env, Resolved},
+    semantic,
+};
+use danubec_diagnostic::Diagnostic;
+use danubec_hir as hir;
+use danubec_symbol::SymbolInterner;
+use std::path::{Path, PathBuf};
 
-#[test]
-fn all_files() {
+fn database(fs: MemoryFileSystem) -> Database {
+    Database::with_fs(Fs::with(fs))
+}
+
+/// Analyses `files`, taking the first of them as the root, without `std`.
+fn run(files: &[(&str, &str)]) -> (Env, SymbolInterner, Diagnostic) {
+    let (_, env, symbols, diagnostic) = analyse(files, CfgSet::new(), false);
+    (env, symbols, diagnostic)
+}
+
+/// Like [`run`], evaluating `#[cfg]` against `cfg` and loading the bundled `std` if asked
+/// to. Also returns the database, to look up the paths of files.
+fn analyse(
+    files: &[(&str, &str)],
+    cfg: CfgSet,
+    std: bool,
+) -> (Database, Env, SymbolInterner, Diagnostic) {
+    let fs = files
+        .iter()
+        .fold(MemoryFileSystem::new(), |fs, &(path, source)| {
+            fs.file(path, source)
+        });
+    let mut db = database(fs);
     let context = Context {
-        root: std::path::PathBuf::from("src/tests/fixtures/lib.dnb"),
+        root: PathBuf::from(files[0].0),
+        cfg,
+        std: std.then(|| db.std()),
     };
-    let (fs, table, symbols, diagnostic) = semantic(context);
+    let (env, symbols, diagnostic) = semantic(&mut db, context);
+
+    (db, env, symbols, diagnostic)
+}
+
+/// Every report, prefixed with its severity.
+fn messages(diagnostic: &Diagnostic) -> Vec<String> {
+    diagnostic
+        .iter()
+        .map(|report| match report.severity() {
+            Some(miette::Severity::Warning) => format!("warning: {}", report),
+            _ => format!("error: {}", report),
+        })
+        .collect()
+}
+
+/// The literals that constants are initialized with, directly or as an operand of a binary
+/// expression.
+fn constant_literals(env: &Env) -> Vec<(&Definition, &hir::Literal)> {
+    let mut literals = vec![];
+    for (_, definition) in env.definitions() {
+        let hir::DefinitionKind::Constant {
+            initializer: Some(initializer),
+            ..
+        } = &definition.definition.kind
+        else {
+            continue;
+        };
+        let operands = match &initializer.kind {
+            hir::ExpressionKind::Binary { left, right, .. } => vec![&**left, &**right],
+            _ => vec![initializer],
+        };
+        for operand in operands {
+            if let hir::ExpressionKind::Literal { value } = &operand.kind {
+                literals.push((definition, value));
+            }
+        }
+    }
+
+    literals
+}
+
+#[test]
+fn all_files() {
+    let (db, table, symbols, diagnostic) = analyse(
+        &[
+            (
+                "lib.dnb",
+                r#"mod imports;
+mod structs;
+mod enums;
+mod functions;
+
+mod bar {
+  mod baz {
+    mod qux {
+      //
+    }
+  }
+}
+"#,
+            ),
+            (
+                "imports.dnb",
+                r#"use *;
+use a;
+use {};
+use {a::{b::{c::d}}};
+"#,
+            ),
+            (
+                "structs.dnb",
+                r#"struct Foo;
+
+struct Bar(usize);
+
+struct Baz {
+  a: usize,
+}
+"#,
+            ),
+            (
+                "enums.dnb",
+                r#"enum Foo { }
+enum Bar {
+  A,
+  B = 1,
+  C(Foo),
+  D {
+    a: usize,
+  },
+}
+"#,
+            ),
+            (
+                "functions.dnb",
+                r#"fn log();
+
+fn add(lhs: usize, rhs: usize) -> usize {
+    let sum: usize = lhs;
+    sum
+}
+
+struct Wrapper(usize);
+
+type Alias = Wrapper;
+
+trait Trait {
+    fn call(&self, value: usize) -> usize;
+    type Output;
+    const VALUE: usize;
+}
+
+impl Trait for Wrapper {
+    fn call(&self, value: usize) -> usize {
+        value
+    }
+
+    type Output = Wrapper;
+
+    const VALUE: usize = 1;
+}
+"#,
+            ),
+        ],
+        CfgSet::new(),
+        false,
+    );
 
-    insta::assert_debug_snapshot!(fs);
+    insta::assert_debug_snapshot!(db.fs());
     insta::assert_debug_snapshot!(table);
     insta::assert_debug_snapshot!(symbols);
     insta::assert_debug_snapshot!(diagnostic);
 }
+
+#[test]
+fn incremental() {
+    let mut db = Database::new();
+    let file = db.krate(PathBuf::from("lib.dnb"));
+
+    db.set_source(file, "fn foo() {}".to_owned());
+    let parse = db.parse(file);
+    assert!(parse.is_some());
+
+    let executed = db.executed();
+    assert_eq!(db.parse(file), parse);
+    assert_eq!(db.executed(), executed);
+
+    // Writing the same source again is not a change.
+    db.set_source(file, "fn foo() {}".to_owned());
+    assert_eq!(db.parse(file), parse);
+    assert_eq!(db.executed(), executed);
+
+    db.set_source(file, "const A: char = '';".to_owned());
+    let parse = db.parse(file).unwrap();
+    assert_eq!(db.executed(), executed + 1);
+    assert_eq!(
+        parse.errors(),
+        [SyntaxError {
+            message: "Empty character literal".to_owned(),
+            severity: None,
+            labels: vec![(16..18, None)],
+        }]
+    );
+}
+
+#[test]
+fn overlay() {
+    let fs = MemoryFileSystem::new()
+        .file("lib.dnb", "mod foo;")
+        .file("foo.dnb", "struct Foo;");
+    let mut db = database(fs);
+    let file = db.krate(PathBuf::from("lib.dnb"));
+    let foo = db.module(file, "foo").unwrap();
+
+    db.set_source(foo, "struct Bar;".to_owned());
+    assert_eq!(db.fs().path(foo), Some(&PathBuf::from("foo.dnb")));
+
+    let context = Context {
+        root: PathBuf::from("lib.dnb"),
+        cfg: CfgSet::new(),
+        std: None,
+    };
+    let (env, symbols, diagnostic) = semantic(&mut db, context);
+    assert!(diagnostic.is_empty());
+    let names: Vec<_> = env
+        .definitions()
+        .map(|(_, definition)| &symbols[definition.definition.name.symbol])
+        .collect();
+    assert_eq!(names, ["foo", "Bar"]);
+}
+
+#[test]
+fn canonical_duplicate() {
+    let mut fs = Fs::with(MemoryFileSystem::new().file("src/lib.dnb", ""));
+    let lhs = fs.krate(PathBuf::from("src/lib.dnb"));
+    let rhs = fs.krate(PathBuf::from("src/../src/./lib.dnb"));
+
+    assert_eq!(lhs, rhs);
+    assert!(fs.same_file(lhs, rhs));
+}
+
+#[test]
+fn overlay_paths() {
+    let mut fs = Fs::with(MemoryFileSystem::new().file("src/lib.dnb", "struct A;"));
+    let file = fs.krate(PathBuf::from("./src/lib.dnb"));
+    assert_eq!(fs.krate(PathBuf::from("src/lib.dnb")), file);
+
+    fs.overlay(PathBuf::from("src/lib.dnb"), "struct B;".to_owned());
+    assert_eq!(fs.source(file), Some("struct B;"));
+    fs.overlay(PathBuf::from("./src/lib.dnb"), "struct C;".to_owned());
+    assert_eq!(fs.source(file), Some("struct C;"));
+
+    // A module that only exists as an overlay.
+    fs.overlay(PathBuf::from("src/new.dnb"), "struct D;".to_owned());
+    let new = fs.module(file, "new").unwrap();
+    assert_eq!(fs.source(new), Some("struct D;"));
+
+    assert_eq!(
+        fs.remove_overlay(Path::new("./src/lib.dnb")).as_deref(),
+        Some("struct C;")
+    );
+    assert_eq!(fs.source(file), Some("struct A;"));
+}
+
+#[test]
+fn krates() {
+    let (db, env, symbols, diagnostic) = analyse(
+        &[
+            (
+                "app/Danube.toml",
+                r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+util = { path = "../util" }
+"#,
+            ),
+            (
+                "app/src/lib.dnb",
+                "use ::util::math::add;\nuse ::util::Point;\nuse crate::local::Thing;\nuse ::app::Thing;\nmod local { struct Thing; }\n",
+            ),
+            (
+                "util/Danube.toml",
+                "[package]\nname = \"util\"\nversion = \"1.0.0\"\n",
+            ),
+            ("util/src/lib.dnb", "mod math;\nstruct Point;\n"),
+            ("util/src/math.dnb", "fn add() {}\n"),
+        ],
+        CfgSet::new(),
+        false,
+    );
+    assert!(diagnostic.is_empty(), "{:?}", diagnostic);
+
+    let names: Vec<_> = env
+        .krates()
+        .map(|(_, krate)| &symbols[krate.name])
+        .collect();
+    assert_eq!(names, ["util", "app"]);
+
+    let (_, app) = env
+        .krates()
+        .find(|(_, krate)| &symbols[krate.name] == "app")
+        .unwrap();
+    let scope = env[app.root].scope;
+    let bindings: Vec<_> = env[scope]
+        .imports()
+        .iter()
+        .map(|import| match import.path.binding {
+            hir::Binding::Definition(definition) => {
+                let definition = &env[definition];
+                let path = db.fs().path(definition.file).unwrap();
+                Some((&symbols[definition.definition.name.symbol], path.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        bindings,
+        [
+            Some(("add", PathBuf::from("app/../util/src/math.dnb"))),
+            Some(("Point", PathBuf::from("app/../util/src/lib.dnb"))),
+            Some(("Thing", PathBuf::from("app/src/lib.dnb"))),
+            // A crate cannot name itself through `::`.
+            None,
+        ]
+    );
+}
+
+#[test]
+fn krate_cycle() {
+    let (env, _, diagnostic) = run(&[
+        (
+            "a/Danube.toml",
+            "[package]\nname = \"a\"\nversion = \"0.1.0\"\n[dependencies]\nb = { path = \"../b\" }\n",
+        ),
+        ("a/src/lib.dnb", ""),
+        (
+            "b/Danube.toml",
+            "[package]\nname = \"b\"\nversion = \"0.1.0\"\n[dependencies]\na = { path = \"../a\" }\n",
+        ),
+        ("b/src/lib.dnb", ""),
+    ]);
+
+    assert_eq!(
+        messages(&diagnostic),
+        ["error: Cyclic dependency on \"a/../b/../a/Danube.toml\""]
+    );
+    assert_eq!(env.krates().count(), 2);
+}
+
+#[test]
+fn module_paths() {
+    let (db, env, _, diagnostic) = analyse(
+        &[
+            (
+                "lib.dnb",
+                "mod a;\n#[path = \"other/c.dnb\"]\nmod c;\nmod missing;\nmod both;\n",
+            ),
+            ("a.dnb", "mod b;\n"),
+            ("a/b.dnb", "struct B;\n"),
+            ("other/c.dnb", "mod d;\n"),
+            ("other/d.dnb", "struct D;\n"),
+            ("both.dnb", ""),
+            ("both/mod.dnb", ""),
+        ],
+        CfgSet::new(),
+        false,
+    );
+
+    assert_eq!(
+        messages(&diagnostic),
+        [
+            r#"error: Module 'missing' not found, tried "missing.dnb" and "missing/mod.dnb""#,
+            r#"error: Module 'both' is ambiguous, found both "both.dnb" and "both/mod.dnb""#,
+        ]
+    );
+
+    let mut files: Vec<_> = env
+        .modules()
+        .map(|(_, module)| db.fs().path(module.file).unwrap().clone())
+        .collect();
+    files.sort();
+    assert_eq!(
+        files,
+        ["a/b.dnb", "a.dnb", "lib.dnb", "other/c.dnb", "other/d.dnb"].map(PathBuf::from)
+    );
+}
+
+#[test]
+fn top_level_attributes() {
+    let (env, _, diagnostic) = run(&[
+        (
+            "lib.dnb",
+            r#"#![no_prelude]
+#![edition = "2024"]
+#![allow(unused, dead_code)]
+#![no_prelude]
+#![edition("2024")]
+#![a("value")]
+#![a::b(key = "value")]
+
+mod foo;
+"#,
+        ),
+        (
+            "foo.dnb",
+            r#"#![deny(warnings)]
+#![edition = "2024"]
+#![allow(unused = "yes")]
+"#,
+        ),
+    ]);
+
+    let reports = messages(&diagnostic);
+    insta::assert_debug_snapshot!(reports);
+
+    let attributes: Vec<_> = env
+        .modules()
+        .map(|(_, module)| module.attributes.len())
+        .collect();
+    assert_eq!(attributes, [7, 3]);
+}
+
+#[test]
+fn builtin_attributes() {
+    let (_, _, diagnostic) = run(&[
+        (
+            "lib.dnb",
+            r#"mod old;
+use old::legacy;
+use old::Gone;
+
+#[test]
+fn check() {}
+
+#[inline]
+#[inline(always)]
+fn fast() {}
+
+#[inline(sometimes)]
+fn slow() {}
+
+#[test]
+struct NotAFunction;
+
+#[cfg(all(feature = "x", not(windows)))]
+#[cfg(any())]
+fn configured() {}
+
+#[cfg(a, b)]
+fn bad_cfg() {}
+
+#[deprecated(since = "0.2.0", reason = "no")]
+fn bad_deprecated() {}
+
+#[path = "old.dnb"]
+fn not_a_module() {}
+
+#[unknown]
+fn unknown() {}
+"#,
+        ),
+        (
+            "old.dnb",
+            r#"#[deprecated = "use `fresh` instead"]
+fn legacy() {}
+
+#[deprecated(since = "0.1.0")]
+struct Gone;
+"#,
+        ),
+    ]);
+
+    let reports = messages(&diagnostic);
+    insta::assert_debug_snapshot!(reports);
+}
+
+#[test]
+fn deprecated_uses() {
+    let (_, _, diagnostic) = run(&[
+        (
+            "lib.dnb",
+            r#"mod old;
+use old::legacy;
+
+fn main() {
+    old::legacy();
+    legacy();
+    let legacy = 1;
+    legacy;
+    let gone: old::Gone = old::Gone;
+    {
+        fn legacy() {}
+        legacy();
+    }
+}
+
+#[deprecated]
+fn also_old() {
+    old::legacy();
+}
+"#,
+        ),
+        (
+            "old.dnb",
+            r#"#[deprecated = "use `fresh` instead"]
+fn legacy() {}
+
+#[deprecated(since = "0.1.0")]
+struct Gone;
+"#,
+        ),
+    ]);
+
+    let mut reports = vec![];
+    for report in diagnostic.iter() {
+        let labels: Vec<_> = report.labels().into_iter().flatten().collect();
+        if labels.is_empty() {
+            reports.push((report.to_string(), None));
+        }
+        for label in labels {
+            let source = report.source_code().unwrap();
+            let contents = source.read_span(label.inner(), 0, 0).unwrap();
+            let line = contents.line() + 1;
+            let text = std::str::from_utf8(contents.data()).unwrap();
+            reports.push((report.to_string(), Some(format!("{}:{}", line, text))));
+        }
+    }
+    assert_eq!(
+        reports,
+        [
+            (
+                "Use of deprecated item `legacy`: use `fresh` instead".to_owned(),
+                None
+            ),
+            (
+                "Use of deprecated item `legacy`: use `fresh` instead".to_owned(),
+                Some("5:legacy".to_owned())
+            ),
+            (
+                "Use of deprecated item `legacy`: use `fresh` instead".to_owned(),
+                Some("6:legacy".to_owned())
+            ),
+            (
+                "Use of deprecated item `Gone` (since 0.1.0)".to_owned(),
+                Some("9:Gone".to_owned())
+            ),
+            (
+                "Use of deprecated item `Gone` (since 0.1.0)".to_owned(),
+                Some("9:Gone".to_owned())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn cfg() {
+    let cfg = CfgSet::new().flag("unix").value("feature", "x");
+    let (_, env, symbols, diagnostic) = analyse(
+        &[
+            (
+                "lib.dnb",
+                r#"#[cfg(feature = "x")]
+mod x;
+
+#[cfg(not(feature = "x"))]
+mod missing;
+
+#[cfg(all(unix, any(feature = "y", feature = "x")))]
+fn both() {}
+
+#[cfg(windows)]
+fn windows_only() {}
+
+trait Platform {
+    #[cfg(unix)]
+    fn unix();
+    #[cfg(windows)]
+    fn windows();
+}
+"#,
+            ),
+            ("x.dnb", "fn from_x() {}\n"),
+        ],
+        cfg,
+        false,
+    );
+    assert!(diagnostic.is_empty(), "{:?}", diagnostic);
+
+    let mut definitions: Vec<_> = env
+        .definitions()
+        .map(|(_, definition)| &symbols[definition.definition.name.symbol])
+        .collect();
+    definitions.sort();
+    assert_eq!(definitions, ["Platform", "both", "from_x", "unix", "x"]);
+    assert_eq!(env.modules().count(), 2);
+}
+
+#[test]
+fn cfg_options() {
+    let mut cfg = CfgSet::new();
+    assert_eq!(cfg.parse("unix"), Ok(()));
+    assert_eq!(cfg.parse("feature=\"x\""), Ok(()));
+    assert!(cfg.parse("feature=x").is_err());
+    assert!(cfg.parse("1x").is_err());
+
+    assert_eq!(cfg, CfgSet::new().flag("unix").value("feature", "x"));
+}
+
+#[test]
+fn attribute_targets() {
+    let (_, env, symbols, diagnostic) = analyse(
+        &[(
+            "lib.dnb",
+            r#"struct S {
+    #[cfg(unix)]
+    a: i32,
+    #[cfg(windows)]
+    b: i32,
+    #[deprecated]
+    c: i32,
+}
+
+enum E {
+    #[cfg(windows)]
+    A,
+    #[test]
+    B,
+    C { #[cfg(windows)] d: i32 },
+}
+
+fn f(#[cfg(windows)] a: i32, #[allow(unused)] b: i32) {
+    #[cfg(windows)]
+    fn g() {}
+    #[cfg(windows)]
+    {
+        f(1);
+    }
+    #[inline]
+    {}
+}
+"#,
+        )],
+        CfgSet::new().flag("unix"),
+        false,
+    );
+
+    let mut reports = vec![];
+    for report in diagnostic.iter() {
+        let source = report.source_code().unwrap();
+        for label in report.labels().unwrap() {
+            let contents = source.read_span(label.inner(), 0, 0).unwrap();
+            let text = std::str::from_utf8(contents.data()).unwrap().to_owned();
+            reports.push((report.to_string(), text));
+        }
+    }
+    assert_eq!(
+        reports,
+        [
+            (
+                "`#[deprecated]` cannot be applied to a field".to_owned(),
+                "#[deprecated]".to_owned()
+            ),
+            (
+                "`#[test]` cannot be applied to an enum variant".to_owned(),
+                "#[test]".to_owned()
+            ),
+            (
+                "`#[inline]` cannot be applied to a statement".to_owned(),
+                "#[inline]".to_owned()
+            ),
+        ]
+    );
+
+    let mut names = vec![];
+    for (_, definition) in env.definitions() {
+        match &definition.definition.kind {
+            hir::DefinitionKind::Struct {
+                body: hir::StructBody::Named(fields),
+                ..
+            } => names.extend(fields.iter().map(|(_, _, name, _)| &symbols[name.symbol])),
+            hir::DefinitionKind::Enum { variants, .. } => {
+                for variant in variants {
+                    names.push(&symbols[variant.name.symbol]);
+                    if let hir::EnumVariantKind::Named(fields) = &variant.kind {
+                        assert!(fields.is_empty());
+                    }
+                }
+            }
+            hir::DefinitionKind::Function {
+                parameters, body, ..
+            } => {
+                names.push(&symbols[definition.definition.name.symbol]);
+                assert_eq!(parameters.len(), 1);
+                // Only `#[inline] {}` is left of the body.
+                assert_eq!(body.as_ref().map(Vec::len), Some(1));
+            }
+            _ => {}
+        }
+    }
+    names.sort();
+    assert_eq!(names, ["B", "C", "a", "c", "f"]);
+}
+
+#[test]
+fn test_functions() {
+    let (_, env, symbols, mut diagnostic) = analyse(
+        &[
+            (
+                "lib.dnb",
+                r#"mod math;
+
+#[test]
+fn smoke() {}
+
+#[test]
+#[should_fail = "overflow"]
+fn overflows() {}
+
+#[should_fail]
+fn not_a_test() {}
+
+#[test]
+fn with_parameter(x: i32) {}
+
+#[cfg(not(test))]
+#[test]
+fn hidden() {}
+"#,
+            ),
+            (
+                "math.dnb",
+                r#"mod inner {
+    #[test]
+    fn add() {}
+}
+
+#[test]
+#[should_fail]
+fn divide_by_zero() {}
+"#,
+            ),
+        ],
+        CfgSet::new().flag("test"),
+        false,
+    );
+
+    let tests = crate::tests(&env, &symbols, "", &mut diagnostic);
+    let tests: Vec<_> = tests
+        .iter()
+        .map(|test| format!("{} {:?}", test.path, test.expectation))
+        .collect();
+    assert_eq!(
+        tests,
+        [
+            "lib::math::divide_by_zero Fail(None)",
+            "lib::math::inner::add Pass",
+            "lib::overflows Fail(Some(\"overflow\"))",
+            "lib::smoke Pass",
+        ]
+    );
+
+    assert_eq!(
+        messages(&diagnostic),
+        [
+            "warning: `#[should_fail]` on `not_a_test` has no effect without `#[test]`",
+            "error: Test function `with_parameter` cannot take parameters",
+        ]
+    );
+
+    let mut diagnostic = Diagnostic::new();
+    let tests = crate::tests(&env, &symbols, "math::", &mut diagnostic);
+    let paths: Vec<_> = tests.iter().map(|test| test.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        ["lib::math::divide_by_zero", "lib::math::inner::add"]
+    );
+}
+
+/// Runs every test in `source`, a crate that sees `std`, and describes how each went.
+fn run_tests(source: &str) -> Vec<String> {
+    let (_, env, symbols, mut diagnostic) =
+        analyse(&[("lib.dnb", source)], CfgSet::new().flag("test"), true);
+    let tests = crate::tests(&env, &symbols, "", &mut diagnostic);
+    assert!(diagnostic.is_empty(), "{:?}", diagnostic);
+
+    tests
+        .iter()
+        .map(|test| {
+            let (outcome, output) = crate::run(&env, &symbols, test);
+            format!("{} {:?} {:?}", test.path, outcome, output)
+        })
+        .collect()
+}
+
+#[test]
+fn interpret() {
+    let outcomes = run_tests(
+        r#"const LIMIT: u8 = 250;
+
+fn fibonacci(n: u64) -> u64 {
+    if n < 2 {
+        return n;
+    }
+    fibonacci(n - 1) + fibonacci(n - 2)
+}
+
+fn sum(values: [i32]) -> i32 {
+    let mut total = 0;
+    for value in values {
+        total += value;
+    }
+    total
+}
+
+#[test]
+fn arithmetic() {
+    assert(fibonacci(20) == 6765);
+    assert(sum([1, 2, 3]) == 6);
+    assert(-7 / 2 == -3 && -7 % 2 == -1);
+    assert(2 ** 10 == 1024 && 1 << 4 == 16);
+    assert(255u8 +| 1 == 255 && 255u8 +% 1 == 0 && -128i8 -% 1 == 127);
+    assert(-1i8 >>> 4 == 15 && -16i8 >> 2 == -4);
+    assert(LIMIT +| 10 == 255);
+}
+
+#[test]
+fn control_flow() {
+    let mut count = 0;
+    let mut i = 0;
+    while i < 10 {
+        i += 1;
+        if i % 2 == 0 {
+            continue;
+        }
+        count += 1;
+    }
+    let found = loop {
+        break;
+    };
+    let mut squares = [0, 0, 0];
+    for i in [0, 1, 2] {
+        squares[i] = i * i;
+    }
+    let kind = match count {
+        0 => "none",
+        1 | 2 => "few",
+        n => "many",
+    };
+    println("${count} ${kind} ${squares}");
+    assert(squares == [0, 1, 4] && found == ());
+}
+
+#[test]
+fn fails() {
+    let values = [1, 2];
+    assert(values[0] == 2);
+}
+
+#[test]
+#[should_fail = "overflow"]
+fn overflows() {
+    let x: u8 = 255;
+    x + 1;
+}
+
+#[test]
+#[should_fail = "out of bounds"]
+fn wrong_message() {
+    panic("no")
+}
+
+#[test]
+#[should_fail]
+fn does_not_fail() {}
+
+#[test]
+fn unsupported() {
+    "text".len();
+}
+
+#[test]
+fn forever() {
+    loop {}
+}
+"#,
+    );
+
+    assert_eq!(
+        outcomes,
+        [
+            "lib::arithmetic Passed \"\"",
+            "lib::control_flow Passed \"5 many [0, 1, 4]\\n\"",
+            "lib::does_not_fail Failed(\"the test did not panic\") \"\"",
+            "lib::fails Failed(\"panicked at lib.dnb:59:5: assertion failed: values[0] == 2\") \"\"",
+            "lib::forever Failed(\"stopped: the test did not finish within 10000000 steps\") \"\"",
+            "lib::overflows Passed \"\"",
+            "lib::unsupported Failed(\"cannot interpret the test: method calls are not supported\") \"\"",
+            "lib::wrong_message Failed(\"panicked at lib.dnb:72:5: no\\nthe panic message does not contain `out of bounds`\") \"\"",
+        ]
+    );
+}
+
+#[test]
+fn interpret_recursion_limit() {
+    let outcomes = run_tests("fn f(n: i32) -> i32 { f(n + 1) }\n\n#[test]\nfn deep() { f(0); }\n");
+
+    assert_eq!(
+        outcomes,
+        ["lib::deep Failed(\"stopped: calls nested more than 128 deep\") \"\""]
+    );
+}
+
+#[test]
+fn operators() {
+    let (env, _, diagnostic) = run(&[(
+        "lib.dnb",
+        "const A: bool = 1 == 2;\nconst B: u8 = 1 +| 2;\nconst C: i32 = 1 >>> 2;\nfn f() {\n    let mut x = 0;\n    x += 1;\n    x **%= 2;\n}\n",
+    )]);
+    assert!(diagnostic.is_empty(), "{:?}", diagnostic);
+
+    let mut operators = vec![];
+    for (_, definition) in env.definitions() {
+        match &definition.definition.kind {
+            hir::DefinitionKind::Constant {
+                initializer: Some(initializer),
+                ..
+            } => {
+                if let hir::ExpressionKind::Binary { operator, .. } = &initializer.kind {
+                    operators.push(format!("{:?}", operator));
+                }
+            }
+            hir::DefinitionKind::Function {
+                body: Some(body), ..
+            } => {
+                for statement in body {
+                    if let hir::StatementKind::Expression { value, .. } = &statement.kind
+                        && let hir::ExpressionKind::Assignment { operator, .. } = &value.kind
+                    {
+                        operators.push(format!("{:?}", operator));
+                    }
+                }
+            }
+            _ => {
+                //
+            }
+        }
+    }
+    operators.sort();
+    assert_eq!(
+        operators,
+        [
+            "Add",
+            "Equal",
+            "RightShiftUnsigned",
+            "SaturatingAdd",
+            "WrappingExponent"
+        ]
+    );
+}
+
+#[test]
+fn prelude() {
+    let (db, env, mut symbols, diagnostic) = analyse(
+        &[
+            (
+                "lib.dnb",
+                "mod inner;\nstruct String;\nuse ::std::fmt::Formatter;\nuse print as write;\n",
+            ),
+            ("inner.dnb", ""),
+        ],
+        CfgSet::new(),
+        true,
+    );
+    assert!(diagnostic.is_empty(), "{:?}", diagnostic);
+
+    let (_, lib) = env
+        .krates()
+        .find(|(_, krate)| &symbols[krate.name] == "lib")
+        .unwrap();
+    let root = lib.root;
+    let inner = env[root][symbols.intern("inner")];
+    let mut resolve = |module, name: &str| match env.resolve_name(module, symbols.intern(name)) {
+        Some(Resolved::Definition(definition)) => {
+            let path = db.fs().path(env[definition].file).unwrap();
+            Some(path.to_string_lossy().into_owned())
+        }
+        _ => None,
+    };
+
+    assert_eq!(
+        resolve(root, "println").as_deref(),
+        Some("<std>/src/io.dnb")
+    );
+    assert_eq!(resolve(root, "write").as_deref(), Some("<std>/src/io.dnb"));
+    // Items of the module shadow the prelude, but only in that module.
+    assert_eq!(resolve(root, "String").as_deref(), Some("lib.dnb"));
+    assert_eq!(
+        resolve(inner, "String").as_deref(),
+        Some("<std>/src/string.dnb")
+    );
+    assert_eq!(resolve(root, "Unknown"), None);
+}
+
+#[test]
+fn no_prelude() {
+    let (_, env, mut symbols, diagnostic) = analyse(
+        &[("lib.dnb", "#![no_prelude]\nuse ::std::string::String;\n")],
+        CfgSet::new(),
+        true,
+    );
+    assert!(diagnostic.is_empty(), "{:?}", diagnostic);
+
+    let (_, lib) = env
+        .krates()
+        .find(|(_, krate)| &symbols[krate.name] == "lib")
+        .unwrap();
+    assert!(lib.prelude.is_none());
+    assert!(matches!(
+        env.resolve_name(lib.root, symbols.intern("String")),
+        Some(Resolved::Definition(_))
+    ));
+    assert_eq!(env.resolve_name(lib.root, symbols.intern("println")), None);
+}
+
+#[test]
+fn primitives() {
+    let (env, mut symbols, diagnostic) = run(&[
+        (
+            "lib.dnb",
+            r#"mod shadow;
+const A: u8 = 300;
+const B: i8 = -128;
+const C: i8 = -129;
+static D: u64 = 18446744073709551615;
+fn f() -> u16 {
+    let x: usize = 1;
+    return 70000;
+}
+"#,
+        ),
+        ("shadow.dnb", "struct u32;\nconst E: u32 = 5000000000;\n"),
+    ]);
+
+    assert_eq!(
+        messages(&diagnostic),
+        [
+            "error: Literal `300` out of range for `u8`, whose range is `0..=255`",
+            "error: Literal `-129` out of range for `i8`, whose range is `-128..=127`",
+            "error: Literal `70000` out of range for `u16`, whose range is `0..=65535`",
+        ]
+    );
+
+    let (_, lib) = env.krates().next().unwrap();
+    let root = lib.root;
+    let shadow = env[root][symbols.intern("shadow")];
+    assert_eq!(
+        env.resolve_name(root, symbols.intern("u32")),
+        Some(Resolved::Primitive(Primitive::U32))
+    );
+    assert!(matches!(
+        env.resolve_name(shadow, symbols.intern("u32")),
+        Some(Resolved::Definition(_))
+    ));
+
+    assert_eq!(Primitive::I8.range(), Some((-128, 127)));
+    assert_eq!(Primitive::U128.range(), Some((0, u128::MAX)));
+    assert_eq!(Primitive::F32.range(), None);
+    assert_eq!(Primitive::Usize.r#type().size, Some(8));
+    assert_eq!(Primitive::Str.r#type().size, None);
+}
+
+#[test]
+fn numeric_suffixes() {
+    let (env, _, diagnostic) = run(&[(
+        "lib.dnb",
+        "const A: u8 = 300u8;\nconst B: u16 = 255 + 1u8;\nconst C = 1f32;\nconst D = 1.0u8;\n",
+    )]);
+
+    assert_eq!(
+        messages(&diagnostic),
+        [
+            "error: Invalid suffix `u8` on float literal",
+            "error: Literal `300` out of range for `u8`, whose range is `0..=255`",
+        ]
+    );
+    let labels: Vec<_> = diagnostic
+        .iter()
+        .next()
+        .unwrap()
+        .labels()
+        .unwrap()
+        .map(|label| (label.offset(), label.len()))
+        .collect();
+    assert_eq!(labels, [(76, 2)]);
+
+    let mut types: Vec<_> = constant_literals(&env)
+        .into_iter()
+        .map(|(definition, literal)| env.literal_type(definition.file, literal.span))
+        .collect();
+    types.sort_by_key(|r#type| r#type.map(Primitive::name));
+    assert_eq!(
+        types,
+        [
+            Some(Primitive::F32),
+            Some(Primitive::U16),
+            Some(Primitive::U8),
+            Some(Primitive::U8)
+        ]
+    );
+}
+
+#[test]
+fn integer_limits() {
+    let u128 = "whose range is `0..=340282366920938463463374607431768211455`";
+    let i128 = "whose range is `-170141183460469231731687303715884105728..=170141183460469231731687303715884105727`";
+    let cases = [
+        ("const A = 0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFu128;", None),
+        (
+            "const B: i128 = -170141183460469231731687303715884105728;",
+            None,
+        ),
+        (
+            "const C = 0x1FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFu128;",
+            Some((
+                format!(
+                    "Literal `0x1FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF` out of range for `u128`, {u128}"
+                ),
+                "0x1FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFu128",
+            )),
+        ),
+        (
+            "const D = 340282366920938463463374607431768211456;",
+            Some((
+                format!(
+                    "Literal `340282366920938463463374607431768211456` out of range for `u128`, {u128}"
+                ),
+                "340282366920938463463374607431768211456",
+            )),
+        ),
+        (
+            "const E: i128 = -170141183460469231731687303715884105729;",
+            Some((
+                format!(
+                    "Literal `-170141183460469231731687303715884105729` out of range for `i128`, {i128}"
+                ),
+                "170141183460469231731687303715884105729",
+            )),
+        ),
+        (
+            "const F = 0b100000000u8;",
+            Some((
+                "Literal `256` out of range for `u8`, whose range is `0..=255`".to_owned(),
+                "0b100000000u8",
+            )),
+        ),
+    ];
+
+    for (source, expected) in cases {
+        let (_, _, diagnostic) = run(&[("lib.dnb", source)]);
+
+        let reports: Vec<_> = diagnostic
+            .iter()
+            .map(|report| {
+                let label = report.labels().unwrap().next().unwrap();
+                let range = label.offset()..label.offset() + label.len();
+                (report.to_string(), &source[range])
+            })
+            .collect();
+        assert_eq!(reports, Vec::from_iter(expected), "{source}");
+    }
+}
+
+#[test]
+fn byte_and_c_strings() {
+    let (env, symbols, diagnostic) = run(&[
+        (
+            "lib.dnb",
+            r##"mod f;
+mod g;
+mod h;
+mod i;
+const A = b'a';
+const B = b'\xFF';
+const C = b"a\x00\n";
+const D = br#"r"#;
+const E = c"h\u{E9}";
+"##,
+        ),
+        ("f.dnb", "const F = b'é';\n"),
+        ("g.dnb", "const G = b\"\\u{41}\";\n"),
+        ("h.dnb", "const H = c\"a\\x00\";\n"),
+        ("i.dnb", "const I = b\"\\xG0\";\n"),
+    ]);
+
+    assert_eq!(
+        messages(&diagnostic),
+        [
+            "error: Non-ASCII character `é` in byte literal",
+            "error: Unicode escape in byte string literal",
+            "error: Nul byte in C string literal",
+            "error: Invalid escape sequence `\\x` in byte string literal",
+        ]
+    );
+    let labels: Vec<_> = diagnostic
+        .iter()
+        .flat_map(|report| report.labels().unwrap())
+        .map(|label| (label.offset(), label.len()))
+        .collect();
+    assert_eq!(labels, [(12, 2), (12, 6), (13, 4), (12, 2)]);
+
+    let mut values = vec![];
+    for (definition, literal) in constant_literals(&env) {
+        let value = match &literal.kind {
+            hir::LiteralKind::Byte { value } => vec![*value],
+            hir::LiteralKind::ByteString { value } | hir::LiteralKind::CString { value } => {
+                value.clone()
+            }
+            _ => continue,
+        };
+        values.push((symbols[definition.definition.name.symbol].to_owned(), value));
+    }
+    values.sort();
+    assert_eq!(
+        values,
+        [
+            ("A".to_owned(), b"a".to_vec()),
+            ("B".to_owned(), vec![0xFF]),
+            ("C".to_owned(), b"a\0\n".to_vec()),
+            ("D".to_owned(), b"r".to_vec()),
+            ("E".to_owned(), "h\u{E9}".as_bytes().to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn escapes() {
+    let (env, symbols, diagnostic) = run(&[
+        (
+            "lib.dnb",
+            r#"mod a;
+mod b;
+mod c;
+mod d;
+mod e;
+const A = '\0';
+const B = '\x41';
+const C = "\$\${x}\r\n\t\\\"\'\u{1F600}";
+"#,
+        ),
+        ("a.dnb", r"const A = '\q';"),
+        ("b.dnb", r"const B = '\x80';"),
+        ("c.dnb", r#"const C = "a\u{D800}";"#),
+        ("d.dnb", r#"const D = "\u{110000}";"#),
+        ("e.dnb", r"const E = '\x4';"),
+    ]);
+
+    let reports: Vec<_> = diagnostic
+        .iter()
+        .map(|report| {
+            let labels: Vec<_> = report
+                .labels()
+                .unwrap()
+                .map(|label| {
+                    (
+                        label.offset(),
+                        label.len(),
+                        label.label().unwrap().to_owned(),
+                    )
+                })
+                .collect();
+            (report.to_string(), labels)
+        })
+        .collect();
+    assert_eq!(
+        reports,
+        [
+            (
+                r"Invalid escape sequence `\q` in character literal".to_owned(),
+                vec![(11, 2, "unknown escape".to_owned())]
+            ),
+            (
+                r"Escape sequence `\x80` out of range in character literal".to_owned(),
+                vec![(11, 4, r"must be at most `\x7F`".to_owned())]
+            ),
+            (
+                r"Invalid unicode escape `\u{D800}` in string literal".to_owned(),
+                vec![(12, 8, "surrogates are not characters".to_owned())]
+            ),
+            (
+                r"Invalid unicode escape `\u{110000}` in string literal".to_owned(),
+                vec![(11, 10, "must be at most `10FFFF`".to_owned())]
+            ),
+            (
+                r"Invalid escape sequence `\x4` in character literal".to_owned(),
+                vec![(11, 3, "expected two hex digits".to_owned())]
+            ),
+        ]
+    );
+
+    let mut values = vec![];
+    for (definition, literal) in constant_literals(&env) {
+        let value = match &literal.kind {
+            hir::LiteralKind::Character { value } => value.to_string(),
+            hir::LiteralKind::String { segments } => segments
+                .iter()
+                .map(|segment| match segment {
+                    hir::StringSegment::Text { value } => value.clone(),
+                    hir::StringSegment::Escape { value }
+                    | hir::StringSegment::Unicode { value } => value.to_string(),
+                    hir::StringSegment::Interpolation { .. } => "<>".to_owned(),
+                })
+                .collect(),
+            _ => continue,
+        };
+        values.push((symbols[definition.definition.name.symbol].to_owned(), value));
+    }
+    values.sort();
+    assert_eq!(
+        values,
+        [
+            ("A".to_owned(), "\0".to_owned()),
+            ("B".to_owned(), "A".to_owned()),
+            ("C".to_owned(), "$${x}\r\n\t\\\"'\u{1F600}".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn invalid_unicode_escapes() {
+    let (_, _, diagnostic) = run(&[
+        (
+            "lib.dnb",
+            r#"mod a;
+mod b;
+const A: char = '\u{zz}';
+"#,
+        ),
+        ("a.dnb", r#"const A = "\u{4g}";"#),
+        ("b.dnb", "const B = 'ab';"),
+    ]);
+
+    // Only the lexer reports them, and the closing quotes do not open more literals.
+    assert_eq!(
+        messages(&diagnostic),
+        [
+            "error: Invalid digit in unicode escape",
+            "error: Invalid digit in unicode escape",
+            "error: Character literal with more than one character",
+        ]
+    );
+}
+
+#[test]
+fn variant_fields_and_type_aliases() {
+    let (env, symbols, diagnostic) = run(&[(
+        "lib.dnb",
+        "enum E { A(i32, u8), B { x: i32 }, C }\ntype T = i32;\ntrait U { type V; }\n",
+    )]);
+    assert!(diagnostic.is_empty(), "{:?}", messages(&diagnostic));
+
+    let mut fields = vec![];
+    let mut aliases = vec![];
+    for (_, definition) in env.definitions() {
+        match &definition.definition.kind {
+            hir::DefinitionKind::Enum { variants, .. } => {
+                for variant in variants {
+                    let count = match &variant.kind {
+                        hir::EnumVariantKind::Named(fields) => fields.len(),
+                        hir::EnumVariantKind::Unnamed(fields) => fields.len(),
+                        _ => 0,
+                    };
+                    fields.push((symbols[variant.name.symbol].to_owned(), count));
+                }
+            }
+            hir::DefinitionKind::Type { initializer, .. } => {
+                let name = &symbols[definition.definition.name.symbol];
+                aliases.push((name.to_owned(), initializer.is_some()));
+            }
+            _ => {}
+        }
+    }
+    aliases.sort();
+    assert_eq!(
+        fields,
+        [
+            ("A".to_owned(), 2),
+            ("B".to_owned(), 1),
+            ("C".to_owned(), 0)
+        ]
+    );
+    assert_eq!(aliases, [("T".to_owned(), true), ("V".to_owned(), false)]);
+}
+
+#[test]
+fn reports_point_into_their_file() {
+    let (_, _, diagnostic) = run(&[
+        ("lib.dnb", "mod a;\nmod b;\n"),
+        ("a.dnb", "const A: u8 = 300;"),
+        ("b.dnb", "const B = 1.5u8;"),
+    ]);
+
+    let mut spans = vec![];
+    for report in diagnostic.iter() {
+        let source = report.source_code().unwrap();
+        for label in report.labels().unwrap() {
+            let contents = source.read_span(label.inner(), 0, 0).unwrap();
+            let text = std::str::from_utf8(contents.data()).unwrap().to_owned();
+            spans.push((contents.name().unwrap().to_owned(), text));
+        }
+    }
+    spans.sort();
+    assert_eq!(
+        spans,
+        [
+            ("a.dnb".to_owned(), "300".to_owned()),
+            ("b.dnb".to_owned(), "u8".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn diagnostics_by_file() {
+    let (db, _, _, diagnostic) = analyse(
+        &[
+            ("lib.dnb", "mod a;\nmod b;\nmod c;\n"),
+            ("a.dnb", "const A: u8 = 300;"),
+            ("b.dnb", "const B = 1.5u8;"),
+        ],
+        CfgSet::new(),
+        false,
+    );
+
+    let mut errors: Vec<_> = db
+        .diagnostics(&diagnostic)
+        .into_iter()
+        .map(|(file, error)| {
+            let path = file.and_then(|file| db.fs().path(file).cloned());
+            let ranges: Vec<_> = error
+                .labels
+                .into_iter()
+                .map(|(range, _)| (range.start, range.end))
+                .collect();
+            (path, ranges)
+        })
+        .collect();
+    errors.sort();
+    assert_eq!(
+        errors,
+        [
+            (None, vec![]),
+            (Some(PathBuf::from("a.dnb")), vec![(14, 17)]),
+            (Some(PathBuf::from("b.dnb")), vec![(13, 15)]),
+        ]
+    );
+}
+
+#[test]
+fn outline() {
+    let source = r#"
+#[deprecated]
+pub fn f() {
+    fn hidden() {}
+}
+struct Point { x: i32, y: i32 }
+enum E { A, B(i32) }
+mod inner { const C: i32 = 1; }
+trait T { fn g(); }
+impl T for Point { fn g() {} }
+use inner::C;
+"#;
+    let mut db = database(MemoryFileSystem::new().file("lib.dnb", source));
+    let file = db.krate(PathBuf::from("lib.dnb"));
+
+    let outline = db.outline(file);
+    assert_eq!(
+        &source[outline[0].range.clone()],
+        "#[deprecated]\npub fn f() {\n    fn hidden() {}\n}"
+    );
+    assert_eq!(&source[outline[0].selection.clone()], "f");
+    insta::assert_debug_snapshot!(outline);
+}
+
+#[test]
+fn references() {
+    let source =
+        "struct P;\nfn f(p: P) -> P { let x = P; x }\nmod m { pub fn h() {} }\nuse m::h;\n";
+    let (db, env, symbols, diagnostic) = analyse(&[("lib.dnb", source)], CfgSet::new(), false);
+    assert!(diagnostic.is_empty(), "{diagnostic:?}");
+
+    let file = db.fs().lookup(Path::new("lib.dnb")).unwrap();
+    let name = |offset| {
+        env.reference_at(file, offset)
+            .map(|(_, definition)| &symbols[env[definition].definition.name.symbol])
+    };
+    let at = |text: &str, nth| source.match_indices(text).nth(nth).unwrap().0;
+    assert_eq!(name(at("P", 1)), Some("P"));
+    assert_eq!(name(at("P", 3)), Some("P"));
+    assert_eq!(name(at("x", 1)), None);
+    assert_eq!(name(at("h", 1)), Some("h"));
+}
@@ -2,6 +2,8 @@ use fxhash::FxHashMap;
 use indexmap::IndexSet;
 
 slotmap::new_key_type! {
+    pub struct KrateId;
+
     pub struct FileId;
 
     pub struct ModuleId;
@@ -17,7 +19,7 @@ slotmap::new_key_type! {
     pub struct LocalId;
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct SymbolInterner {
     inner: IndexSet<String>,
 }
@@ -4,4 +4,4 @@ pub mod syntax_kind;
 pub use language::*;
 pub use syntax_kind::*;
 
-pub use rowan::ast::AstNode;
+pub use rowan::{GreenNode, GreenToken, NodeOrToken, TextRange, TextSize, ast::AstNode};
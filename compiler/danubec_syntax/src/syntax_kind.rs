@@ -7,7 +7,6 @@ pub enum SyntaxKind {
 
     RAW_IDENTIFIER_START,
     IDENTIFIER,
-    IDENTIFIER_SEGMENT,
     INTEGER_SEGMENT,
     FRACTION_START,
     FRACTION_SEGMENT,
@@ -21,6 +20,7 @@ pub enum SyntaxKind {
     OCTAL_SEGMENT,
     HEX_START,
     HEX_SEGMENT,
+    NUMERIC_SUFFIX,
     CHARACTER_START,
     CHARACTER_SEGMENT,
     CHARACTER_END,
@@ -37,6 +37,10 @@ pub enum SyntaxKind {
     RAW_STRING_START,
     RAW_STRING_SEGMENT,
     RAW_STRING_END,
+    BYTE_START,
+    BYTE_STRING_START,
+    RAW_BYTE_STRING_START,
+    C_STRING_START,
     LINE_COMMENT_START,
     LINE_COMMENT_SEGMENT,
     DOC_COMMENT_START,
@@ -278,179 +282,120 @@ pub enum SyntaxKind {
     /// Any node that represents an error in the syntax tree
     ERROR_NODE,
 
-    // A source file in a krate.
+    // Generated from `danube.ungram` by `cargo test -p danubec_grammar`.
     ROOT_NODE,
-
-    /// Comments and other non-semantic tokens
-    TRIVIA_NODE,
-
-    RAW_NODE,
-
     TOP_LEVEL_ATTRIBUTE_NODE,
     ATTRIBUTE_NODE,
-    ATTRIBUTE_ARGUMENT_NODE,
     EXPRESSION_ATTRIBUTE_ARGUMENT_NODE,
     KEY_VALUE_ATTRIBUTE_ARGUMENT_NODE,
     NESTED_ATTRIBUTE_ARGUMENT_NODE,
-
     DEFINITION_NODE,
     FUNCTION_DEFINITION_NODE,
-    TYPE_DEFINITION_NODE,
     STRUCT_DEFINITION_NODE,
     ENUM_DEFINITION_NODE,
+    USE_DEFINITION_NODE,
+    MODULE_DEFINITION_NODE,
     TRAIT_DEFINITION_NODE,
-    IMPLEMENT_DEFINITION_NODE,
     CONSTANT_DEFINITION_NODE,
     STATIC_DEFINITION_NODE,
-    USE_DEFINITION_NODE,
-    MODULE_DEFINITION_NODE,
+    TYPE_DEFINITION_NODE,
+    IMPLEMENT_DEFINITION_NODE,
     MODULE_DEFINITION_INLINE_NODE,
     MODULE_DEFINITION_EXTERNAL_NODE,
     FUNCTION_BODY_BLOCK_NODE,
     FUNCTION_BODY_UNIT_NODE,
-
-    NEVER_TYPE_NODE,
-    MUTABLE_TYPE_NODE,
-    PATH_TYPE_NODE,
-    SLICE_TYPE_NODE,
-    TUPLE_TYPE_NODE,
-
+    USE_TREE_NODE,
+    USE_TREE_LIST_NODE,
+    USE_TREE_GLOB_NODE,
+    USE_TREE_ELEMENT_NODE,
+    USE_TREE_RENAME_NODE,
+    ASSOCIATED_DEFINITION_NODE,
+    FUNCTION_PARAMETER_NODE,
+    STRUCT_BODY_UNIT_NODE,
+    STRUCT_BODY_NAMED_NODE,
+    STRUCT_BODY_NAMED_FIELD_NODE,
+    STRUCT_BODY_UNNAMED_NODE,
+    STRUCT_BODY_UNNAMED_FIELD_NODE,
+    ENUM_VARIANT_UNIT_NODE,
+    ENUM_VARIANT_SCALAR_NODE,
+    ENUM_VARIANT_NAMED_NODE,
+    ENUM_VARIANT_NAMED_FIELD_NODE,
+    ENUM_VARIANT_UNNAMED_NODE,
+    NEVER_PATTERN_NODE,
+    PLACEHOLDER_PATTERN_NODE,
+    PATH_PATTERN_NODE,
+    MUTABLE_PATTERN_NODE,
+    TUPLE_PATTERN_NODE,
+    ARRAY_PATTERN_NODE,
+    LITERAL_PATTERN_NODE,
+    AT_PATTERN_NODE,
+    OR_PATTERN_NODE,
+    NAMED_PATTERN_NODE,
+    UNNAMED_PATTERN_NODE,
+    NAMED_PATTERN_FIELD_NODE,
     DEFINITION_STATEMENT_NODE,
     EXPRESSION_STATEMENT_NODE,
     LET_STATEMENT_NODE,
     SEMICOLON_STATEMENT_NODE,
-
-    // Top-level expressions
     BREAK_EXPRESSION_NODE,
     CONTINUE_EXPRESSION_NODE,
+    RETURN_EXPRESSION_NODE,
     FOR_EXPRESSION_NODE,
-    IF_EXPRESSION_NODE,
-    LET_EXPRESSION_NODE,
+    WHILE_EXPRESSION_NODE,
     LOOP_EXPRESSION_NODE,
+    IF_EXPRESSION_NODE,
     MATCH_EXPRESSION_NODE,
-    RETURN_EXPRESSION_NODE,
-    WHILE_EXPRESSION_NODE,
-    // Innermost expressions
+    MATCH_ARM_NODE,
+    LET_EXPRESSION_NODE,
     ARRAY_EXPRESSION_NODE,
     TUPLE_EXPRESSION_NODE,
     BLOCK_EXPRESSION_NODE,
     LITERAL_EXPRESSION_NODE,
     PATH_EXPRESSION_NODE,
     UNARY_EXPRESSION_NODE,
-    // Infix expressions
-    ASSIGNMENT_EXPRESSION_NODE,
-    AWAIT_EXPRESSION_NODE,
     BINARY_EXPRESSION_NODE,
+    ASSIGNMENT_EXPRESSION_NODE,
     FUNCTION_CALL_EXPRESSION_NODE,
+    METHOD_CALL_EXPRESSION_NODE,
     FIELD_EXPRESSION_NODE,
     INDEX_EXPRESSION_NODE,
-    RANGE_FROM_TO_EXPRESSION_NODE,
-    RANGE_FROM_EXPRESSION_NODE,
-    RANGE_TO_EXPRESSION_NODE,
-    RANGE_FULL_EXPRESSION_NODE,
-    RANGE_FROM_TO_INCLUSIVE_EXPRESSION_NODE,
-    RANGE_TO_INCLUSIVE_EXPRESSION_NODE,
-    STRUCT_EXPRESSION_NODE,
+    AWAIT_EXPRESSION_NODE,
     TRY_EXPRESSION_NODE,
     YIELD_EXPRESSION_NODE,
-    METHOD_CALL_EXPRESSION_NODE,
-
-    PATTERN_NODE,
-    NEVER_PATTERN_NODE,
-    PLACEHOLDER_PATTERN_NODE,
-    PATH_PATTERN_NODE,
-    MUTABLE_PATTERN_NODE,
-    TUPLE_PATTERN_NODE,
-    ARRAY_PATTERN_NODE,
-    LITERAL_PATTERN_NODE,
-    RANGE_FROM_TO_PATTERN_NODE,
-    RANGE_FROM_TO_INCLUSIVE_PATTERN_NODE,
-    RANGE_FROM_PATTERN_NODE,
-    RANGE_TO_PATTERN_NODE,
-    RANGE_TO_INCLUSIVE_PATTERN_NODE,
-    AT_PATTERN_NODE,
-    OR_PATTERN_NODE,
-    NAMED_PATTERN_NODE,
-    UNNAMED_PATTERN_NODE,
-
-    FUNCTION_PARAMETER_NODE,
-
-    // LITERAL_NODE,
-    ARRAY_LITERAL_NODE,
+    UNARY_OPERATOR_NODE,
+    BINARY_OPERATOR_NODE,
+    ASSIGNMENT_OPERATOR_NODE,
     BOOLEAN_LITERAL_NODE,
     CHARACTER_LITERAL_NODE,
     CHARACTER_LITERAL_ONE_NODE,
     CHARACTER_LITERAL_ESCAPE_NODE,
     CHARACTER_LITERAL_UNICODE_NODE,
+    INTEGER_LITERAL_NODE,
+    FLOAT_LITERAL_NODE,
     STRING_LITERAL_NODE,
+    BYTE_LITERAL_NODE,
+    BYTE_STRING_LITERAL_NODE,
+    C_STRING_LITERAL_NODE,
     STRING_LITERAL_TEXT_NODE,
     STRING_LITERAL_ESCAPE_NODE,
     STRING_LITERAL_UNICODE_NODE,
     STRING_LITERAL_INTERPOLATION_NODE,
-    INTEGER_LITERAL_NODE,
-    FLOAT_LITERAL_NODE,
     BINARY_NUMERIC_LITERAL_NODE,
     OCTAL_NUMERIC_LITERAL_NODE,
     HEX_NUMERIC_LITERAL_NODE,
-    ESCAPE_NODE,
-    INTERPOLATION_NODE,
-    ARRAY_EXPRESSION_ELEMENT_NODE,
-    TUPLE_TYPE_ELEMENT_NODE,
-    /// `0b` | `0B` | `0o` | `0O` | `0x` | `0X`
-    NUMERIC_LITERAL_PREFIX_NODE,
-    /// `e` | `E`
-    E_NODE,
-
+    MUTABLE_TYPE_NODE,
+    PATH_TYPE_NODE,
+    SLICE_TYPE_NODE,
+    TUPLE_TYPE_NODE,
     VISIBILITY_NODE,
-    VISIBILITY_CRATE_NODE,
-    VISIBILITY_SUPER_NODE,
-    VISIBILITY_IN_NODE,
-
-    IDENTIFIER_NODE,
-    TARGET_TYPE_NODE,
-    ASSOCIATED_DEFINITION_NODE,
     PATH_NODE,
     PATH_SEGMENT_ROOT_NODE,
     PATH_SEGMENT_SELF_NODE,
     PATH_SEGMENT_SUPER_NODE,
     PATH_SEGMENT_KRATE_NODE,
     PATH_SEGMENT_IDENTIFIER_NODE,
-    ASSIGNMENT_OPERATOR_NODE,
-    BINARY_OPERATOR_NODE,
-    UNARY_OPERATOR_NODE,
-    RANGE_OPERATOR_NODE,
-    TYPE_ARGUMENT_NODE,
-    TYPE_PARAMETER_NODE,
-    TYPE_PARAMETER_CONSTRAINT_NODE,
-    TYPE_BOUND_NODE,
-    WHERE_CLAUSE_NODE,
-    ENUM_VARIANT_NODE,
-    ENUM_VARIANT_UNIT_NODE,
-    ENUM_VARIANT_SCALAR_NODE,
-    ENUM_VARIANT_NAMED_NODE,
-    ENUM_VARIANT_NAMED_FIELD_NODE,
-    ENUM_VARIANT_UNNAMED_NODE,
-    ENUM_VARIANT_UNNAMED_FIELD_NODE,
-    ENUM_VARIANT_SEQUENCE_NODE,
-    STRUCT_BODY_UNIT_NODE,
-    STRUCT_BODY_NAMED_NODE,
-    STRUCT_BODY_NAMED_FIELD_NODE,
-    STRUCT_BODY_UNNAMED_NODE,
-    STRUCT_BODY_UNNAMED_FIELD_NODE,
-    USE_TREE_NODE,
-    USE_TREE_LIST_NODE,
-    USE_TREE_GLOB_NODE,
-    USE_TREE_ELEMENT_NODE,
-    USE_TREE_RENAME_NODE,
-    NAMED_PATTERN_FIELD_NODE,
-    ELSE_BRANCH_NODE,
-    MATCH_ARM_NODE,
-    FOR_ITERATOR_NODE,
-    BINARY_EXPRESSION_RHS_NODE,
-    OR_PATTERN_RHS_NODE,
-    ARGUMENT_NODE,
-    STRUCT_EXPRESSION_FIELD_NODE,
-    INDEX_ELEMENT_NODE,
+    IDENTIFIER_NODE,
+    IDENTIFIER_SEGMENT,
 }
 
 impl SyntaxKind {
@@ -460,7 +405,13 @@ impl SyntaxKind {
 
         matches!(
             self,
-            LINE_COMMENT_START | LINE_COMMENT_SEGMENT | WHITESPACE | NEW_LINE | TAB
+            LINE_COMMENT_START
+                | LINE_COMMENT_SEGMENT
+                | DOC_COMMENT_START
+                | DOC_COMMENT_SEGMENT
+                | WHITESPACE
+                | NEW_LINE
+                | TAB
         )
     }
 
@@ -473,6 +424,10 @@ impl SyntaxKind {
             CHARACTER_START
                 | STRING_START
                 | RAW_STRING_START
+                | BYTE_START
+                | BYTE_STRING_START
+                | RAW_BYTE_STRING_START
+                | C_STRING_START
                 | INTEGER_SEGMENT
                 | BINARY_START
                 | OCTAL_START